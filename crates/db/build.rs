@@ -1,37 +1,36 @@
 use std::env;
 use std::path::Path;
 
+// The queries are compiled ahead of time into generated/cornucopia.rs, see
+// `cargo run --bin codegen`, so building doesn't need a database.
 fn main() {
-    // Compile our SQL
-    cornucopia();
-}
-
-fn cornucopia() {
-    // For the sake of simplicity, this example uses the defaults.
-    let queries_path = "queries";
+    let embedded = embed("migrations", "EMBEDDED") + &embed("seeds", "SEEDS");
 
     let out_dir = env::var_os("OUT_DIR").unwrap();
-    let file_path = Path::new(&out_dir).join("cornucopia.rs");
-
-    let db_url = env::var_os("DATABASE_URL").unwrap();
+    std::fs::write(Path::new(&out_dir).join("migrations.rs"), embedded).unwrap();
+}
 
-    // Rerun this build script if the queries or migrations change.
-    println!("cargo:rerun-if-changed={queries_path}");
+// A list of the SQL files in a directory, in the order they run, for
+// `migrations.rs` to include.
+fn embed(dir: &str, name: &str) -> String {
+    let path = Path::new(&env::var_os("CARGO_MANIFEST_DIR").unwrap()).join(dir);
+    println!("cargo:rerun-if-changed={}", path.display());
 
-    // Call cornucopia. Use whatever CLI command you need.
-    let output = std::process::Command::new("cornucopia")
-        .arg("-q")
-        .arg(queries_path)
-        .arg("--serialize")
-        .arg("-d")
-        .arg(&file_path)
-        .arg("live")
-        .arg(db_url)
-        .output()
-        .unwrap();
+    let mut files: Vec<_> = std::fs::read_dir(&path)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "sql"))
+        .collect();
+    files.sort();
 
-    // If Cornucopia couldn't run properly, try to display the error.
-    if !output.status.success() {
-        panic!("{}", &std::str::from_utf8(&output.stderr).unwrap());
+    let mut embedded = format!("static {name}: &[(&str, &str)] = &[\n");
+    for path in files {
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+        embedded.push_str(&format!(
+            "    ({file_name:?}, include_str!({:?})),\n",
+            path.display().to_string()
+        ));
     }
+    embedded.push_str("];\n");
+    embedded
 }
@@ -0,0 +1,2432 @@
+// Fingerprint of the migrations and queries: 7f8cf02fb9fd88bf
+// This file was generated with `cornucopia`. Do not modify.
+
+#[allow(clippy::all, clippy::pedantic)] #[allow(unused_variables)]
+#[allow(unused_imports)] #[allow(dead_code)] pub mod types { pub mod public { #[derive(serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)] pub enum Permission { ViewUsers,CreateUsers,ViewTeam,ManageTeam,ViewJobs,RetryJobs,ViewAuditLog,ViewFiles,UploadFiles,}impl<'a> postgres_types::ToSql for Permission
+{
+    fn
+    to_sql(&self, ty: &postgres_types::Type, buf: &mut
+    postgres_types::private::BytesMut,) -> Result<postgres_types::IsNull,
+    Box<dyn std::error::Error + Sync + Send>,>
+    {
+        let s = match *self { Permission::ViewUsers => "ViewUsers",Permission::CreateUsers => "CreateUsers",Permission::ViewTeam => "ViewTeam",Permission::ManageTeam => "ManageTeam",Permission::ViewJobs => "ViewJobs",Permission::RetryJobs => "RetryJobs",Permission::ViewAuditLog => "ViewAuditLog",Permission::ViewFiles => "ViewFiles",Permission::UploadFiles => "UploadFiles",};
+        buf.extend_from_slice(s.as_bytes());
+        std::result::Result::Ok(postgres_types::IsNull::No)
+    } fn accepts(ty: &postgres_types::Type) -> bool
+    {
+        if ty.name() != "permission" { return false; } match *ty.kind()
+        {
+            postgres_types::Kind::Enum(ref variants) =>
+            {
+                if variants.len() != 9 { return false; }
+                variants.iter().all(|v| match &**v
+                { "ViewUsers" => true,"CreateUsers" => true,"ViewTeam" => true,"ManageTeam" => true,"ViewJobs" => true,"RetryJobs" => true,"ViewAuditLog" => true,"ViewFiles" => true,"UploadFiles" => true,_ => false, })
+            } _ => false,
+        }
+    } fn
+    to_sql_checked(&self, ty: &postgres_types::Type, out: &mut
+    postgres_types::private::BytesMut,) -> Result<postgres_types::IsNull,
+    Box<dyn std::error::Error + Sync + Send>>
+    { postgres_types::__to_sql_checked(self, ty, out) }
+} impl<'a> postgres_types::FromSql<'a> for Permission
+{
+    fn from_sql(ty: &postgres_types::Type, buf: &'a [u8],) ->
+    Result<Permission, Box<dyn std::error::Error + Sync + Send>,>
+    {
+        match std::str::from_utf8(buf)?
+        {
+            "ViewUsers" => Ok(Permission::ViewUsers),"CreateUsers" => Ok(Permission::CreateUsers),"ViewTeam" => Ok(Permission::ViewTeam),"ManageTeam" => Ok(Permission::ManageTeam),"ViewJobs" => Ok(Permission::ViewJobs),"RetryJobs" => Ok(Permission::RetryJobs),"ViewAuditLog" => Ok(Permission::ViewAuditLog),"ViewFiles" => Ok(Permission::ViewFiles),"UploadFiles" => Ok(Permission::UploadFiles),s =>
+            Result::Err(Into::into(format!("invalid variant `{}`", s))),
+        }
+    } fn accepts(ty: &postgres_types::Type) -> bool
+    {
+        if ty.name() != "permission" { return false; } match *ty.kind()
+        {
+            postgres_types::Kind::Enum(ref variants) =>
+            {
+                if variants.len() != 9 { return false; }
+                variants.iter().all(|v| match &**v
+                { "ViewUsers" => true,"CreateUsers" => true,"ViewTeam" => true,"ManageTeam" => true,"ViewJobs" => true,"RetryJobs" => true,"ViewAuditLog" => true,"ViewFiles" => true,"UploadFiles" => true,_ => false, })
+            } _ => false,
+        }
+    }
+}#[derive(serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)] pub enum FileKind { Avatar,Attachment,}impl<'a> postgres_types::ToSql for FileKind
+{
+    fn
+    to_sql(&self, ty: &postgres_types::Type, buf: &mut
+    postgres_types::private::BytesMut,) -> Result<postgres_types::IsNull,
+    Box<dyn std::error::Error + Sync + Send>,>
+    {
+        let s = match *self { FileKind::Avatar => "Avatar",FileKind::Attachment => "Attachment",};
+        buf.extend_from_slice(s.as_bytes());
+        std::result::Result::Ok(postgres_types::IsNull::No)
+    } fn accepts(ty: &postgres_types::Type) -> bool
+    {
+        if ty.name() != "file_kind" { return false; } match *ty.kind()
+        {
+            postgres_types::Kind::Enum(ref variants) =>
+            {
+                if variants.len() != 2 { return false; }
+                variants.iter().all(|v| match &**v
+                { "Avatar" => true,"Attachment" => true,_ => false, })
+            } _ => false,
+        }
+    } fn
+    to_sql_checked(&self, ty: &postgres_types::Type, out: &mut
+    postgres_types::private::BytesMut,) -> Result<postgres_types::IsNull,
+    Box<dyn std::error::Error + Sync + Send>>
+    { postgres_types::__to_sql_checked(self, ty, out) }
+} impl<'a> postgres_types::FromSql<'a> for FileKind
+{
+    fn from_sql(ty: &postgres_types::Type, buf: &'a [u8],) ->
+    Result<FileKind, Box<dyn std::error::Error + Sync + Send>,>
+    {
+        match std::str::from_utf8(buf)?
+        {
+            "Avatar" => Ok(FileKind::Avatar),"Attachment" => Ok(FileKind::Attachment),s =>
+            Result::Err(Into::into(format!("invalid variant `{}`", s))),
+        }
+    } fn accepts(ty: &postgres_types::Type) -> bool
+    {
+        if ty.name() != "file_kind" { return false; } match *ty.kind()
+        {
+            postgres_types::Kind::Enum(ref variants) =>
+            {
+                if variants.len() != 2 { return false; }
+                variants.iter().all(|v| match &**v
+                { "Avatar" => true,"Attachment" => true,_ => false, })
+            } _ => false,
+        }
+    }
+}#[derive(serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)] pub enum JobStatus { Pending,Running,Completed,Dead,}impl<'a> postgres_types::ToSql for JobStatus
+{
+    fn
+    to_sql(&self, ty: &postgres_types::Type, buf: &mut
+    postgres_types::private::BytesMut,) -> Result<postgres_types::IsNull,
+    Box<dyn std::error::Error + Sync + Send>,>
+    {
+        let s = match *self { JobStatus::Pending => "Pending",JobStatus::Running => "Running",JobStatus::Completed => "Completed",JobStatus::Dead => "Dead",};
+        buf.extend_from_slice(s.as_bytes());
+        std::result::Result::Ok(postgres_types::IsNull::No)
+    } fn accepts(ty: &postgres_types::Type) -> bool
+    {
+        if ty.name() != "job_status" { return false; } match *ty.kind()
+        {
+            postgres_types::Kind::Enum(ref variants) =>
+            {
+                if variants.len() != 4 { return false; }
+                variants.iter().all(|v| match &**v
+                { "Pending" => true,"Running" => true,"Completed" => true,"Dead" => true,_ => false, })
+            } _ => false,
+        }
+    } fn
+    to_sql_checked(&self, ty: &postgres_types::Type, out: &mut
+    postgres_types::private::BytesMut,) -> Result<postgres_types::IsNull,
+    Box<dyn std::error::Error + Sync + Send>>
+    { postgres_types::__to_sql_checked(self, ty, out) }
+} impl<'a> postgres_types::FromSql<'a> for JobStatus
+{
+    fn from_sql(ty: &postgres_types::Type, buf: &'a [u8],) ->
+    Result<JobStatus, Box<dyn std::error::Error + Sync + Send>,>
+    {
+        match std::str::from_utf8(buf)?
+        {
+            "Pending" => Ok(JobStatus::Pending),"Running" => Ok(JobStatus::Running),"Completed" => Ok(JobStatus::Completed),"Dead" => Ok(JobStatus::Dead),s =>
+            Result::Err(Into::into(format!("invalid variant `{}`", s))),
+        }
+    } fn accepts(ty: &postgres_types::Type) -> bool
+    {
+        if ty.name() != "job_status" { return false; } match *ty.kind()
+        {
+            postgres_types::Kind::Enum(ref variants) =>
+            {
+                if variants.len() != 4 { return false; }
+                variants.iter().all(|v| match &**v
+                { "Pending" => true,"Running" => true,"Completed" => true,"Dead" => true,_ => false, })
+            } _ => false,
+        }
+    }
+}#[derive(serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)] pub enum TeamRole { Administrator,Member,ReadOnly,}impl<'a> postgres_types::ToSql for TeamRole
+{
+    fn
+    to_sql(&self, ty: &postgres_types::Type, buf: &mut
+    postgres_types::private::BytesMut,) -> Result<postgres_types::IsNull,
+    Box<dyn std::error::Error + Sync + Send>,>
+    {
+        let s = match *self { TeamRole::Administrator => "Administrator",TeamRole::Member => "Member",TeamRole::ReadOnly => "ReadOnly",};
+        buf.extend_from_slice(s.as_bytes());
+        std::result::Result::Ok(postgres_types::IsNull::No)
+    } fn accepts(ty: &postgres_types::Type) -> bool
+    {
+        if ty.name() != "team_role" { return false; } match *ty.kind()
+        {
+            postgres_types::Kind::Enum(ref variants) =>
+            {
+                if variants.len() != 3 { return false; }
+                variants.iter().all(|v| match &**v
+                { "Administrator" => true,"Member" => true,"ReadOnly" => true,_ => false, })
+            } _ => false,
+        }
+    } fn
+    to_sql_checked(&self, ty: &postgres_types::Type, out: &mut
+    postgres_types::private::BytesMut,) -> Result<postgres_types::IsNull,
+    Box<dyn std::error::Error + Sync + Send>>
+    { postgres_types::__to_sql_checked(self, ty, out) }
+} impl<'a> postgres_types::FromSql<'a> for TeamRole
+{
+    fn from_sql(ty: &postgres_types::Type, buf: &'a [u8],) ->
+    Result<TeamRole, Box<dyn std::error::Error + Sync + Send>,>
+    {
+        match std::str::from_utf8(buf)?
+        {
+            "Administrator" => Ok(TeamRole::Administrator),"Member" => Ok(TeamRole::Member),"ReadOnly" => Ok(TeamRole::ReadOnly),s =>
+            Result::Err(Into::into(format!("invalid variant `{}`", s))),
+        }
+    } fn accepts(ty: &postgres_types::Type) -> bool
+    {
+        if ty.name() != "team_role" { return false; } match *ty.kind()
+        {
+            postgres_types::Kind::Enum(ref variants) =>
+            {
+                if variants.len() != 3 { return false; }
+                variants.iter().all(|v| match &**v
+                { "Administrator" => true,"Member" => true,"ReadOnly" => true,_ => false, })
+            } _ => false,
+        }
+    }
+}#[derive(serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)] pub enum Theme { System,Light,Dark,}impl<'a> postgres_types::ToSql for Theme
+{
+    fn
+    to_sql(&self, ty: &postgres_types::Type, buf: &mut
+    postgres_types::private::BytesMut,) -> Result<postgres_types::IsNull,
+    Box<dyn std::error::Error + Sync + Send>,>
+    {
+        let s = match *self { Theme::System => "System",Theme::Light => "Light",Theme::Dark => "Dark",};
+        buf.extend_from_slice(s.as_bytes());
+        std::result::Result::Ok(postgres_types::IsNull::No)
+    } fn accepts(ty: &postgres_types::Type) -> bool
+    {
+        if ty.name() != "theme" { return false; } match *ty.kind()
+        {
+            postgres_types::Kind::Enum(ref variants) =>
+            {
+                if variants.len() != 3 { return false; }
+                variants.iter().all(|v| match &**v
+                { "System" => true,"Light" => true,"Dark" => true,_ => false, })
+            } _ => false,
+        }
+    } fn
+    to_sql_checked(&self, ty: &postgres_types::Type, out: &mut
+    postgres_types::private::BytesMut,) -> Result<postgres_types::IsNull,
+    Box<dyn std::error::Error + Sync + Send>>
+    { postgres_types::__to_sql_checked(self, ty, out) }
+} impl<'a> postgres_types::FromSql<'a> for Theme
+{
+    fn from_sql(ty: &postgres_types::Type, buf: &'a [u8],) ->
+    Result<Theme, Box<dyn std::error::Error + Sync + Send>,>
+    {
+        match std::str::from_utf8(buf)?
+        {
+            "System" => Ok(Theme::System),"Light" => Ok(Theme::Light),"Dark" => Ok(Theme::Dark),s =>
+            Result::Err(Into::into(format!("invalid variant `{}`", s))),
+        }
+    } fn accepts(ty: &postgres_types::Type) -> bool
+    {
+        if ty.name() != "theme" { return false; } match *ty.kind()
+        {
+            postgres_types::Kind::Enum(ref variants) =>
+            {
+                if variants.len() != 3 { return false; }
+                variants.iter().all(|v| match &**v
+                { "System" => true,"Light" => true,"Dark" => true,_ => false, })
+            } _ => false,
+        }
+    }
+} }}#[allow(clippy::all, clippy::pedantic)] #[allow(unused_variables)]
+#[allow(unused_imports)] #[allow(dead_code)] pub mod queries
+{ pub mod api_keys
+{ use futures::{{StreamExt, TryStreamExt}};use futures; use cornucopia_async::GenericClient;#[derive( Debug)] pub struct CreateApiKeyParams<T1: cornucopia_async::StringSql,T2: cornucopia_async::StringSql,T3: cornucopia_async::BytesSql,T4: cornucopia_async::ArraySql<Item = super::super::types::public::Permission>,> { pub team_id: i32,pub user_id: i32,pub name: T1,pub prefix: T2,pub key_hash: T3,pub scopes: T4,}#[derive(Clone,Copy, Debug)] pub struct GetApiKeysParams<> { pub team_id: i32,pub user_id: i32,}#[derive(Clone,Copy, Debug)] pub struct RevokeApiKeyParams<> { pub id: i32,pub team_id: i32,pub user_id: i32,}pub struct I32Query<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> i32,
+    mapper: fn(i32) -> T,
+} impl<'a, C, T:'a, const N: usize> I32Query<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(i32) -> R) ->
+    I32Query<'a,C,R,N>
+    {
+        I32Query
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}#[derive(serde::Serialize, Debug, Clone, PartialEq,)] pub struct ApiKey
+{ pub id : i32,pub name : String,pub prefix : String,pub scopes : Vec<super::super::types::public::Permission>,pub created_at : time::OffsetDateTime,pub last_used_at : Option<time::OffsetDateTime>,}pub struct ApiKeyBorrowed<'a> { pub id : i32,pub name : &'a str,pub prefix : &'a str,pub scopes : cornucopia_async::ArrayIterator<'a, super::super::types::public::Permission>,pub created_at : time::OffsetDateTime,pub last_used_at : Option<time::OffsetDateTime>,}
+impl<'a> From<ApiKeyBorrowed<'a>> for ApiKey
+{
+    fn from(ApiKeyBorrowed { id,name,prefix,scopes,created_at,last_used_at,}: ApiKeyBorrowed<'a>) -> Self
+    { Self { id,name: name.into(),prefix: prefix.into(),scopes: scopes.map(|v| v).collect(),created_at,last_used_at,} }
+}pub struct ApiKeyQuery<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> ApiKeyBorrowed,
+    mapper: fn(ApiKeyBorrowed) -> T,
+} impl<'a, C, T:'a, const N: usize> ApiKeyQuery<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(ApiKeyBorrowed) -> R) ->
+    ApiKeyQuery<'a,C,R,N>
+    {
+        ApiKeyQuery
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}#[derive(serde::Serialize, Debug, Clone, PartialEq,)] pub struct ApiKeyOwner
+{ pub id : i32,pub team_id : i32,pub user_id : i32,pub permissions : Vec<super::super::types::public::Permission>,}pub struct ApiKeyOwnerBorrowed<'a> { pub id : i32,pub team_id : i32,pub user_id : i32,pub permissions : cornucopia_async::ArrayIterator<'a, super::super::types::public::Permission>,}
+impl<'a> From<ApiKeyOwnerBorrowed<'a>> for ApiKeyOwner
+{
+    fn from(ApiKeyOwnerBorrowed { id,team_id,user_id,permissions,}: ApiKeyOwnerBorrowed<'a>) -> Self
+    { Self { id,team_id,user_id,permissions: permissions.map(|v| v).collect(),} }
+}pub struct ApiKeyOwnerQuery<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> ApiKeyOwnerBorrowed,
+    mapper: fn(ApiKeyOwnerBorrowed) -> T,
+} impl<'a, C, T:'a, const N: usize> ApiKeyOwnerQuery<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(ApiKeyOwnerBorrowed) -> R) ->
+    ApiKeyOwnerQuery<'a,C,R,N>
+    {
+        ApiKeyOwnerQuery
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}pub fn create_api_key() -> CreateApiKeyStmt
+{ CreateApiKeyStmt(cornucopia_async::private::Stmt::new("INSERT INTO api_keys (team_id, user_id, name, prefix, key_hash, scopes)
+VALUES ($1, $2, $3, $4, $5, $6)
+RETURNING id")) } pub struct
+CreateApiKeyStmt(cornucopia_async::private::Stmt); impl CreateApiKeyStmt
+{ pub fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::StringSql,T2:
+cornucopia_async::StringSql,T3:
+cornucopia_async::BytesSql,T4:
+cornucopia_async::ArraySql<Item = super::super::types::public::Permission>,>(&'a mut self, client: &'a  C,
+team_id: &'a i32,user_id: &'a i32,name: &'a T1,prefix: &'a T2,key_hash: &'a T3,scopes: &'a T4,) -> I32Query<'a,C, i32,
+6>
+{
+    I32Query
+    {
+        client, params: [team_id,user_id,name,prefix,key_hash,scopes,], stmt: &mut self.0, extractor:
+        |row| { row.get(0) }, mapper: |it| { it },
+    }
+} }impl <'a, C: GenericClient,T1: cornucopia_async::StringSql,T2: cornucopia_async::StringSql,T3: cornucopia_async::BytesSql,T4: cornucopia_async::ArraySql<Item = super::super::types::public::Permission>,> cornucopia_async::Params<'a,
+CreateApiKeyParams<T1,T2,T3,T4,>, I32Query<'a, C, i32,
+6>, C> for CreateApiKeyStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    CreateApiKeyParams<T1,T2,T3,T4,>) -> I32Query<'a, C,
+    i32, 6>
+    { self.bind(client, &params.team_id,&params.user_id,&params.name,&params.prefix,&params.key_hash,&params.scopes,) }
+}pub fn get_api_keys() -> GetApiKeysStmt
+{ GetApiKeysStmt(cornucopia_async::private::Stmt::new("SELECT
+    id,
+    name,
+    prefix,
+    scopes,
+    created_at,
+    last_used_at
+FROM api_keys
+WHERE team_id = $1 AND user_id = $2 AND revoked_at IS NULL
+ORDER BY created_at DESC")) } pub struct
+GetApiKeysStmt(cornucopia_async::private::Stmt); impl GetApiKeysStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+team_id: &'a i32,user_id: &'a i32,) -> ApiKeyQuery<'a,C, ApiKey,
+2>
+{
+    ApiKeyQuery
+    {
+        client, params: [team_id,user_id,], stmt: &mut self.0, extractor:
+        |row| { ApiKeyBorrowed { id: row.get(0),name: row.get(1),prefix: row.get(2),scopes: row.get(3),created_at: row.get(4),last_used_at: row.get(5),} }, mapper: |it| { <ApiKey>::from(it) },
+    }
+} }impl <'a, C: GenericClient,> cornucopia_async::Params<'a,
+GetApiKeysParams<>, ApiKeyQuery<'a, C, ApiKey,
+2>, C> for GetApiKeysStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    GetApiKeysParams<>) -> ApiKeyQuery<'a, C,
+    ApiKey, 2>
+    { self.bind(client, &params.team_id,&params.user_id,) }
+}pub fn revoke_api_key() -> RevokeApiKeyStmt
+{ RevokeApiKeyStmt(cornucopia_async::private::Stmt::new("UPDATE api_keys
+SET revoked_at = NOW()
+WHERE id = $1 AND team_id = $2 AND user_id = $3 AND revoked_at IS NULL
+RETURNING id")) } pub struct
+RevokeApiKeyStmt(cornucopia_async::private::Stmt); impl RevokeApiKeyStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+id: &'a i32,team_id: &'a i32,user_id: &'a i32,) -> I32Query<'a,C, i32,
+3>
+{
+    I32Query
+    {
+        client, params: [id,team_id,user_id,], stmt: &mut self.0, extractor:
+        |row| { row.get(0) }, mapper: |it| { it },
+    }
+} }impl <'a, C: GenericClient,> cornucopia_async::Params<'a,
+RevokeApiKeyParams<>, I32Query<'a, C, i32,
+3>, C> for RevokeApiKeyStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    RevokeApiKeyParams<>) -> I32Query<'a, C,
+    i32, 3>
+    { self.bind(client, &params.id,&params.team_id,&params.user_id,) }
+}pub fn get_api_key_owner() -> GetApiKeyOwnerStmt
+{ GetApiKeyOwnerStmt(cornucopia_async::private::Stmt::new("SELECT
+    k.id,
+    k.team_id,
+    k.user_id,
+    ARRAY(
+        SELECT scope
+        FROM unnest(k.scopes) AS scope
+        JOIN role_permissions rp ON rp.permission = scope AND rp.role = m.role
+        ORDER BY scope
+    ) AS permissions
+FROM api_keys k
+JOIN team_memberships m ON m.team_id = k.team_id AND m.user_id = k.user_id
+WHERE k.key_hash = $1 AND k.revoked_at IS NULL")) } pub struct
+GetApiKeyOwnerStmt(cornucopia_async::private::Stmt); impl GetApiKeyOwnerStmt
+{ pub fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::BytesSql,>(&'a mut self, client: &'a  C,
+key_hash: &'a T1,) -> ApiKeyOwnerQuery<'a,C, ApiKeyOwner,
+1>
+{
+    ApiKeyOwnerQuery
+    {
+        client, params: [key_hash,], stmt: &mut self.0, extractor:
+        |row| { ApiKeyOwnerBorrowed { id: row.get(0),team_id: row.get(1),user_id: row.get(2),permissions: row.get(3),} }, mapper: |it| { <ApiKeyOwner>::from(it) },
+    }
+} }pub fn touch_api_key() -> TouchApiKeyStmt
+{ TouchApiKeyStmt(cornucopia_async::private::Stmt::new("UPDATE api_keys
+SET last_used_at = NOW()
+WHERE id = $1")) } pub struct
+TouchApiKeyStmt(cornucopia_async::private::Stmt); impl TouchApiKeyStmt
+{ pub async fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+id: &'a i32,) -> Result<u64, tokio_postgres::Error>
+{
+    let stmt = self.0.prepare(client).await?;
+    client.execute(stmt, &[id,]).await
+} }}pub mod audit_events
+{ use futures::{{StreamExt, TryStreamExt}};use futures; use cornucopia_async::GenericClient;#[derive( Debug)] pub struct InsertAuditEventParams<T1: cornucopia_async::StringSql,T2: cornucopia_async::StringSql,T3: cornucopia_async::JsonSql,T4: cornucopia_async::StringSql,> { pub team_id: Option<i32>,pub actor_id: Option<i32>,pub action: T1,pub target_type: T2,pub target_id: Option<i32>,pub diff: T3,pub ip_address: Option<std::net::IpAddr>,pub user_agent: Option<T4>,}#[derive( Debug)] pub struct GetAuditEventsParams<T1: cornucopia_async::StringSql,T2: cornucopia_async::StringSql,T3: cornucopia_async::StringSql,> { pub team_id: i32,pub actor: Option<T1>,pub target_type: Option<T2>,pub target_id: Option<i32>,pub from: Option<time::Date>,pub timezone: T3,pub to: Option<time::Date>,}#[derive(serde::Serialize, Debug, Clone, PartialEq,)] pub struct AuditLogEntry
+{ pub id : i32,pub actor_email : Option<String>,pub action : String,pub target_type : String,pub target_id : Option<i32>,pub diff : serde_json::Value,pub ip_address : Option<std::net::IpAddr>,pub user_agent : Option<String>,pub created_at : time::OffsetDateTime,}pub struct AuditLogEntryBorrowed<'a> { pub id : i32,pub actor_email : Option<&'a str>,pub action : &'a str,pub target_type : &'a str,pub target_id : Option<i32>,pub diff : postgres_types::Json<&'a serde_json::value::RawValue>,pub ip_address : Option<std::net::IpAddr>,pub user_agent : Option<&'a str>,pub created_at : time::OffsetDateTime,}
+impl<'a> From<AuditLogEntryBorrowed<'a>> for AuditLogEntry
+{
+    fn from(AuditLogEntryBorrowed { id,actor_email,action,target_type,target_id,diff,ip_address,user_agent,created_at,}: AuditLogEntryBorrowed<'a>) -> Self
+    { Self { id,actor_email: actor_email.map(|v| v.into()),action: action.into(),target_type: target_type.into(),target_id,diff: serde_json::from_str(diff.0.get()).unwrap(),ip_address,user_agent: user_agent.map(|v| v.into()),created_at,} }
+}pub struct AuditLogEntryQuery<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> AuditLogEntryBorrowed,
+    mapper: fn(AuditLogEntryBorrowed) -> T,
+} impl<'a, C, T:'a, const N: usize> AuditLogEntryQuery<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(AuditLogEntryBorrowed) -> R) ->
+    AuditLogEntryQuery<'a,C,R,N>
+    {
+        AuditLogEntryQuery
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}pub struct StringQuery<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> & str,
+    mapper: fn(& str) -> T,
+} impl<'a, C, T:'a, const N: usize> StringQuery<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(& str) -> R) ->
+    StringQuery<'a,C,R,N>
+    {
+        StringQuery
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}pub fn insert_audit_event() -> InsertAuditEventStmt
+{ InsertAuditEventStmt(cornucopia_async::private::Stmt::new("INSERT INTO audit_events (
+    team_id,
+    actor_id,
+    action,
+    target_type,
+    target_id,
+    diff,
+    ip_address,
+    user_agent
+)
+VALUES (
+    $1,
+    $2,
+    $3,
+    $4,
+    $5,
+    $6,
+    $7,
+    $8
+)")) } pub struct
+InsertAuditEventStmt(cornucopia_async::private::Stmt); impl InsertAuditEventStmt
+{ pub async fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::StringSql,T2:
+cornucopia_async::StringSql,T3:
+cornucopia_async::JsonSql,T4:
+cornucopia_async::StringSql,>(&'a mut self, client: &'a  C,
+team_id: &'a Option<i32>,actor_id: &'a Option<i32>,action: &'a T1,target_type: &'a T2,target_id: &'a Option<i32>,diff: &'a T3,ip_address: &'a Option<std::net::IpAddr>,user_agent: &'a Option<T4>,) -> Result<u64, tokio_postgres::Error>
+{
+    let stmt = self.0.prepare(client).await?;
+    client.execute(stmt, &[team_id,actor_id,action,target_type,target_id,diff,ip_address,user_agent,]).await
+} }impl <'a, C: GenericClient + Send + Sync, T1: cornucopia_async::StringSql,T2: cornucopia_async::StringSql,T3: cornucopia_async::JsonSql,T4: cornucopia_async::StringSql,>
+cornucopia_async::Params<'a, InsertAuditEventParams<T1,T2,T3,T4,>, std::pin::Pin<Box<dyn futures::Future<Output = Result<u64,
+tokio_postgres::Error>> + Send + 'a>>, C> for InsertAuditEventStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    InsertAuditEventParams<T1,T2,T3,T4,>) -> std::pin::Pin<Box<dyn futures::Future<Output = Result<u64,
+    tokio_postgres::Error>> + Send + 'a>>
+    { Box::pin(self.bind(client, &params.team_id,&params.actor_id,&params.action,&params.target_type,&params.target_id,&params.diff,&params.ip_address,&params.user_agent,)) }
+}pub fn get_audit_events() -> GetAuditEventsStmt
+{ GetAuditEventsStmt(cornucopia_async::private::Stmt::new("SELECT
+    a.id,
+    u.email AS actor_email,
+    a.action,
+    a.target_type,
+    a.target_id,
+    a.diff,
+    a.ip_address,
+    a.user_agent,
+    a.created_at
+FROM audit_events a
+LEFT JOIN users u ON u.id = a.actor_id
+WHERE
+    a.team_id = $1
+    AND ($2::VARCHAR IS NULL OR u.email ILIKE '%' || $2 || '%')
+    AND ($3::VARCHAR IS NULL OR a.target_type = $3)
+    AND ($4::INT IS NULL OR a.target_id = $4)
+    AND ($5::DATE IS NULL OR a.created_at >= ($5::DATE)::TIMESTAMP AT TIME ZONE $6)
+    AND ($7::DATE IS NULL OR a.created_at < ($7::DATE + 1)::TIMESTAMP AT TIME ZONE $6)
+ORDER BY a.created_at DESC, a.id DESC
+LIMIT 200")) } pub struct
+GetAuditEventsStmt(cornucopia_async::private::Stmt); impl GetAuditEventsStmt
+{ pub fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::StringSql,T2:
+cornucopia_async::StringSql,T3:
+cornucopia_async::StringSql,>(&'a mut self, client: &'a  C,
+team_id: &'a i32,actor: &'a Option<T1>,target_type: &'a Option<T2>,target_id: &'a Option<i32>,from: &'a Option<time::Date>,timezone: &'a T3,to: &'a Option<time::Date>,) -> AuditLogEntryQuery<'a,C, AuditLogEntry,
+7>
+{
+    AuditLogEntryQuery
+    {
+        client, params: [team_id,actor,target_type,target_id,from,timezone,to,], stmt: &mut self.0, extractor:
+        |row| { AuditLogEntryBorrowed { id: row.get(0),actor_email: row.get(1),action: row.get(2),target_type: row.get(3),target_id: row.get(4),diff: row.get(5),ip_address: row.get(6),user_agent: row.get(7),created_at: row.get(8),} }, mapper: |it| { <AuditLogEntry>::from(it) },
+    }
+} }impl <'a, C: GenericClient,T1: cornucopia_async::StringSql,T2: cornucopia_async::StringSql,T3: cornucopia_async::StringSql,> cornucopia_async::Params<'a,
+GetAuditEventsParams<T1,T2,T3,>, AuditLogEntryQuery<'a, C, AuditLogEntry,
+7>, C> for GetAuditEventsStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    GetAuditEventsParams<T1,T2,T3,>) -> AuditLogEntryQuery<'a, C,
+    AuditLogEntry, 7>
+    { self.bind(client, &params.team_id,&params.actor,&params.target_type,&params.target_id,&params.from,&params.timezone,&params.to,) }
+}pub fn get_audit_target_types() -> GetAuditTargetTypesStmt
+{ GetAuditTargetTypesStmt(cornucopia_async::private::Stmt::new("SELECT DISTINCT target_type FROM audit_events WHERE team_id = $1 ORDER BY target_type")) } pub struct
+GetAuditTargetTypesStmt(cornucopia_async::private::Stmt); impl GetAuditTargetTypesStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+team_id: &'a i32,) -> StringQuery<'a,C, String,
+1>
+{
+    StringQuery
+    {
+        client, params: [team_id,], stmt: &mut self.0, extractor:
+        |row| { row.get(0) }, mapper: |it| { it.into() },
+    }
+} }}pub mod feature_flags
+{ use futures::{{StreamExt, TryStreamExt}};use futures; use cornucopia_async::GenericClient;#[derive( Debug)] pub struct CreateFeatureFlagParams<T1: cornucopia_async::StringSql,T2: cornucopia_async::StringSql,> { pub name: T1,pub description: T2,}#[derive( Debug)] pub struct UpdateFeatureFlagParams<T1: cornucopia_async::ArraySql<Item = i32>,T2: cornucopia_async::ArraySql<Item = i32>,> { pub enabled: bool,pub rollout_percentage: i32,pub user_ids: T1,pub team_ids: T2,pub id: i32,}#[derive(serde::Serialize, Debug, Clone, PartialEq,)] pub struct FeatureFlag
+{ pub id : i32,pub name : String,pub description : String,pub enabled : bool,pub rollout_percentage : i32,pub user_ids : Vec<i32>,pub team_ids : Vec<i32>,pub updated_at : time::OffsetDateTime,}pub struct FeatureFlagBorrowed<'a> { pub id : i32,pub name : &'a str,pub description : &'a str,pub enabled : bool,pub rollout_percentage : i32,pub user_ids : cornucopia_async::ArrayIterator<'a, i32>,pub team_ids : cornucopia_async::ArrayIterator<'a, i32>,pub updated_at : time::OffsetDateTime,}
+impl<'a> From<FeatureFlagBorrowed<'a>> for FeatureFlag
+{
+    fn from(FeatureFlagBorrowed { id,name,description,enabled,rollout_percentage,user_ids,team_ids,updated_at,}: FeatureFlagBorrowed<'a>) -> Self
+    { Self { id,name: name.into(),description: description.into(),enabled,rollout_percentage,user_ids: user_ids.map(|v| v).collect(),team_ids: team_ids.map(|v| v).collect(),updated_at,} }
+}pub struct FeatureFlagQuery<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> FeatureFlagBorrowed,
+    mapper: fn(FeatureFlagBorrowed) -> T,
+} impl<'a, C, T:'a, const N: usize> FeatureFlagQuery<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(FeatureFlagBorrowed) -> R) ->
+    FeatureFlagQuery<'a,C,R,N>
+    {
+        FeatureFlagQuery
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}pub fn get_feature_flags() -> GetFeatureFlagsStmt
+{ GetFeatureFlagsStmt(cornucopia_async::private::Stmt::new("SELECT
+    id,
+    name,
+    description,
+    enabled,
+    rollout_percentage,
+    user_ids,
+    team_ids,
+    updated_at
+FROM feature_flags
+ORDER BY name")) } pub struct
+GetFeatureFlagsStmt(cornucopia_async::private::Stmt); impl GetFeatureFlagsStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+) -> FeatureFlagQuery<'a,C, FeatureFlag,
+0>
+{
+    FeatureFlagQuery
+    {
+        client, params: [], stmt: &mut self.0, extractor:
+        |row| { FeatureFlagBorrowed { id: row.get(0),name: row.get(1),description: row.get(2),enabled: row.get(3),rollout_percentage: row.get(4),user_ids: row.get(5),team_ids: row.get(6),updated_at: row.get(7),} }, mapper: |it| { <FeatureFlag>::from(it) },
+    }
+} }pub fn get_feature_flag() -> GetFeatureFlagStmt
+{ GetFeatureFlagStmt(cornucopia_async::private::Stmt::new("SELECT
+    id,
+    name,
+    description,
+    enabled,
+    rollout_percentage,
+    user_ids,
+    team_ids,
+    updated_at
+FROM feature_flags
+WHERE id = $1")) } pub struct
+GetFeatureFlagStmt(cornucopia_async::private::Stmt); impl GetFeatureFlagStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+id: &'a i32,) -> FeatureFlagQuery<'a,C, FeatureFlag,
+1>
+{
+    FeatureFlagQuery
+    {
+        client, params: [id,], stmt: &mut self.0, extractor:
+        |row| { FeatureFlagBorrowed { id: row.get(0),name: row.get(1),description: row.get(2),enabled: row.get(3),rollout_percentage: row.get(4),user_ids: row.get(5),team_ids: row.get(6),updated_at: row.get(7),} }, mapper: |it| { <FeatureFlag>::from(it) },
+    }
+} }pub fn create_feature_flag() -> CreateFeatureFlagStmt
+{ CreateFeatureFlagStmt(cornucopia_async::private::Stmt::new("INSERT INTO feature_flags (name, description)
+VALUES ($1, $2)
+ON CONFLICT (name) DO NOTHING
+RETURNING
+    id,
+    name,
+    description,
+    enabled,
+    rollout_percentage,
+    user_ids,
+    team_ids,
+    updated_at")) } pub struct
+CreateFeatureFlagStmt(cornucopia_async::private::Stmt); impl CreateFeatureFlagStmt
+{ pub fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::StringSql,T2:
+cornucopia_async::StringSql,>(&'a mut self, client: &'a  C,
+name: &'a T1,description: &'a T2,) -> FeatureFlagQuery<'a,C, FeatureFlag,
+2>
+{
+    FeatureFlagQuery
+    {
+        client, params: [name,description,], stmt: &mut self.0, extractor:
+        |row| { FeatureFlagBorrowed { id: row.get(0),name: row.get(1),description: row.get(2),enabled: row.get(3),rollout_percentage: row.get(4),user_ids: row.get(5),team_ids: row.get(6),updated_at: row.get(7),} }, mapper: |it| { <FeatureFlag>::from(it) },
+    }
+} }impl <'a, C: GenericClient,T1: cornucopia_async::StringSql,T2: cornucopia_async::StringSql,> cornucopia_async::Params<'a,
+CreateFeatureFlagParams<T1,T2,>, FeatureFlagQuery<'a, C, FeatureFlag,
+2>, C> for CreateFeatureFlagStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    CreateFeatureFlagParams<T1,T2,>) -> FeatureFlagQuery<'a, C,
+    FeatureFlag, 2>
+    { self.bind(client, &params.name,&params.description,) }
+}pub fn update_feature_flag() -> UpdateFeatureFlagStmt
+{ UpdateFeatureFlagStmt(cornucopia_async::private::Stmt::new("UPDATE feature_flags
+SET
+    enabled = $1,
+    rollout_percentage = $2,
+    user_ids = $3,
+    team_ids = $4,
+    updated_at = NOW()
+WHERE id = $5
+RETURNING
+    id,
+    name,
+    description,
+    enabled,
+    rollout_percentage,
+    user_ids,
+    team_ids,
+    updated_at")) } pub struct
+UpdateFeatureFlagStmt(cornucopia_async::private::Stmt); impl UpdateFeatureFlagStmt
+{ pub fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::ArraySql<Item = i32>,T2:
+cornucopia_async::ArraySql<Item = i32>,>(&'a mut self, client: &'a  C,
+enabled: &'a bool,rollout_percentage: &'a i32,user_ids: &'a T1,team_ids: &'a T2,id: &'a i32,) -> FeatureFlagQuery<'a,C, FeatureFlag,
+5>
+{
+    FeatureFlagQuery
+    {
+        client, params: [enabled,rollout_percentage,user_ids,team_ids,id,], stmt: &mut self.0, extractor:
+        |row| { FeatureFlagBorrowed { id: row.get(0),name: row.get(1),description: row.get(2),enabled: row.get(3),rollout_percentage: row.get(4),user_ids: row.get(5),team_ids: row.get(6),updated_at: row.get(7),} }, mapper: |it| { <FeatureFlag>::from(it) },
+    }
+} }impl <'a, C: GenericClient,T1: cornucopia_async::ArraySql<Item = i32>,T2: cornucopia_async::ArraySql<Item = i32>,> cornucopia_async::Params<'a,
+UpdateFeatureFlagParams<T1,T2,>, FeatureFlagQuery<'a, C, FeatureFlag,
+5>, C> for UpdateFeatureFlagStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    UpdateFeatureFlagParams<T1,T2,>) -> FeatureFlagQuery<'a, C,
+    FeatureFlag, 5>
+    { self.bind(client, &params.enabled,&params.rollout_percentage,&params.user_ids,&params.team_ids,&params.id,) }
+}}pub mod files
+{ use futures::{{StreamExt, TryStreamExt}};use futures; use cornucopia_async::GenericClient;#[derive( Debug)] pub struct CreateFileParams<T1: cornucopia_async::StringSql,T2: cornucopia_async::StringSql,T3: cornucopia_async::StringSql,T4: cornucopia_async::BytesSql,> { pub team_id: i32,pub user_id: Option<i32>,pub kind: super::super::types::public::FileKind,pub storage_key: T1,pub file_name: T2,pub content_type: T3,pub size_bytes: i64,pub sha256: T4,}pub struct I32Query<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> i32,
+    mapper: fn(i32) -> T,
+} impl<'a, C, T:'a, const N: usize> I32Query<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(i32) -> R) ->
+    I32Query<'a,C,R,N>
+    {
+        I32Query
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}#[derive(serde::Serialize, Debug, Clone, PartialEq,)] pub struct File
+{ pub id : i32,pub file_name : String,pub content_type : String,pub size_bytes : i64,pub uploaded_by : Option<String>,pub created_at : time::OffsetDateTime,}pub struct FileBorrowed<'a> { pub id : i32,pub file_name : &'a str,pub content_type : &'a str,pub size_bytes : i64,pub uploaded_by : Option<&'a str>,pub created_at : time::OffsetDateTime,}
+impl<'a> From<FileBorrowed<'a>> for File
+{
+    fn from(FileBorrowed { id,file_name,content_type,size_bytes,uploaded_by,created_at,}: FileBorrowed<'a>) -> Self
+    { Self { id,file_name: file_name.into(),content_type: content_type.into(),size_bytes,uploaded_by: uploaded_by.map(|v| v.into()),created_at,} }
+}pub struct FileQuery<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> FileBorrowed,
+    mapper: fn(FileBorrowed) -> T,
+} impl<'a, C, T:'a, const N: usize> FileQuery<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(FileBorrowed) -> R) ->
+    FileQuery<'a,C,R,N>
+    {
+        FileQuery
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}#[derive(serde::Serialize, Debug, Clone, PartialEq,)] pub struct GetFileDownload
+{ pub storage_key : String,pub file_name : String,pub content_type : String,}pub struct GetFileDownloadBorrowed<'a> { pub storage_key : &'a str,pub file_name : &'a str,pub content_type : &'a str,}
+impl<'a> From<GetFileDownloadBorrowed<'a>> for GetFileDownload
+{
+    fn from(GetFileDownloadBorrowed { storage_key,file_name,content_type,}: GetFileDownloadBorrowed<'a>) -> Self
+    { Self { storage_key: storage_key.into(),file_name: file_name.into(),content_type: content_type.into(),} }
+}pub struct GetFileDownloadQuery<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> GetFileDownloadBorrowed,
+    mapper: fn(GetFileDownloadBorrowed) -> T,
+} impl<'a, C, T:'a, const N: usize> GetFileDownloadQuery<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(GetFileDownloadBorrowed) -> R) ->
+    GetFileDownloadQuery<'a,C,R,N>
+    {
+        GetFileDownloadQuery
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}pub fn create_file() -> CreateFileStmt
+{ CreateFileStmt(cornucopia_async::private::Stmt::new("INSERT INTO files (
+    team_id,
+    user_id,
+    kind,
+    storage_key,
+    file_name,
+    content_type,
+    size_bytes,
+    sha256
+)
+VALUES (
+    $1,
+    $2,
+    $3,
+    $4,
+    $5,
+    $6,
+    $7,
+    $8
+)
+RETURNING id")) } pub struct
+CreateFileStmt(cornucopia_async::private::Stmt); impl CreateFileStmt
+{ pub fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::StringSql,T2:
+cornucopia_async::StringSql,T3:
+cornucopia_async::StringSql,T4:
+cornucopia_async::BytesSql,>(&'a mut self, client: &'a  C,
+team_id: &'a i32,user_id: &'a Option<i32>,kind: &'a super::super::types::public::FileKind,storage_key: &'a T1,file_name: &'a T2,content_type: &'a T3,size_bytes: &'a i64,sha256: &'a T4,) -> I32Query<'a,C, i32,
+8>
+{
+    I32Query
+    {
+        client, params: [team_id,user_id,kind,storage_key,file_name,content_type,size_bytes,sha256,], stmt: &mut self.0, extractor:
+        |row| { row.get(0) }, mapper: |it| { it },
+    }
+} }impl <'a, C: GenericClient,T1: cornucopia_async::StringSql,T2: cornucopia_async::StringSql,T3: cornucopia_async::StringSql,T4: cornucopia_async::BytesSql,> cornucopia_async::Params<'a,
+CreateFileParams<T1,T2,T3,T4,>, I32Query<'a, C, i32,
+8>, C> for CreateFileStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    CreateFileParams<T1,T2,T3,T4,>) -> I32Query<'a, C,
+    i32, 8>
+    { self.bind(client, &params.team_id,&params.user_id,&params.kind,&params.storage_key,&params.file_name,&params.content_type,&params.size_bytes,&params.sha256,) }
+}pub fn get_files() -> GetFilesStmt
+{ GetFilesStmt(cornucopia_async::private::Stmt::new("SELECT
+    f.id,
+    f.file_name,
+    f.content_type,
+    f.size_bytes,
+    u.email AS uploaded_by,
+    f.created_at
+FROM files f
+LEFT JOIN users u ON u.id = f.user_id
+WHERE f.team_id = $1 AND f.kind = 'Attachment'
+ORDER BY f.created_at DESC, f.id DESC")) } pub struct
+GetFilesStmt(cornucopia_async::private::Stmt); impl GetFilesStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+team_id: &'a i32,) -> FileQuery<'a,C, File,
+1>
+{
+    FileQuery
+    {
+        client, params: [team_id,], stmt: &mut self.0, extractor:
+        |row| { FileBorrowed { id: row.get(0),file_name: row.get(1),content_type: row.get(2),size_bytes: row.get(3),uploaded_by: row.get(4),created_at: row.get(5),} }, mapper: |it| { <File>::from(it) },
+    }
+} }pub fn get_avatar() -> GetAvatarStmt
+{ GetAvatarStmt(cornucopia_async::private::Stmt::new("SELECT id
+FROM files
+WHERE user_id = $1 AND kind = 'Avatar'
+ORDER BY created_at DESC, id DESC
+LIMIT 1")) } pub struct
+GetAvatarStmt(cornucopia_async::private::Stmt); impl GetAvatarStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+user_id: &'a i32,) -> I32Query<'a,C, i32,
+1>
+{
+    I32Query
+    {
+        client, params: [user_id,], stmt: &mut self.0, extractor:
+        |row| { row.get(0) }, mapper: |it| { it },
+    }
+} }pub fn get_file_download() -> GetFileDownloadStmt
+{ GetFileDownloadStmt(cornucopia_async::private::Stmt::new("SELECT
+    storage_key,
+    file_name,
+    content_type
+FROM files
+WHERE id = $1")) } pub struct
+GetFileDownloadStmt(cornucopia_async::private::Stmt); impl GetFileDownloadStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+id: &'a i32,) -> GetFileDownloadQuery<'a,C, GetFileDownload,
+1>
+{
+    GetFileDownloadQuery
+    {
+        client, params: [id,], stmt: &mut self.0, extractor:
+        |row| { GetFileDownloadBorrowed { storage_key: row.get(0),file_name: row.get(1),content_type: row.get(2),} }, mapper: |it| { <GetFileDownload>::from(it) },
+    }
+} }}pub mod jobs
+{ use futures::{{StreamExt, TryStreamExt}};use futures; use cornucopia_async::GenericClient;#[derive( Debug)] pub struct EnqueueJobParams<T1: cornucopia_async::StringSql,T2: cornucopia_async::JsonSql,> { pub team_id: Option<i32>,pub kind: T1,pub payload: T2,pub max_attempts: i32,}#[derive( Debug)] pub struct RetryJobParams<T1: cornucopia_async::StringSql,> { pub last_error: T1,pub delay_seconds: f64,pub id: i32,}#[derive( Debug)] pub struct DeadLetterJobParams<T1: cornucopia_async::StringSql,> { pub last_error: T1,pub id: i32,}#[derive(Clone,Copy, Debug)] pub struct RequeueJobParams<> { pub id: i32,pub team_id: i32,}pub struct I32Query<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> i32,
+    mapper: fn(i32) -> T,
+} impl<'a, C, T:'a, const N: usize> I32Query<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(i32) -> R) ->
+    I32Query<'a,C,R,N>
+    {
+        I32Query
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}#[derive(serde::Serialize, Debug, Clone, PartialEq,)] pub struct ClaimedJob
+{ pub id : i32,pub kind : String,pub payload : serde_json::Value,pub attempts : i32,pub max_attempts : i32,}pub struct ClaimedJobBorrowed<'a> { pub id : i32,pub kind : &'a str,pub payload : postgres_types::Json<&'a serde_json::value::RawValue>,pub attempts : i32,pub max_attempts : i32,}
+impl<'a> From<ClaimedJobBorrowed<'a>> for ClaimedJob
+{
+    fn from(ClaimedJobBorrowed { id,kind,payload,attempts,max_attempts,}: ClaimedJobBorrowed<'a>) -> Self
+    { Self { id,kind: kind.into(),payload: serde_json::from_str(payload.0.get()).unwrap(),attempts,max_attempts,} }
+}pub struct ClaimedJobQuery<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> ClaimedJobBorrowed,
+    mapper: fn(ClaimedJobBorrowed) -> T,
+} impl<'a, C, T:'a, const N: usize> ClaimedJobQuery<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(ClaimedJobBorrowed) -> R) ->
+    ClaimedJobQuery<'a,C,R,N>
+    {
+        ClaimedJobQuery
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}#[derive(serde::Serialize, Debug, Clone, PartialEq,)] pub struct FailedJob
+{ pub id : i32,pub kind : String,pub status : super::super::types::public::JobStatus,pub attempts : i32,pub max_attempts : i32,pub last_error : Option<String>,pub run_at : time::OffsetDateTime,pub updated_at : time::OffsetDateTime,}pub struct FailedJobBorrowed<'a> { pub id : i32,pub kind : &'a str,pub status : super::super::types::public::JobStatus,pub attempts : i32,pub max_attempts : i32,pub last_error : Option<&'a str>,pub run_at : time::OffsetDateTime,pub updated_at : time::OffsetDateTime,}
+impl<'a> From<FailedJobBorrowed<'a>> for FailedJob
+{
+    fn from(FailedJobBorrowed { id,kind,status,attempts,max_attempts,last_error,run_at,updated_at,}: FailedJobBorrowed<'a>) -> Self
+    { Self { id,kind: kind.into(),status,attempts,max_attempts,last_error: last_error.map(|v| v.into()),run_at,updated_at,} }
+}pub struct FailedJobQuery<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> FailedJobBorrowed,
+    mapper: fn(FailedJobBorrowed) -> T,
+} impl<'a, C, T:'a, const N: usize> FailedJobQuery<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(FailedJobBorrowed) -> R) ->
+    FailedJobQuery<'a,C,R,N>
+    {
+        FailedJobQuery
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}pub fn enqueue_job() -> EnqueueJobStmt
+{ EnqueueJobStmt(cornucopia_async::private::Stmt::new("INSERT INTO jobs (team_id, kind, payload, max_attempts)
+VALUES ($1, $2, $3, $4)
+RETURNING id")) } pub struct
+EnqueueJobStmt(cornucopia_async::private::Stmt); impl EnqueueJobStmt
+{ pub fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::StringSql,T2:
+cornucopia_async::JsonSql,>(&'a mut self, client: &'a  C,
+team_id: &'a Option<i32>,kind: &'a T1,payload: &'a T2,max_attempts: &'a i32,) -> I32Query<'a,C, i32,
+4>
+{
+    I32Query
+    {
+        client, params: [team_id,kind,payload,max_attempts,], stmt: &mut self.0, extractor:
+        |row| { row.get(0) }, mapper: |it| { it },
+    }
+} }impl <'a, C: GenericClient,T1: cornucopia_async::StringSql,T2: cornucopia_async::JsonSql,> cornucopia_async::Params<'a,
+EnqueueJobParams<T1,T2,>, I32Query<'a, C, i32,
+4>, C> for EnqueueJobStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    EnqueueJobParams<T1,T2,>) -> I32Query<'a, C,
+    i32, 4>
+    { self.bind(client, &params.team_id,&params.kind,&params.payload,&params.max_attempts,) }
+}pub fn claim_job() -> ClaimJobStmt
+{ ClaimJobStmt(cornucopia_async::private::Stmt::new("UPDATE jobs
+SET
+    status = 'Running',
+    attempts = attempts + 1,
+    locked_at = NOW(),
+    updated_at = NOW()
+WHERE id = (
+    SELECT id
+    FROM jobs
+    WHERE status = 'Pending' AND run_at <= NOW()
+    ORDER BY run_at, id
+    FOR UPDATE SKIP LOCKED
+    LIMIT 1
+)
+RETURNING id, kind, payload, attempts, max_attempts")) } pub struct
+ClaimJobStmt(cornucopia_async::private::Stmt); impl ClaimJobStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+) -> ClaimedJobQuery<'a,C, ClaimedJob,
+0>
+{
+    ClaimedJobQuery
+    {
+        client, params: [], stmt: &mut self.0, extractor:
+        |row| { ClaimedJobBorrowed { id: row.get(0),kind: row.get(1),payload: row.get(2),attempts: row.get(3),max_attempts: row.get(4),} }, mapper: |it| { <ClaimedJob>::from(it) },
+    }
+} }pub fn complete_job() -> CompleteJobStmt
+{ CompleteJobStmt(cornucopia_async::private::Stmt::new("UPDATE jobs
+SET
+    status = 'Completed',
+    locked_at = NULL,
+    last_error = NULL,
+    updated_at = NOW()
+WHERE id = $1")) } pub struct
+CompleteJobStmt(cornucopia_async::private::Stmt); impl CompleteJobStmt
+{ pub async fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+id: &'a i32,) -> Result<u64, tokio_postgres::Error>
+{
+    let stmt = self.0.prepare(client).await?;
+    client.execute(stmt, &[id,]).await
+} }pub fn retry_job() -> RetryJobStmt
+{ RetryJobStmt(cornucopia_async::private::Stmt::new("UPDATE jobs
+SET
+    status = 'Pending',
+    locked_at = NULL,
+    last_error = $1,
+    run_at = NOW() + make_interval(secs => $2),
+    updated_at = NOW()
+WHERE id = $3")) } pub struct
+RetryJobStmt(cornucopia_async::private::Stmt); impl RetryJobStmt
+{ pub async fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::StringSql,>(&'a mut self, client: &'a  C,
+last_error: &'a T1,delay_seconds: &'a f64,id: &'a i32,) -> Result<u64, tokio_postgres::Error>
+{
+    let stmt = self.0.prepare(client).await?;
+    client.execute(stmt, &[last_error,delay_seconds,id,]).await
+} }impl <'a, C: GenericClient + Send + Sync, T1: cornucopia_async::StringSql,>
+cornucopia_async::Params<'a, RetryJobParams<T1,>, std::pin::Pin<Box<dyn futures::Future<Output = Result<u64,
+tokio_postgres::Error>> + Send + 'a>>, C> for RetryJobStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    RetryJobParams<T1,>) -> std::pin::Pin<Box<dyn futures::Future<Output = Result<u64,
+    tokio_postgres::Error>> + Send + 'a>>
+    { Box::pin(self.bind(client, &params.last_error,&params.delay_seconds,&params.id,)) }
+}pub fn dead_letter_job() -> DeadLetterJobStmt
+{ DeadLetterJobStmt(cornucopia_async::private::Stmt::new("UPDATE jobs
+SET
+    status = 'Dead',
+    locked_at = NULL,
+    last_error = $1,
+    updated_at = NOW()
+WHERE id = $2")) } pub struct
+DeadLetterJobStmt(cornucopia_async::private::Stmt); impl DeadLetterJobStmt
+{ pub async fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::StringSql,>(&'a mut self, client: &'a  C,
+last_error: &'a T1,id: &'a i32,) -> Result<u64, tokio_postgres::Error>
+{
+    let stmt = self.0.prepare(client).await?;
+    client.execute(stmt, &[last_error,id,]).await
+} }impl <'a, C: GenericClient + Send + Sync, T1: cornucopia_async::StringSql,>
+cornucopia_async::Params<'a, DeadLetterJobParams<T1,>, std::pin::Pin<Box<dyn futures::Future<Output = Result<u64,
+tokio_postgres::Error>> + Send + 'a>>, C> for DeadLetterJobStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    DeadLetterJobParams<T1,>) -> std::pin::Pin<Box<dyn futures::Future<Output = Result<u64,
+    tokio_postgres::Error>> + Send + 'a>>
+    { Box::pin(self.bind(client, &params.last_error,&params.id,)) }
+}pub fn release_stale_jobs() -> ReleaseStaleJobsStmt
+{ ReleaseStaleJobsStmt(cornucopia_async::private::Stmt::new("UPDATE jobs
+SET
+    status = 'Pending',
+    locked_at = NULL,
+    updated_at = NOW()
+WHERE status = 'Running' AND locked_at < NOW() - make_interval(secs => $1)")) } pub struct
+ReleaseStaleJobsStmt(cornucopia_async::private::Stmt); impl ReleaseStaleJobsStmt
+{ pub async fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+stale_after_seconds: &'a f64,) -> Result<u64, tokio_postgres::Error>
+{
+    let stmt = self.0.prepare(client).await?;
+    client.execute(stmt, &[stale_after_seconds,]).await
+} }pub fn get_failed_jobs() -> GetFailedJobsStmt
+{ GetFailedJobsStmt(cornucopia_async::private::Stmt::new("SELECT
+    id,
+    kind,
+    status,
+    attempts,
+    max_attempts,
+    last_error,
+    run_at,
+    updated_at
+FROM jobs
+WHERE team_id = $1
+    AND (status = 'Dead' OR (status = 'Pending' AND last_error IS NOT NULL))
+ORDER BY updated_at DESC
+LIMIT 100")) } pub struct
+GetFailedJobsStmt(cornucopia_async::private::Stmt); impl GetFailedJobsStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+team_id: &'a i32,) -> FailedJobQuery<'a,C, FailedJob,
+1>
+{
+    FailedJobQuery
+    {
+        client, params: [team_id,], stmt: &mut self.0, extractor:
+        |row| { FailedJobBorrowed { id: row.get(0),kind: row.get(1),status: row.get(2),attempts: row.get(3),max_attempts: row.get(4),last_error: row.get(5),run_at: row.get(6),updated_at: row.get(7),} }, mapper: |it| { <FailedJob>::from(it) },
+    }
+} }pub fn requeue_job() -> RequeueJobStmt
+{ RequeueJobStmt(cornucopia_async::private::Stmt::new("UPDATE jobs
+SET
+    status = 'Pending',
+    attempts = 0,
+    run_at = NOW(),
+    updated_at = NOW()
+WHERE id = $1 AND team_id = $2 AND status = 'Dead'
+RETURNING id")) } pub struct
+RequeueJobStmt(cornucopia_async::private::Stmt); impl RequeueJobStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+id: &'a i32,team_id: &'a i32,) -> I32Query<'a,C, i32,
+2>
+{
+    I32Query
+    {
+        client, params: [id,team_id,], stmt: &mut self.0, extractor:
+        |row| { row.get(0) }, mapper: |it| { it },
+    }
+} }impl <'a, C: GenericClient,> cornucopia_async::Params<'a,
+RequeueJobParams<>, I32Query<'a, C, i32,
+2>, C> for RequeueJobStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    RequeueJobParams<>) -> I32Query<'a, C,
+    i32, 2>
+    { self.bind(client, &params.id,&params.team_id,) }
+}}pub mod teams
+{ use futures::{{StreamExt, TryStreamExt}};use futures; use cornucopia_async::GenericClient;#[derive( Debug)] pub struct CreateTeamParams<T1: cornucopia_async::StringSql,> { pub name: T1,pub created_by: i32,}#[derive(Clone,Copy, Debug)] pub struct AddTeamMemberParams<> { pub team_id: i32,pub user_id: i32,pub role: super::super::types::public::TeamRole,}#[derive( Debug)] pub struct CreateInvitationParams<T1: cornucopia_async::StringSql,> { pub team_id: i32,pub email: T1,pub role: super::super::types::public::TeamRole,pub invited_by: i32,}#[derive( Debug)] pub struct AcceptInvitationParams<T1: cornucopia_async::StringSql,> { pub id: i32,pub email: T1,}#[derive(Clone,Copy, Debug)] pub struct GetPermissionsParams<> { pub team_id: i32,pub user_id: i32,}#[derive( Debug)] pub struct AddImportedMembersParams<T1: cornucopia_async::ArraySql<Item = i32>,> { pub team_id: i32,pub user_ids: T1,}#[derive(Clone,Copy, Debug)] pub struct InviteImportedUsersParams<> { pub team_id: i32,pub invited_by: i32,}#[derive(serde::Serialize, Debug, Clone, PartialEq,)] pub struct Team
+{ pub id : i32,pub name : String,pub role : super::super::types::public::TeamRole,}pub struct TeamBorrowed<'a> { pub id : i32,pub name : &'a str,pub role : super::super::types::public::TeamRole,}
+impl<'a> From<TeamBorrowed<'a>> for Team
+{
+    fn from(TeamBorrowed { id,name,role,}: TeamBorrowed<'a>) -> Self
+    { Self { id,name: name.into(),role,} }
+}pub struct TeamQuery<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> TeamBorrowed,
+    mapper: fn(TeamBorrowed) -> T,
+} impl<'a, C, T:'a, const N: usize> TeamQuery<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(TeamBorrowed) -> R) ->
+    TeamQuery<'a,C,R,N>
+    {
+        TeamQuery
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}pub struct I32Query<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> i32,
+    mapper: fn(i32) -> T,
+} impl<'a, C, T:'a, const N: usize> I32Query<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(i32) -> R) ->
+    I32Query<'a,C,R,N>
+    {
+        I32Query
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}#[derive(serde::Serialize, Debug, Clone, PartialEq,)] pub struct TeamMember
+{ pub id : i32,pub email : String,pub role : super::super::types::public::TeamRole,pub joined_at : time::OffsetDateTime,}pub struct TeamMemberBorrowed<'a> { pub id : i32,pub email : &'a str,pub role : super::super::types::public::TeamRole,pub joined_at : time::OffsetDateTime,}
+impl<'a> From<TeamMemberBorrowed<'a>> for TeamMember
+{
+    fn from(TeamMemberBorrowed { id,email,role,joined_at,}: TeamMemberBorrowed<'a>) -> Self
+    { Self { id,email: email.into(),role,joined_at,} }
+}pub struct TeamMemberQuery<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> TeamMemberBorrowed,
+    mapper: fn(TeamMemberBorrowed) -> T,
+} impl<'a, C, T:'a, const N: usize> TeamMemberQuery<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(TeamMemberBorrowed) -> R) ->
+    TeamMemberQuery<'a,C,R,N>
+    {
+        TeamMemberQuery
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}#[derive(serde::Serialize, Debug, Clone, PartialEq,)] pub struct Invitation
+{ pub id : i32,pub email : String,pub role : super::super::types::public::TeamRole,pub created_at : time::OffsetDateTime,}pub struct InvitationBorrowed<'a> { pub id : i32,pub email : &'a str,pub role : super::super::types::public::TeamRole,pub created_at : time::OffsetDateTime,}
+impl<'a> From<InvitationBorrowed<'a>> for Invitation
+{
+    fn from(InvitationBorrowed { id,email,role,created_at,}: InvitationBorrowed<'a>) -> Self
+    { Self { id,email: email.into(),role,created_at,} }
+}pub struct InvitationQuery<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> InvitationBorrowed,
+    mapper: fn(InvitationBorrowed) -> T,
+} impl<'a, C, T:'a, const N: usize> InvitationQuery<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(InvitationBorrowed) -> R) ->
+    InvitationQuery<'a,C,R,N>
+    {
+        InvitationQuery
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}#[derive(serde::Serialize, Debug, Clone, PartialEq,)] pub struct InvitationForUser
+{ pub id : i32,pub team_id : i32,pub team_name : String,pub role : super::super::types::public::TeamRole,pub created_at : time::OffsetDateTime,}pub struct InvitationForUserBorrowed<'a> { pub id : i32,pub team_id : i32,pub team_name : &'a str,pub role : super::super::types::public::TeamRole,pub created_at : time::OffsetDateTime,}
+impl<'a> From<InvitationForUserBorrowed<'a>> for InvitationForUser
+{
+    fn from(InvitationForUserBorrowed { id,team_id,team_name,role,created_at,}: InvitationForUserBorrowed<'a>) -> Self
+    { Self { id,team_id,team_name: team_name.into(),role,created_at,} }
+}pub struct InvitationForUserQuery<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> InvitationForUserBorrowed,
+    mapper: fn(InvitationForUserBorrowed) -> T,
+} impl<'a, C, T:'a, const N: usize> InvitationForUserQuery<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(InvitationForUserBorrowed) -> R) ->
+    InvitationForUserQuery<'a,C,R,N>
+    {
+        InvitationForUserQuery
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}#[derive(serde::Serialize, Debug, Clone, PartialEq,Copy)] pub struct AcceptedInvitation
+{ pub team_id : i32,pub role : super::super::types::public::TeamRole,}pub struct AcceptedInvitationQuery<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> AcceptedInvitation,
+    mapper: fn(AcceptedInvitation) -> T,
+} impl<'a, C, T:'a, const N: usize> AcceptedInvitationQuery<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(AcceptedInvitation) -> R) ->
+    AcceptedInvitationQuery<'a,C,R,N>
+    {
+        AcceptedInvitationQuery
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}pub struct SuperSuperTypesPublicPermissionQuery<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> super::super::types::public::Permission,
+    mapper: fn(super::super::types::public::Permission) -> T,
+} impl<'a, C, T:'a, const N: usize> SuperSuperTypesPublicPermissionQuery<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(super::super::types::public::Permission) -> R) ->
+    SuperSuperTypesPublicPermissionQuery<'a,C,R,N>
+    {
+        SuperSuperTypesPublicPermissionQuery
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}pub struct StringQuery<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> & str,
+    mapper: fn(& str) -> T,
+} impl<'a, C, T:'a, const N: usize> StringQuery<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(& str) -> R) ->
+    StringQuery<'a,C,R,N>
+    {
+        StringQuery
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}pub fn get_teams() -> GetTeamsStmt
+{ GetTeamsStmt(cornucopia_async::private::Stmt::new("SELECT
+    t.id,
+    t.name,
+    m.role
+FROM teams t
+JOIN team_memberships m ON m.team_id = t.id
+WHERE m.user_id = $1
+ORDER BY m.joined_at, t.id")) } pub struct
+GetTeamsStmt(cornucopia_async::private::Stmt); impl GetTeamsStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+user_id: &'a i32,) -> TeamQuery<'a,C, Team,
+1>
+{
+    TeamQuery
+    {
+        client, params: [user_id,], stmt: &mut self.0, extractor:
+        |row| { TeamBorrowed { id: row.get(0),name: row.get(1),role: row.get(2),} }, mapper: |it| { <Team>::from(it) },
+    }
+} }pub fn create_team() -> CreateTeamStmt
+{ CreateTeamStmt(cornucopia_async::private::Stmt::new("INSERT INTO teams (name, created_by)
+VALUES ($1, $2)
+RETURNING id")) } pub struct
+CreateTeamStmt(cornucopia_async::private::Stmt); impl CreateTeamStmt
+{ pub fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::StringSql,>(&'a mut self, client: &'a  C,
+name: &'a T1,created_by: &'a i32,) -> I32Query<'a,C, i32,
+2>
+{
+    I32Query
+    {
+        client, params: [name,created_by,], stmt: &mut self.0, extractor:
+        |row| { row.get(0) }, mapper: |it| { it },
+    }
+} }impl <'a, C: GenericClient,T1: cornucopia_async::StringSql,> cornucopia_async::Params<'a,
+CreateTeamParams<T1,>, I32Query<'a, C, i32,
+2>, C> for CreateTeamStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    CreateTeamParams<T1,>) -> I32Query<'a, C,
+    i32, 2>
+    { self.bind(client, &params.name,&params.created_by,) }
+}pub fn add_team_member() -> AddTeamMemberStmt
+{ AddTeamMemberStmt(cornucopia_async::private::Stmt::new("INSERT INTO team_memberships (team_id, user_id, role)
+VALUES ($1, $2, $3)
+ON CONFLICT (team_id, user_id) DO NOTHING")) } pub struct
+AddTeamMemberStmt(cornucopia_async::private::Stmt); impl AddTeamMemberStmt
+{ pub async fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+team_id: &'a i32,user_id: &'a i32,role: &'a super::super::types::public::TeamRole,) -> Result<u64, tokio_postgres::Error>
+{
+    let stmt = self.0.prepare(client).await?;
+    client.execute(stmt, &[team_id,user_id,role,]).await
+} }impl <'a, C: GenericClient + Send + Sync, >
+cornucopia_async::Params<'a, AddTeamMemberParams<>, std::pin::Pin<Box<dyn futures::Future<Output = Result<u64,
+tokio_postgres::Error>> + Send + 'a>>, C> for AddTeamMemberStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    AddTeamMemberParams<>) -> std::pin::Pin<Box<dyn futures::Future<Output = Result<u64,
+    tokio_postgres::Error>> + Send + 'a>>
+    { Box::pin(self.bind(client, &params.team_id,&params.user_id,&params.role,)) }
+}pub fn get_team_members() -> GetTeamMembersStmt
+{ GetTeamMembersStmt(cornucopia_async::private::Stmt::new("SELECT
+    u.id,
+    u.email,
+    m.role,
+    m.joined_at
+FROM team_memberships m
+JOIN users u ON u.id = m.user_id
+WHERE m.team_id = $1
+ORDER BY m.joined_at, u.id")) } pub struct
+GetTeamMembersStmt(cornucopia_async::private::Stmt); impl GetTeamMembersStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+team_id: &'a i32,) -> TeamMemberQuery<'a,C, TeamMember,
+1>
+{
+    TeamMemberQuery
+    {
+        client, params: [team_id,], stmt: &mut self.0, extractor:
+        |row| { TeamMemberBorrowed { id: row.get(0),email: row.get(1),role: row.get(2),joined_at: row.get(3),} }, mapper: |it| { <TeamMember>::from(it) },
+    }
+} }pub fn create_invitation() -> CreateInvitationStmt
+{ CreateInvitationStmt(cornucopia_async::private::Stmt::new("INSERT INTO invitations (team_id, email, role, invited_by)
+VALUES ($1, $2, $3, $4)
+ON CONFLICT (team_id, email) DO UPDATE
+SET
+    role = EXCLUDED.role,
+    invited_by = EXCLUDED.invited_by,
+    created_at = NOW(),
+    accepted_at = NULL
+RETURNING id")) } pub struct
+CreateInvitationStmt(cornucopia_async::private::Stmt); impl CreateInvitationStmt
+{ pub fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::StringSql,>(&'a mut self, client: &'a  C,
+team_id: &'a i32,email: &'a T1,role: &'a super::super::types::public::TeamRole,invited_by: &'a i32,) -> I32Query<'a,C, i32,
+4>
+{
+    I32Query
+    {
+        client, params: [team_id,email,role,invited_by,], stmt: &mut self.0, extractor:
+        |row| { row.get(0) }, mapper: |it| { it },
+    }
+} }impl <'a, C: GenericClient,T1: cornucopia_async::StringSql,> cornucopia_async::Params<'a,
+CreateInvitationParams<T1,>, I32Query<'a, C, i32,
+4>, C> for CreateInvitationStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    CreateInvitationParams<T1,>) -> I32Query<'a, C,
+    i32, 4>
+    { self.bind(client, &params.team_id,&params.email,&params.role,&params.invited_by,) }
+}pub fn get_invitations() -> GetInvitationsStmt
+{ GetInvitationsStmt(cornucopia_async::private::Stmt::new("SELECT
+    id,
+    email,
+    role,
+    created_at
+FROM invitations
+WHERE team_id = $1 AND accepted_at IS NULL
+ORDER BY created_at DESC")) } pub struct
+GetInvitationsStmt(cornucopia_async::private::Stmt); impl GetInvitationsStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+team_id: &'a i32,) -> InvitationQuery<'a,C, Invitation,
+1>
+{
+    InvitationQuery
+    {
+        client, params: [team_id,], stmt: &mut self.0, extractor:
+        |row| { InvitationBorrowed { id: row.get(0),email: row.get(1),role: row.get(2),created_at: row.get(3),} }, mapper: |it| { <Invitation>::from(it) },
+    }
+} }pub fn get_invitations_for_email() -> GetInvitationsForEmailStmt
+{ GetInvitationsForEmailStmt(cornucopia_async::private::Stmt::new("SELECT
+    i.id,
+    i.team_id,
+    t.name AS team_name,
+    i.role,
+    i.created_at
+FROM invitations i
+JOIN teams t ON t.id = i.team_id
+WHERE LOWER(i.email) = LOWER($1) AND i.accepted_at IS NULL
+ORDER BY i.created_at DESC")) } pub struct
+GetInvitationsForEmailStmt(cornucopia_async::private::Stmt); impl GetInvitationsForEmailStmt
+{ pub fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::StringSql,>(&'a mut self, client: &'a  C,
+email: &'a T1,) -> InvitationForUserQuery<'a,C, InvitationForUser,
+1>
+{
+    InvitationForUserQuery
+    {
+        client, params: [email,], stmt: &mut self.0, extractor:
+        |row| { InvitationForUserBorrowed { id: row.get(0),team_id: row.get(1),team_name: row.get(2),role: row.get(3),created_at: row.get(4),} }, mapper: |it| { <InvitationForUser>::from(it) },
+    }
+} }pub fn accept_invitation() -> AcceptInvitationStmt
+{ AcceptInvitationStmt(cornucopia_async::private::Stmt::new("UPDATE invitations
+SET accepted_at = NOW()
+WHERE id = $1 AND LOWER(email) = LOWER($2) AND accepted_at IS NULL
+RETURNING team_id, role")) } pub struct
+AcceptInvitationStmt(cornucopia_async::private::Stmt); impl AcceptInvitationStmt
+{ pub fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::StringSql,>(&'a mut self, client: &'a  C,
+id: &'a i32,email: &'a T1,) -> AcceptedInvitationQuery<'a,C, AcceptedInvitation,
+2>
+{
+    AcceptedInvitationQuery
+    {
+        client, params: [id,email,], stmt: &mut self.0, extractor:
+        |row| { AcceptedInvitation { team_id: row.get(0),role: row.get(1),} }, mapper: |it| { <AcceptedInvitation>::from(it) },
+    }
+} }impl <'a, C: GenericClient,T1: cornucopia_async::StringSql,> cornucopia_async::Params<'a,
+AcceptInvitationParams<T1,>, AcceptedInvitationQuery<'a, C, AcceptedInvitation,
+2>, C> for AcceptInvitationStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    AcceptInvitationParams<T1,>) -> AcceptedInvitationQuery<'a, C,
+    AcceptedInvitation, 2>
+    { self.bind(client, &params.id,&params.email,) }
+}pub fn get_permissions() -> GetPermissionsStmt
+{ GetPermissionsStmt(cornucopia_async::private::Stmt::new("SELECT rp.permission
+FROM role_permissions rp
+JOIN team_memberships m ON m.role = rp.role
+WHERE m.team_id = $1 AND m.user_id = $2
+ORDER BY rp.permission")) } pub struct
+GetPermissionsStmt(cornucopia_async::private::Stmt); impl GetPermissionsStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+team_id: &'a i32,user_id: &'a i32,) -> SuperSuperTypesPublicPermissionQuery<'a,C, super::super::types::public::Permission,
+2>
+{
+    SuperSuperTypesPublicPermissionQuery
+    {
+        client, params: [team_id,user_id,], stmt: &mut self.0, extractor:
+        |row| { row.get(0) }, mapper: |it| { it },
+    }
+} }impl <'a, C: GenericClient,> cornucopia_async::Params<'a,
+GetPermissionsParams<>, SuperSuperTypesPublicPermissionQuery<'a, C, super::super::types::public::Permission,
+2>, C> for GetPermissionsStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    GetPermissionsParams<>) -> SuperSuperTypesPublicPermissionQuery<'a, C,
+    super::super::types::public::Permission, 2>
+    { self.bind(client, &params.team_id,&params.user_id,) }
+}pub fn add_imported_members() -> AddImportedMembersStmt
+{ AddImportedMembersStmt(cornucopia_async::private::Stmt::new("INSERT INTO team_memberships (team_id, user_id, role)
+SELECT $1, id, 'Member' FROM unnest($2::INT[]) AS id")) } pub struct
+AddImportedMembersStmt(cornucopia_async::private::Stmt); impl AddImportedMembersStmt
+{ pub async fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::ArraySql<Item = i32>,>(&'a mut self, client: &'a  C,
+team_id: &'a i32,user_ids: &'a T1,) -> Result<u64, tokio_postgres::Error>
+{
+    let stmt = self.0.prepare(client).await?;
+    client.execute(stmt, &[team_id,user_ids,]).await
+} }impl <'a, C: GenericClient + Send + Sync, T1: cornucopia_async::ArraySql<Item = i32>,>
+cornucopia_async::Params<'a, AddImportedMembersParams<T1,>, std::pin::Pin<Box<dyn futures::Future<Output = Result<u64,
+tokio_postgres::Error>> + Send + 'a>>, C> for AddImportedMembersStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    AddImportedMembersParams<T1,>) -> std::pin::Pin<Box<dyn futures::Future<Output = Result<u64,
+    tokio_postgres::Error>> + Send + 'a>>
+    { Box::pin(self.bind(client, &params.team_id,&params.user_ids,)) }
+}pub fn invite_imported_users() -> InviteImportedUsersStmt
+{ InviteImportedUsersStmt(cornucopia_async::private::Stmt::new("INSERT INTO invitations (team_id, email, role, invited_by)
+SELECT $1, u.email, 'Member', $2
+FROM users u
+JOIN (SELECT DISTINCT email FROM user_import) i ON i.email = u.email
+WHERE NOT EXISTS (
+    SELECT 1 FROM team_memberships m
+    WHERE m.team_id = $1 AND m.user_id = u.id
+)
+ON CONFLICT (team_id, email) DO UPDATE
+SET
+    role = EXCLUDED.role,
+    invited_by = EXCLUDED.invited_by,
+    created_at = NOW(),
+    accepted_at = NULL
+RETURNING email")) } pub struct
+InviteImportedUsersStmt(cornucopia_async::private::Stmt); impl InviteImportedUsersStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+team_id: &'a i32,invited_by: &'a i32,) -> StringQuery<'a,C, String,
+2>
+{
+    StringQuery
+    {
+        client, params: [team_id,invited_by,], stmt: &mut self.0, extractor:
+        |row| { row.get(0) }, mapper: |it| { it.into() },
+    }
+} }impl <'a, C: GenericClient,> cornucopia_async::Params<'a,
+InviteImportedUsersParams<>, StringQuery<'a, C, String,
+2>, C> for InviteImportedUsersStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    InviteImportedUsersParams<>) -> StringQuery<'a, C,
+    String, 2>
+    { self.bind(client, &params.team_id,&params.invited_by,) }
+}}pub mod user_settings
+{ use futures::{{StreamExt, TryStreamExt}};use futures; use cornucopia_async::GenericClient;#[derive( Debug)] pub struct UpsertUserSettingsParams<T1: cornucopia_async::BytesSql,T2: cornucopia_async::StringSql,T3: cornucopia_async::StringSql,> { pub user_id: i32,pub encrypted_display_name: Option<T1>,pub timezone: T2,pub theme: super::super::types::public::Theme,pub notify_product_updates: bool,pub notify_security_alerts: bool,pub locale: Option<T3>,}#[derive(serde::Serialize, Debug, Clone, PartialEq,)] pub struct UserSettings
+{ pub user_id : i32,pub email : String,pub display_name : Option<String>,pub encrypted_display_name : Option<Vec<u8>>,pub timezone : String,pub theme : super::super::types::public::Theme,pub notify_product_updates : bool,pub notify_security_alerts : bool,pub locale : Option<String>,pub avatar_id : Option<i32>,}pub struct UserSettingsBorrowed<'a> { pub user_id : i32,pub email : &'a str,pub display_name : Option<&'a str>,pub encrypted_display_name : Option<&'a [u8]>,pub timezone : &'a str,pub theme : super::super::types::public::Theme,pub notify_product_updates : bool,pub notify_security_alerts : bool,pub locale : Option<&'a str>,pub avatar_id : Option<i32>,}
+impl<'a> From<UserSettingsBorrowed<'a>> for UserSettings
+{
+    fn from(UserSettingsBorrowed { user_id,email,display_name,encrypted_display_name,timezone,theme,notify_product_updates,notify_security_alerts,locale,avatar_id,}: UserSettingsBorrowed<'a>) -> Self
+    { Self { user_id,email: email.into(),display_name: display_name.map(|v| v.into()),encrypted_display_name: encrypted_display_name.map(|v| v.into()),timezone: timezone.into(),theme,notify_product_updates,notify_security_alerts,locale: locale.map(|v| v.into()),avatar_id,} }
+}pub struct UserSettingsQuery<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> UserSettingsBorrowed,
+    mapper: fn(UserSettingsBorrowed) -> T,
+} impl<'a, C, T:'a, const N: usize> UserSettingsQuery<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(UserSettingsBorrowed) -> R) ->
+    UserSettingsQuery<'a,C,R,N>
+    {
+        UserSettingsQuery
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}pub fn get_user_settings() -> GetUserSettingsStmt
+{ GetUserSettingsStmt(cornucopia_async::private::Stmt::new("SELECT
+    u.id AS user_id,
+    u.email,
+    s.display_name,
+    s.encrypted_display_name,
+    COALESCE(s.timezone, 'UTC') AS timezone,
+    COALESCE(s.theme, 'System') AS theme,
+    COALESCE(s.notify_product_updates, false) AS notify_product_updates,
+    COALESCE(s.notify_security_alerts, true) AS notify_security_alerts,
+    s.locale,
+    (
+        SELECT f.id
+        FROM files f
+        WHERE f.user_id = u.id AND f.kind = 'Avatar'
+        ORDER BY f.created_at DESC, f.id DESC
+        LIMIT 1
+    ) AS avatar_id
+FROM users u
+LEFT JOIN user_settings s ON s.user_id = u.id
+WHERE u.id = $1")) } pub struct
+GetUserSettingsStmt(cornucopia_async::private::Stmt); impl GetUserSettingsStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+user_id: &'a i32,) -> UserSettingsQuery<'a,C, UserSettings,
+1>
+{
+    UserSettingsQuery
+    {
+        client, params: [user_id,], stmt: &mut self.0, extractor:
+        |row| { UserSettingsBorrowed { user_id: row.get(0),email: row.get(1),display_name: row.get(2),encrypted_display_name: row.get(3),timezone: row.get(4),theme: row.get(5),notify_product_updates: row.get(6),notify_security_alerts: row.get(7),locale: row.get(8),avatar_id: row.get(9),} }, mapper: |it| { <UserSettings>::from(it) },
+    }
+} }pub fn upsert_user_settings() -> UpsertUserSettingsStmt
+{ UpsertUserSettingsStmt(cornucopia_async::private::Stmt::new("INSERT INTO user_settings (
+    user_id,
+    encrypted_display_name,
+    timezone,
+    theme,
+    notify_product_updates,
+    notify_security_alerts,
+    locale
+)
+VALUES (
+    $1,
+    $2,
+    $3,
+    $4,
+    $5,
+    $6,
+    $7
+)
+ON CONFLICT (user_id) DO UPDATE SET
+    display_name = NULL,
+    encrypted_display_name = EXCLUDED.encrypted_display_name,
+    timezone = EXCLUDED.timezone,
+    theme = EXCLUDED.theme,
+    notify_product_updates = EXCLUDED.notify_product_updates,
+    notify_security_alerts = EXCLUDED.notify_security_alerts,
+    locale = EXCLUDED.locale,
+    updated_at = NOW()")) } pub struct
+UpsertUserSettingsStmt(cornucopia_async::private::Stmt); impl UpsertUserSettingsStmt
+{ pub async fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::BytesSql,T2:
+cornucopia_async::StringSql,T3:
+cornucopia_async::StringSql,>(&'a mut self, client: &'a  C,
+user_id: &'a i32,encrypted_display_name: &'a Option<T1>,timezone: &'a T2,theme: &'a super::super::types::public::Theme,notify_product_updates: &'a bool,notify_security_alerts: &'a bool,locale: &'a Option<T3>,) -> Result<u64, tokio_postgres::Error>
+{
+    let stmt = self.0.prepare(client).await?;
+    client.execute(stmt, &[user_id,encrypted_display_name,timezone,theme,notify_product_updates,notify_security_alerts,locale,]).await
+} }impl <'a, C: GenericClient + Send + Sync, T1: cornucopia_async::BytesSql,T2: cornucopia_async::StringSql,T3: cornucopia_async::StringSql,>
+cornucopia_async::Params<'a, UpsertUserSettingsParams<T1,T2,T3,>, std::pin::Pin<Box<dyn futures::Future<Output = Result<u64,
+tokio_postgres::Error>> + Send + 'a>>, C> for UpsertUserSettingsStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    UpsertUserSettingsParams<T1,T2,T3,>) -> std::pin::Pin<Box<dyn futures::Future<Output = Result<u64,
+    tokio_postgres::Error>> + Send + 'a>>
+    { Box::pin(self.bind(client, &params.user_id,&params.encrypted_display_name,&params.timezone,&params.theme,&params.notify_product_updates,&params.notify_security_alerts,&params.locale,)) }
+}}pub mod users
+{ use futures::{{StreamExt, TryStreamExt}};use futures; use cornucopia_async::GenericClient;#[derive(Clone,Copy, Debug)] pub struct GetTeamUserParams<> { pub team_id: i32,pub id: i32,}#[derive(Clone,Copy, Debug)] pub struct GetTeamUsersAfterParams<> { pub team_id: i32,pub id: i32,}#[derive( Debug)] pub struct SearchTeamUsersParams<T1: cornucopia_async::StringSql,> { pub team_id: i32,pub email: Option<T1>,}#[derive(serde::Serialize, Debug, Clone, PartialEq,)] pub struct User
+{ pub id : i32,pub email : String,pub created_at : time::OffsetDateTime,}pub struct UserBorrowed<'a> { pub id : i32,pub email : &'a str,pub created_at : time::OffsetDateTime,}
+impl<'a> From<UserBorrowed<'a>> for User
+{
+    fn from(UserBorrowed { id,email,created_at,}: UserBorrowed<'a>) -> Self
+    { Self { id,email: email.into(),created_at,} }
+}pub struct UserQuery<'a, C: GenericClient, T, const N: usize>
+{
+    client: &'a  C, params:
+    [&'a (dyn postgres_types::ToSql + Sync); N], stmt: &'a mut
+    cornucopia_async::private::Stmt, extractor: fn(&tokio_postgres::Row) -> UserBorrowed,
+    mapper: fn(UserBorrowed) -> T,
+} impl<'a, C, T:'a, const N: usize> UserQuery<'a, C, T, N> where C:
+GenericClient
+{
+    pub fn map<R>(self, mapper: fn(UserBorrowed) -> R) ->
+    UserQuery<'a,C,R,N>
+    {
+        UserQuery
+        {
+            client: self.client, params: self.params, stmt: self.stmt,
+            extractor: self.extractor, mapper,
+        }
+    } pub async fn one(self) -> Result<T, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let row =
+        self.client.query_one(stmt, &self.params).await?;
+        Ok((self.mapper)((self.extractor)(&row)))
+    } pub async fn all(self) -> Result<Vec<T>, tokio_postgres::Error>
+    { self.iter().await?.try_collect().await } pub async fn opt(self) ->
+    Result<Option<T>, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?;
+        Ok(self.client.query_opt(stmt, &self.params) .await?
+        .map(|row| (self.mapper)((self.extractor)(&row))))
+    } pub async fn iter(self,) -> Result<impl futures::Stream<Item = Result<T,
+    tokio_postgres::Error>> + 'a, tokio_postgres::Error>
+    {
+        let stmt = self.stmt.prepare(self.client).await?; let it =
+        self.client.query_raw(stmt,
+        cornucopia_async::private::slice_iter(&self.params)) .await?
+        .map(move |res|
+        res.map(|row| (self.mapper)((self.extractor)(&row)))) .into_stream();
+        Ok(it)
+    }
+}pub fn get_users() -> GetUsersStmt
+{ GetUsersStmt(cornucopia_async::private::Stmt::new("SELECT 
+    id, 
+    email,
+    created_at::TIMESTAMPTZ AS created_at
+FROM users
+ORDER BY id")) } pub struct
+GetUsersStmt(cornucopia_async::private::Stmt); impl GetUsersStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+) -> UserQuery<'a,C, User,
+0>
+{
+    UserQuery
+    {
+        client, params: [], stmt: &mut self.0, extractor:
+        |row| { UserBorrowed { id: row.get(0),email: row.get(1),created_at: row.get(2),} }, mapper: |it| { <User>::from(it) },
+    }
+} }pub fn create_user() -> CreateUserStmt
+{ CreateUserStmt(cornucopia_async::private::Stmt::new("INSERT INTO 
+    users (email)
+VALUES
+    ($1)
+RETURNING 
+    id, 
+    email,
+    created_at::TIMESTAMPTZ AS created_at")) } pub struct
+CreateUserStmt(cornucopia_async::private::Stmt); impl CreateUserStmt
+{ pub fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::StringSql,>(&'a mut self, client: &'a  C,
+email: &'a T1,) -> UserQuery<'a,C, User,
+1>
+{
+    UserQuery
+    {
+        client, params: [email,], stmt: &mut self.0, extractor:
+        |row| { UserBorrowed { id: row.get(0),email: row.get(1),created_at: row.get(2),} }, mapper: |it| { <User>::from(it) },
+    }
+} }pub fn create_new_user() -> CreateNewUserStmt
+{ CreateNewUserStmt(cornucopia_async::private::Stmt::new("INSERT INTO 
+    users (email)
+VALUES
+    ($1)
+ON CONFLICT (email) DO NOTHING
+RETURNING 
+    id, 
+    email,
+    created_at::TIMESTAMPTZ AS created_at")) } pub struct
+CreateNewUserStmt(cornucopia_async::private::Stmt); impl CreateNewUserStmt
+{ pub fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::StringSql,>(&'a mut self, client: &'a  C,
+email: &'a T1,) -> UserQuery<'a,C, User,
+1>
+{
+    UserQuery
+    {
+        client, params: [email,], stmt: &mut self.0, extractor:
+        |row| { UserBorrowed { id: row.get(0),email: row.get(1),created_at: row.get(2),} }, mapper: |it| { <User>::from(it) },
+    }
+} }pub fn get_or_create_user() -> GetOrCreateUserStmt
+{ GetOrCreateUserStmt(cornucopia_async::private::Stmt::new("WITH inserted AS (
+    INSERT INTO 
+        users (email)
+    VALUES
+        ($1)
+    ON CONFLICT (email) DO NOTHING
+    RETURNING id, email, created_at
+)
+SELECT id, email, created_at::TIMESTAMPTZ AS created_at FROM inserted
+UNION ALL
+SELECT id, email, created_at::TIMESTAMPTZ AS created_at FROM users WHERE email = $1")) } pub struct
+GetOrCreateUserStmt(cornucopia_async::private::Stmt); impl GetOrCreateUserStmt
+{ pub fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::StringSql,>(&'a mut self, client: &'a  C,
+email: &'a T1,) -> UserQuery<'a,C, User,
+1>
+{
+    UserQuery
+    {
+        client, params: [email,], stmt: &mut self.0, extractor:
+        |row| { UserBorrowed { id: row.get(0),email: row.get(1),created_at: row.get(2),} }, mapper: |it| { <User>::from(it) },
+    }
+} }pub fn get_user_by_email() -> GetUserByEmailStmt
+{ GetUserByEmailStmt(cornucopia_async::private::Stmt::new("SELECT
+    id,
+    email,
+    created_at::TIMESTAMPTZ AS created_at
+FROM users
+WHERE email = $1")) } pub struct
+GetUserByEmailStmt(cornucopia_async::private::Stmt); impl GetUserByEmailStmt
+{ pub fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::StringSql,>(&'a mut self, client: &'a  C,
+email: &'a T1,) -> UserQuery<'a,C, User,
+1>
+{
+    UserQuery
+    {
+        client, params: [email,], stmt: &mut self.0, extractor:
+        |row| { UserBorrowed { id: row.get(0),email: row.get(1),created_at: row.get(2),} }, mapper: |it| { <User>::from(it) },
+    }
+} }pub fn get_team_users() -> GetTeamUsersStmt
+{ GetTeamUsersStmt(cornucopia_async::private::Stmt::new("SELECT 
+    u.id, 
+    u.email,
+    u.created_at::TIMESTAMPTZ AS created_at
+FROM users u
+JOIN team_memberships m ON m.user_id = u.id
+WHERE m.team_id = $1
+ORDER BY u.id")) } pub struct
+GetTeamUsersStmt(cornucopia_async::private::Stmt); impl GetTeamUsersStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+team_id: &'a i32,) -> UserQuery<'a,C, User,
+1>
+{
+    UserQuery
+    {
+        client, params: [team_id,], stmt: &mut self.0, extractor:
+        |row| { UserBorrowed { id: row.get(0),email: row.get(1),created_at: row.get(2),} }, mapper: |it| { <User>::from(it) },
+    }
+} }pub fn get_team_user() -> GetTeamUserStmt
+{ GetTeamUserStmt(cornucopia_async::private::Stmt::new("SELECT 
+    u.id, 
+    u.email,
+    u.created_at::TIMESTAMPTZ AS created_at
+FROM users u
+JOIN team_memberships m ON m.user_id = u.id
+WHERE m.team_id = $1 AND u.id = $2")) } pub struct
+GetTeamUserStmt(cornucopia_async::private::Stmt); impl GetTeamUserStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+team_id: &'a i32,id: &'a i32,) -> UserQuery<'a,C, User,
+2>
+{
+    UserQuery
+    {
+        client, params: [team_id,id,], stmt: &mut self.0, extractor:
+        |row| { UserBorrowed { id: row.get(0),email: row.get(1),created_at: row.get(2),} }, mapper: |it| { <User>::from(it) },
+    }
+} }impl <'a, C: GenericClient,> cornucopia_async::Params<'a,
+GetTeamUserParams<>, UserQuery<'a, C, User,
+2>, C> for GetTeamUserStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    GetTeamUserParams<>) -> UserQuery<'a, C,
+    User, 2>
+    { self.bind(client, &params.team_id,&params.id,) }
+}pub fn get_team_users_after() -> GetTeamUsersAfterStmt
+{ GetTeamUsersAfterStmt(cornucopia_async::private::Stmt::new("SELECT 
+    u.id, 
+    u.email,
+    u.created_at::TIMESTAMPTZ AS created_at
+FROM users u
+JOIN team_memberships m ON m.user_id = u.id
+WHERE m.team_id = $1 AND u.id > $2
+ORDER BY u.id")) } pub struct
+GetTeamUsersAfterStmt(cornucopia_async::private::Stmt); impl GetTeamUsersAfterStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+team_id: &'a i32,id: &'a i32,) -> UserQuery<'a,C, User,
+2>
+{
+    UserQuery
+    {
+        client, params: [team_id,id,], stmt: &mut self.0, extractor:
+        |row| { UserBorrowed { id: row.get(0),email: row.get(1),created_at: row.get(2),} }, mapper: |it| { <User>::from(it) },
+    }
+} }impl <'a, C: GenericClient,> cornucopia_async::Params<'a,
+GetTeamUsersAfterParams<>, UserQuery<'a, C, User,
+2>, C> for GetTeamUsersAfterStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    GetTeamUsersAfterParams<>) -> UserQuery<'a, C,
+    User, 2>
+    { self.bind(client, &params.team_id,&params.id,) }
+}pub fn search_team_users() -> SearchTeamUsersStmt
+{ SearchTeamUsersStmt(cornucopia_async::private::Stmt::new("SELECT 
+    u.id, 
+    u.email,
+    u.created_at::TIMESTAMPTZ AS created_at
+FROM users u
+JOIN team_memberships m ON m.user_id = u.id
+WHERE m.team_id = $1
+    AND ($2::TEXT IS NULL OR u.email ILIKE '%' || $2 || '%')
+ORDER BY u.id")) } pub struct
+SearchTeamUsersStmt(cornucopia_async::private::Stmt); impl SearchTeamUsersStmt
+{ pub fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::StringSql,>(&'a mut self, client: &'a  C,
+team_id: &'a i32,email: &'a Option<T1>,) -> UserQuery<'a,C, User,
+2>
+{
+    UserQuery
+    {
+        client, params: [team_id,email,], stmt: &mut self.0, extractor:
+        |row| { UserBorrowed { id: row.get(0),email: row.get(1),created_at: row.get(2),} }, mapper: |it| { <User>::from(it) },
+    }
+} }impl <'a, C: GenericClient,T1: cornucopia_async::StringSql,> cornucopia_async::Params<'a,
+SearchTeamUsersParams<T1,>, UserQuery<'a, C, User,
+2>, C> for SearchTeamUsersStmt
+{
+    fn
+    params(&'a mut self, client: &'a  C, params: &'a
+    SearchTeamUsersParams<T1,>) -> UserQuery<'a, C,
+    User, 2>
+    { self.bind(client, &params.team_id,&params.email,) }
+}pub fn stage_user_import() -> StageUserImportStmt
+{ StageUserImportStmt(cornucopia_async::private::Stmt::new("INSERT INTO user_import (email)
+SELECT unnest($1::VARCHAR[])")) } pub struct
+StageUserImportStmt(cornucopia_async::private::Stmt); impl StageUserImportStmt
+{ pub async fn bind<'a, C:
+GenericClient,T1:
+cornucopia_async::StringSql,T2:
+cornucopia_async::ArraySql<Item = T1>,>(&'a mut self, client: &'a  C,
+emails: &'a T2,) -> Result<u64, tokio_postgres::Error>
+{
+    let stmt = self.0.prepare(client).await?;
+    client.execute(stmt, &[emails,]).await
+} }pub fn create_imported_users() -> CreateImportedUsersStmt
+{ CreateImportedUsersStmt(cornucopia_async::private::Stmt::new("INSERT INTO users (email)
+SELECT DISTINCT email FROM user_import
+ON CONFLICT (email) DO NOTHING
+RETURNING id, email, created_at::TIMESTAMPTZ AS created_at")) } pub struct
+CreateImportedUsersStmt(cornucopia_async::private::Stmt); impl CreateImportedUsersStmt
+{ pub fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+) -> UserQuery<'a,C, User,
+0>
+{
+    UserQuery
+    {
+        client, params: [], stmt: &mut self.0, extractor:
+        |row| { UserBorrowed { id: row.get(0),email: row.get(1),created_at: row.get(2),} }, mapper: |it| { <User>::from(it) },
+    }
+} }pub fn clear_user_import() -> ClearUserImportStmt
+{ ClearUserImportStmt(cornucopia_async::private::Stmt::new("DELETE FROM user_import")) } pub struct
+ClearUserImportStmt(cornucopia_async::private::Stmt); impl ClearUserImportStmt
+{ pub async fn bind<'a, C:
+GenericClient,>(&'a mut self, client: &'a  C,
+) -> Result<u64, tokio_postgres::Error>
+{
+    let stmt = self.0.prepare(client).await?;
+    client.execute(stmt, &[]).await
+} }}}
\ No newline at end of file
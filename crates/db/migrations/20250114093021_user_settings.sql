@@ -0,0 +1,23 @@
+-- migrate:up
+CREATE TYPE theme AS ENUM (
+    'System',
+    'Light',
+    'Dark'
+);
+COMMENT ON TYPE theme IS 'The colour scheme a user wants the application rendered in.';
+
+CREATE TABLE user_settings (
+    user_id INT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    display_name VARCHAR,
+    timezone VARCHAR NOT NULL DEFAULT 'UTC',
+    theme theme NOT NULL DEFAULT 'System',
+    notify_product_updates BOOLEAN NOT NULL DEFAULT false,
+    notify_security_alerts BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+COMMENT ON TABLE user_settings IS 'Per user preferences. Users without a row get the column defaults.';
+
+-- migrate:down
+DROP TABLE user_settings;
+DROP TYPE theme;
@@ -0,0 +1,22 @@
+-- migrate:up
+CREATE FUNCTION notify_users_changed() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify(
+        'users_changed',
+        json_build_object(
+            'operation', TG_OP,
+            'id', COALESCE(NEW.id, OLD.id)
+        )::text
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+COMMENT ON FUNCTION notify_users_changed IS 'Tells anyone listening on users_changed which user was inserted, updated or deleted.';
+
+CREATE TRIGGER users_changed
+    AFTER INSERT OR UPDATE OR DELETE ON users
+    FOR EACH ROW EXECUTE FUNCTION notify_users_changed();
+
+-- migrate:down
+DROP TRIGGER users_changed ON users;
+DROP FUNCTION notify_users_changed;
@@ -0,0 +1,29 @@
+-- migrate:up
+CREATE TYPE job_status AS ENUM (
+    'Pending',
+    'Running',
+    'Completed',
+    'Dead'
+);
+COMMENT ON TYPE job_status IS 'Jobs wait as Pending (including between retries) and become Dead once they run out of attempts.';
+
+CREATE TABLE jobs (
+    id SERIAL PRIMARY KEY,
+    kind VARCHAR NOT NULL,
+    payload JSONB NOT NULL,
+    status job_status NOT NULL DEFAULT 'Pending',
+    attempts INT NOT NULL DEFAULT 0,
+    max_attempts INT NOT NULL DEFAULT 5,
+    run_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    locked_at TIMESTAMPTZ,
+    last_error VARCHAR,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+COMMENT ON TABLE jobs IS 'Work to run outside of the request cycle. Workers claim rows with FOR UPDATE SKIP LOCKED.';
+
+CREATE INDEX jobs_ready_idx ON jobs (run_at, id) WHERE status = 'Pending';
+
+-- migrate:down
+DROP TABLE jobs;
+DROP TYPE job_status;
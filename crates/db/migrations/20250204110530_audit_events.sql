@@ -0,0 +1,21 @@
+-- migrate:up
+CREATE TABLE audit_events (
+    id SERIAL PRIMARY KEY,
+    actor_id INT REFERENCES users(id) ON DELETE SET NULL,
+    action VARCHAR NOT NULL,
+    target_type VARCHAR NOT NULL,
+    target_id INT,
+    diff JSONB NOT NULL DEFAULT '{}',
+    ip_address INET,
+    user_agent VARCHAR,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+COMMENT ON TABLE audit_events IS 'Who did what to which record. Written in the same transaction as the change.';
+COMMENT ON COLUMN audit_events.diff IS 'Changed fields as {"field": {"old": .., "new": ..}}.';
+
+CREATE INDEX audit_events_actor_idx ON audit_events (actor_id, created_at);
+CREATE INDEX audit_events_target_idx ON audit_events (target_type, target_id, created_at);
+CREATE INDEX audit_events_created_at_idx ON audit_events (created_at);
+
+-- migrate:down
+DROP TABLE audit_events;
@@ -0,0 +1,64 @@
+-- migrate:up
+CREATE TYPE team_role AS ENUM (
+    'Owner',
+    'Member'
+);
+
+CREATE TABLE teams (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR NOT NULL,
+    created_by INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+COMMENT ON TABLE teams IS 'The tenant. Anything a customer owns hangs off a team.';
+
+CREATE INDEX teams_created_by_idx ON teams (created_by);
+
+CREATE TABLE team_memberships (
+    team_id INT NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+    user_id INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role team_role NOT NULL DEFAULT 'Member',
+    joined_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (team_id, user_id)
+);
+
+CREATE INDEX team_memberships_user_idx ON team_memberships (user_id);
+
+CREATE TABLE invitations (
+    id SERIAL PRIMARY KEY,
+    team_id INT NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+    email VARCHAR NOT NULL,
+    role team_role NOT NULL DEFAULT 'Member',
+    invited_by INT REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    accepted_at TIMESTAMPTZ,
+    UNIQUE (team_id, email)
+);
+COMMENT ON TABLE invitations IS 'Someone asked to join a team. Accepted by signing in with the invited email.';
+
+-- Tenant scoped tables
+ALTER TABLE audit_events ADD COLUMN team_id INT REFERENCES teams(id) ON DELETE CASCADE;
+CREATE INDEX audit_events_team_idx ON audit_events (team_id, created_at);
+
+ALTER TABLE jobs ADD COLUMN team_id INT REFERENCES teams(id) ON DELETE CASCADE;
+CREATE INDEX jobs_team_idx ON jobs (team_id);
+
+-- Everyone who already has an account gets a team of their own.
+WITH personal_teams AS (
+    INSERT INTO teams (name, created_by)
+    SELECT email || '''s team', id FROM users
+    RETURNING id, created_by
+)
+INSERT INTO team_memberships (team_id, user_id, role)
+SELECT id, created_by, 'Owner' FROM personal_teams;
+
+-- migrate:down
+DROP INDEX jobs_team_idx;
+ALTER TABLE jobs DROP COLUMN team_id;
+DROP INDEX audit_events_team_idx;
+ALTER TABLE audit_events DROP COLUMN team_id;
+DROP TABLE invitations;
+DROP TABLE team_memberships;
+DROP TABLE teams;
+DROP TYPE team_role;
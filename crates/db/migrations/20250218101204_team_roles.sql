@@ -0,0 +1,26 @@
+-- migrate:up
+-- New enum values can't be used in the transaction that adds them, so
+-- the permissions that refer to them are in the next migration.
+ALTER TYPE team_role RENAME VALUE 'Owner' TO 'Administrator';
+ALTER TYPE team_role ADD VALUE 'ReadOnly';
+
+-- migrate:down
+UPDATE team_memberships SET role = 'Member' WHERE role = 'ReadOnly';
+UPDATE invitations SET role = 'Member' WHERE role = 'ReadOnly';
+ALTER TYPE team_role RENAME VALUE 'Administrator' TO 'Owner';
+
+-- Postgres can't drop an enum value, so swap the type out.
+ALTER TYPE team_role RENAME TO team_role_old;
+CREATE TYPE team_role AS ENUM (
+    'Owner',
+    'Member'
+);
+ALTER TABLE team_memberships
+    ALTER COLUMN role DROP DEFAULT,
+    ALTER COLUMN role TYPE team_role USING role::TEXT::team_role,
+    ALTER COLUMN role SET DEFAULT 'Member';
+ALTER TABLE invitations
+    ALTER COLUMN role DROP DEFAULT,
+    ALTER COLUMN role TYPE team_role USING role::TEXT::team_role,
+    ALTER COLUMN role SET DEFAULT 'Member';
+DROP TYPE team_role_old;
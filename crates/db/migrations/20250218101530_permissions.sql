@@ -0,0 +1,34 @@
+-- migrate:up
+CREATE TYPE permission AS ENUM (
+    'ViewUsers',
+    'CreateUsers',
+    'ViewTeam',
+    'ManageTeam',
+    'ViewJobs',
+    'RetryJobs',
+    'ViewAuditLog'
+);
+COMMENT ON TYPE permission IS 'Something a handler checks before it runs. Add a value here and grant it below.';
+
+CREATE TABLE role_permissions (
+    role team_role NOT NULL,
+    permission permission NOT NULL,
+    PRIMARY KEY (role, permission)
+);
+COMMENT ON TABLE role_permissions IS 'What each team role is allowed to do.';
+
+INSERT INTO role_permissions (role, permission)
+SELECT 'Administrator', unnest(enum_range(NULL::permission));
+
+INSERT INTO role_permissions (role, permission) VALUES
+    ('Member', 'ViewUsers'),
+    ('Member', 'CreateUsers'),
+    ('Member', 'ViewTeam'),
+    ('Member', 'ViewJobs'),
+    ('ReadOnly', 'ViewUsers'),
+    ('ReadOnly', 'ViewTeam'),
+    ('ReadOnly', 'ViewJobs');
+
+-- migrate:down
+DROP TABLE role_permissions;
+DROP TYPE permission;
@@ -0,0 +1,88 @@
+-- migrate:up
+-- Requests switch to this role with SET LOCAL ROLE once they know which
+-- user and team they're acting for. It can't bypass RLS, so the policies
+-- below decide which rows it sees. Background work like the job worker
+-- keeps using the login role.
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT FROM pg_roles WHERE rolname = 'application') THEN
+        CREATE ROLE application NOLOGIN NOBYPASSRLS;
+    END IF;
+END
+$$;
+GRANT application TO CURRENT_USER;
+
+GRANT USAGE ON SCHEMA public TO application;
+GRANT SELECT, INSERT, UPDATE, DELETE ON ALL TABLES IN SCHEMA public TO application;
+GRANT USAGE, SELECT ON ALL SEQUENCES IN SCHEMA public TO application;
+ALTER DEFAULT PRIVILEGES IN SCHEMA public GRANT SELECT, INSERT, UPDATE, DELETE ON TABLES TO application;
+ALTER DEFAULT PRIVILEGES IN SCHEMA public GRANT USAGE, SELECT ON SEQUENCES TO application;
+
+-- Set with SET LOCAL (or set_config(.., true)) at the start of a transaction.
+-- Unset means NULL, and NULL never matches a policy.
+CREATE FUNCTION current_app_user() RETURNS INT
+LANGUAGE sql STABLE
+AS $$ SELECT NULLIF(current_setting('app.user_id', true), '')::INT $$;
+
+CREATE FUNCTION current_app_team() RETURNS INT
+LANGUAGE sql STABLE
+AS $$ SELECT NULLIF(current_setting('app.team_id', true), '')::INT $$;
+
+ALTER TABLE teams ENABLE ROW LEVEL SECURITY;
+CREATE POLICY tenant_policy ON teams TO application
+USING (id = current_app_team());
+
+ALTER TABLE team_memberships ENABLE ROW LEVEL SECURITY;
+CREATE POLICY tenant_policy ON team_memberships TO application
+USING (team_id = current_app_team() OR user_id = current_app_user())
+WITH CHECK (team_id = current_app_team());
+
+ALTER TABLE invitations ENABLE ROW LEVEL SECURITY;
+CREATE POLICY tenant_policy ON invitations TO application
+USING (team_id = current_app_team());
+
+ALTER TABLE audit_events ENABLE ROW LEVEL SECURITY;
+CREATE POLICY tenant_policy ON audit_events TO application
+USING (team_id = current_app_team());
+
+ALTER TABLE jobs ENABLE ROW LEVEL SECURITY;
+CREATE POLICY tenant_policy ON jobs TO application
+USING (team_id = current_app_team());
+
+-- You can see yourself and the other members of the current team.
+ALTER TABLE users ENABLE ROW LEVEL SECURITY;
+CREATE POLICY tenant_policy ON users TO application
+USING (
+    id = current_app_user()
+    OR id IN (SELECT user_id FROM team_memberships WHERE team_id = current_app_team())
+);
+
+ALTER TABLE user_settings ENABLE ROW LEVEL SECURITY;
+CREATE POLICY tenant_policy ON user_settings TO application
+USING (user_id = current_app_user());
+
+-- migrate:down
+DROP POLICY tenant_policy ON user_settings;
+ALTER TABLE user_settings DISABLE ROW LEVEL SECURITY;
+DROP POLICY tenant_policy ON users;
+ALTER TABLE users DISABLE ROW LEVEL SECURITY;
+DROP POLICY tenant_policy ON jobs;
+ALTER TABLE jobs DISABLE ROW LEVEL SECURITY;
+DROP POLICY tenant_policy ON audit_events;
+ALTER TABLE audit_events DISABLE ROW LEVEL SECURITY;
+DROP POLICY tenant_policy ON invitations;
+ALTER TABLE invitations DISABLE ROW LEVEL SECURITY;
+DROP POLICY tenant_policy ON team_memberships;
+ALTER TABLE team_memberships DISABLE ROW LEVEL SECURITY;
+DROP POLICY tenant_policy ON teams;
+ALTER TABLE teams DISABLE ROW LEVEL SECURITY;
+
+DROP FUNCTION current_app_team();
+DROP FUNCTION current_app_user();
+
+ALTER DEFAULT PRIVILEGES IN SCHEMA public REVOKE USAGE, SELECT ON SEQUENCES FROM application;
+ALTER DEFAULT PRIVILEGES IN SCHEMA public REVOKE SELECT, INSERT, UPDATE, DELETE ON TABLES FROM application;
+REVOKE ALL ON ALL SEQUENCES IN SCHEMA public FROM application;
+REVOKE ALL ON ALL TABLES IN SCHEMA public FROM application;
+REVOKE USAGE ON SCHEMA public FROM application;
+-- The role is shared by every database in the cluster so we leave it.
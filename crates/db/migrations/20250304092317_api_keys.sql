@@ -0,0 +1,25 @@
+-- migrate:up
+-- Keys belong to a user in a team. Only a SHA-256 of the key is stored, the
+-- prefix is kept in the clear so people can tell their keys apart.
+CREATE TABLE api_keys (
+    id SERIAL PRIMARY KEY,
+    team_id INT NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+    user_id INT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR NOT NULL,
+    prefix VARCHAR NOT NULL,
+    key_hash BYTEA NOT NULL UNIQUE,
+    -- A key can only ever do what its scopes and its owner's role both allow.
+    scopes permission[] NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX api_keys_team_user ON api_keys (team_id, user_id);
+
+ALTER TABLE api_keys ENABLE ROW LEVEL SECURITY;
+CREATE POLICY tenant_policy ON api_keys TO application
+USING (team_id = current_app_team() AND user_id = current_app_user());
+
+-- migrate:down
+DROP TABLE api_keys;
@@ -0,0 +1,6 @@
+-- migrate:up
+ALTER TABLE user_settings ADD COLUMN locale VARCHAR;
+COMMENT ON COLUMN user_settings.locale IS 'A locale code like ''de''. NULL follows the browser''s Accept-Language.';
+
+-- migrate:down
+ALTER TABLE user_settings DROP COLUMN locale;
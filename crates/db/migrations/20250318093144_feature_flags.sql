@@ -0,0 +1,38 @@
+-- migrate:up
+-- Flags are global rather than per team, so there's no row level security,
+-- only site admins can reach the page that changes them.
+CREATE TABLE feature_flags (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR NOT NULL UNIQUE,
+    description VARCHAR NOT NULL DEFAULT '',
+    -- On for everyone, whatever the rest says.
+    enabled BOOLEAN NOT NULL DEFAULT false,
+    -- Otherwise on for this share of users, and always for the ones listed.
+    rollout_percentage INT NOT NULL DEFAULT 0
+        CHECK (rollout_percentage BETWEEN 0 AND 100),
+    user_ids INT[] NOT NULL DEFAULT '{}',
+    team_ids INT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE FUNCTION notify_feature_flags_changed() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify(
+        'feature_flags_changed',
+        json_build_object('operation', TG_OP)::text
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+COMMENT ON FUNCTION notify_feature_flags_changed IS 'Tells every server on feature_flags_changed to reload its flags.';
+
+-- There are only ever a handful of flags, so servers reload them all
+-- rather than being told which one changed.
+CREATE TRIGGER feature_flags_changed
+    AFTER INSERT OR UPDATE OR DELETE ON feature_flags
+    FOR EACH STATEMENT EXECUTE FUNCTION notify_feature_flags_changed();
+
+-- migrate:down
+DROP TABLE feature_flags;
+DROP FUNCTION notify_feature_flags_changed;
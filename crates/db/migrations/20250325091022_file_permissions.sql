@@ -0,0 +1,9 @@
+-- migrate:up
+-- A value added to an enum can't be used until it's committed, so these
+-- are granted in the next migration.
+ALTER TYPE permission ADD VALUE 'ViewFiles';
+ALTER TYPE permission ADD VALUE 'UploadFiles';
+
+-- migrate:down
+-- Postgres can't drop a value from an enum, the next migration's down
+-- removes every use of them.
@@ -0,0 +1,42 @@
+-- migrate:up
+INSERT INTO role_permissions (role, permission) VALUES
+    ('Administrator', 'ViewFiles'),
+    ('Administrator', 'UploadFiles'),
+    ('Member', 'ViewFiles'),
+    ('Member', 'UploadFiles'),
+    ('ReadOnly', 'ViewFiles');
+
+CREATE TYPE file_kind AS ENUM ('Avatar', 'Attachment');
+
+-- What we know about each uploaded file. The contents are kept by the
+-- storage crate under storage_key, in a directory or an S3 bucket.
+CREATE TABLE files (
+    id SERIAL PRIMARY KEY,
+    team_id INT NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+    user_id INT REFERENCES users(id) ON DELETE SET NULL,
+    kind file_kind NOT NULL,
+    storage_key VARCHAR NOT NULL UNIQUE,
+    file_name VARCHAR NOT NULL,
+    -- What the contents turned out to be, not what the browser said.
+    content_type VARCHAR NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    sha256 BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX files_team_kind ON files (team_id, kind);
+CREATE INDEX files_user_kind ON files (user_id, kind);
+
+-- Avatars belong to the user wherever they are, attachments to the team.
+ALTER TABLE files ENABLE ROW LEVEL SECURITY;
+CREATE POLICY tenant_policy ON files TO application
+USING (
+    team_id = current_app_team()
+    OR (kind = 'Avatar' AND user_id = current_app_user())
+)
+WITH CHECK (team_id = current_app_team());
+
+-- migrate:down
+DROP TABLE files;
+DROP TYPE file_kind;
+DELETE FROM role_permissions WHERE permission IN ('ViewFiles', 'UploadFiles');
@@ -0,0 +1,13 @@
+-- migrate:up
+-- The web server logs in as `application` for requests, rather than as the
+-- owner of the tables, so row level security applies to every query they
+-- run. SET LOCAL ROLE alone left it up to each handler, and RESET ROLE got
+-- out of it. Only migrations, background work and the lookups that find
+-- out who a request is for use the owner, see db::PrivilegedPool.
+--
+-- Give it a password outside of migrations:
+--   ALTER ROLE application PASSWORD '...';
+ALTER ROLE application LOGIN;
+
+-- migrate:down
+ALTER ROLE application NOLOGIN;
@@ -0,0 +1,9 @@
+-- migrate:up
+-- Display names are personal data, so they're kept encrypted with
+-- db::encryption, in the context user_settings.encrypted_display_name:user_id.
+-- Names saved before this stay in display_name until they're next saved,
+-- which empties it.
+ALTER TABLE user_settings ADD COLUMN encrypted_display_name BYTEA;
+
+-- migrate:down
+ALTER TABLE user_settings DROP COLUMN encrypted_display_name;
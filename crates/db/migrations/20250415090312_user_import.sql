@@ -0,0 +1,12 @@
+-- migrate:up
+-- Where db::import stages the emails of a bulk import, so the queries that
+-- use them can be checked by cornucopia like any other. An import fills it
+-- and empties it again in one transaction, so no other transaction ever
+-- sees its rows.
+CREATE UNLOGGED TABLE user_import (
+    email VARCHAR NOT NULL
+);
+COMMENT ON TABLE user_import IS 'Emails of a bulk import in progress, only ever filled inside the importing transaction.';
+
+-- migrate:down
+DROP TABLE user_import;
@@ -0,0 +1,48 @@
+--: ApiKey(last_used_at?)
+--: ApiKeyOwner()
+
+--! create_api_key
+INSERT INTO api_keys (team_id, user_id, name, prefix, key_hash, scopes)
+VALUES (:team_id, :user_id, :name, :prefix, :key_hash, :scopes)
+RETURNING id;
+
+--! get_api_keys : ApiKey
+SELECT
+    id,
+    name,
+    prefix,
+    scopes,
+    created_at,
+    last_used_at
+FROM api_keys
+WHERE team_id = :team_id AND user_id = :user_id AND revoked_at IS NULL
+ORDER BY created_at DESC;
+
+--! revoke_api_key
+UPDATE api_keys
+SET revoked_at = NOW()
+WHERE id = :id AND team_id = :team_id AND user_id = :user_id AND revoked_at IS NULL
+RETURNING id;
+
+-- Runs before we know the tenant, so it's the only query that can see every
+-- key. The permissions are the key's scopes that the owner's role still has,
+-- and owners who have left the team no longer match.
+--! get_api_key_owner : ApiKeyOwner
+SELECT
+    k.id,
+    k.team_id,
+    k.user_id,
+    ARRAY(
+        SELECT scope
+        FROM unnest(k.scopes) AS scope
+        JOIN role_permissions rp ON rp.permission = scope AND rp.role = m.role
+        ORDER BY scope
+    ) AS permissions
+FROM api_keys k
+JOIN team_memberships m ON m.team_id = k.team_id AND m.user_id = k.user_id
+WHERE k.key_hash = :key_hash AND k.revoked_at IS NULL;
+
+--! touch_api_key
+UPDATE api_keys
+SET last_used_at = NOW()
+WHERE id = :id;
@@ -0,0 +1,50 @@
+--: AuditLogEntry(actor_email?, target_id?, ip_address?, user_agent?)
+
+--! insert_audit_event(team_id?, actor_id?, target_id?, ip_address?, user_agent?)
+INSERT INTO audit_events (
+    team_id,
+    actor_id,
+    action,
+    target_type,
+    target_id,
+    diff,
+    ip_address,
+    user_agent
+)
+VALUES (
+    :team_id,
+    :actor_id,
+    :action,
+    :target_type,
+    :target_id,
+    :diff,
+    :ip_address,
+    :user_agent
+);
+
+-- Dates are whole days in the users timezone, `to` is inclusive.
+--! get_audit_events(actor?, target_type?, target_id?, from?, to?) : AuditLogEntry
+SELECT
+    a.id,
+    u.email AS actor_email,
+    a.action,
+    a.target_type,
+    a.target_id,
+    a.diff,
+    a.ip_address,
+    a.user_agent,
+    a.created_at
+FROM audit_events a
+LEFT JOIN users u ON u.id = a.actor_id
+WHERE
+    a.team_id = :team_id
+    AND (:actor::VARCHAR IS NULL OR u.email ILIKE '%' || :actor || '%')
+    AND (:target_type::VARCHAR IS NULL OR a.target_type = :target_type)
+    AND (:target_id::INT IS NULL OR a.target_id = :target_id)
+    AND (:from::DATE IS NULL OR a.created_at >= (:from::DATE)::TIMESTAMP AT TIME ZONE :timezone)
+    AND (:to::DATE IS NULL OR a.created_at < (:to::DATE + 1)::TIMESTAMP AT TIME ZONE :timezone)
+ORDER BY a.created_at DESC, a.id DESC
+LIMIT 200;
+
+--! get_audit_target_types
+SELECT DISTINCT target_type FROM audit_events WHERE team_id = :team_id ORDER BY target_type;
@@ -0,0 +1,60 @@
+--: FeatureFlag()
+
+--! get_feature_flags : FeatureFlag
+SELECT
+    id,
+    name,
+    description,
+    enabled,
+    rollout_percentage,
+    user_ids,
+    team_ids,
+    updated_at
+FROM feature_flags
+ORDER BY name;
+
+--! get_feature_flag : FeatureFlag
+SELECT
+    id,
+    name,
+    description,
+    enabled,
+    rollout_percentage,
+    user_ids,
+    team_ids,
+    updated_at
+FROM feature_flags
+WHERE id = :id;
+
+--! create_feature_flag : FeatureFlag
+INSERT INTO feature_flags (name, description)
+VALUES (:name, :description)
+ON CONFLICT (name) DO NOTHING
+RETURNING
+    id,
+    name,
+    description,
+    enabled,
+    rollout_percentage,
+    user_ids,
+    team_ids,
+    updated_at;
+
+--! update_feature_flag : FeatureFlag
+UPDATE feature_flags
+SET
+    enabled = :enabled,
+    rollout_percentage = :rollout_percentage,
+    user_ids = :user_ids,
+    team_ids = :team_ids,
+    updated_at = NOW()
+WHERE id = :id
+RETURNING
+    id,
+    name,
+    description,
+    enabled,
+    rollout_percentage,
+    user_ids,
+    team_ids,
+    updated_at;
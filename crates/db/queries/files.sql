@@ -0,0 +1,54 @@
+--: File(uploaded_by?)
+
+--! create_file(user_id?)
+INSERT INTO files (
+    team_id,
+    user_id,
+    kind,
+    storage_key,
+    file_name,
+    content_type,
+    size_bytes,
+    sha256
+)
+VALUES (
+    :team_id,
+    :user_id,
+    :kind,
+    :storage_key,
+    :file_name,
+    :content_type,
+    :size_bytes,
+    :sha256
+)
+RETURNING id;
+
+--! get_files : File
+SELECT
+    f.id,
+    f.file_name,
+    f.content_type,
+    f.size_bytes,
+    u.email AS uploaded_by,
+    f.created_at
+FROM files f
+LEFT JOIN users u ON u.id = f.user_id
+WHERE f.team_id = :team_id AND f.kind = 'Attachment'
+ORDER BY f.created_at DESC, f.id DESC;
+
+--! get_avatar
+SELECT id
+FROM files
+WHERE user_id = :user_id AND kind = 'Avatar'
+ORDER BY created_at DESC, id DESC
+LIMIT 1;
+
+-- Downloads are checked by their signature rather than by who's asking,
+-- so like get_api_key_owner this runs without a tenant and sees every file.
+--! get_file_download
+SELECT
+    storage_key,
+    file_name,
+    content_type
+FROM files
+WHERE id = :id;
@@ -0,0 +1,89 @@
+--: ClaimedJob()
+--: FailedJob(last_error?)
+
+--! enqueue_job(team_id?)
+INSERT INTO jobs (team_id, kind, payload, max_attempts)
+VALUES (:team_id, :kind, :payload, :max_attempts)
+RETURNING id;
+
+-- Lock the next job that's due without waiting for other workers.
+--! claim_job : ClaimedJob
+UPDATE jobs
+SET
+    status = 'Running',
+    attempts = attempts + 1,
+    locked_at = NOW(),
+    updated_at = NOW()
+WHERE id = (
+    SELECT id
+    FROM jobs
+    WHERE status = 'Pending' AND run_at <= NOW()
+    ORDER BY run_at, id
+    FOR UPDATE SKIP LOCKED
+    LIMIT 1
+)
+RETURNING id, kind, payload, attempts, max_attempts;
+
+--! complete_job
+UPDATE jobs
+SET
+    status = 'Completed',
+    locked_at = NULL,
+    last_error = NULL,
+    updated_at = NOW()
+WHERE id = :id;
+
+--! retry_job
+UPDATE jobs
+SET
+    status = 'Pending',
+    locked_at = NULL,
+    last_error = :last_error,
+    run_at = NOW() + make_interval(secs => :delay_seconds),
+    updated_at = NOW()
+WHERE id = :id;
+
+--! dead_letter_job
+UPDATE jobs
+SET
+    status = 'Dead',
+    locked_at = NULL,
+    last_error = :last_error,
+    updated_at = NOW()
+WHERE id = :id;
+
+-- Jobs stay Running if a worker dies half way through, so put them back.
+--! release_stale_jobs
+UPDATE jobs
+SET
+    status = 'Pending',
+    locked_at = NULL,
+    updated_at = NOW()
+WHERE status = 'Running' AND locked_at < NOW() - make_interval(secs => :stale_after_seconds);
+
+--! get_failed_jobs : FailedJob
+SELECT
+    id,
+    kind,
+    status,
+    attempts,
+    max_attempts,
+    last_error,
+    run_at,
+    updated_at
+FROM jobs
+WHERE team_id = :team_id
+    AND (status = 'Dead' OR (status = 'Pending' AND last_error IS NOT NULL))
+ORDER BY updated_at DESC
+LIMIT 100;
+
+-- Give a dead job a fresh set of attempts.
+--! requeue_job
+UPDATE jobs
+SET
+    status = 'Pending',
+    attempts = 0,
+    run_at = NOW(),
+    updated_at = NOW()
+WHERE id = :id AND team_id = :team_id AND status = 'Dead'
+RETURNING id;
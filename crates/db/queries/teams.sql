@@ -0,0 +1,111 @@
+--: Team()
+--: TeamMember()
+--: Invitation()
+--: InvitationForUser()
+--: AcceptedInvitation()
+
+-- Every team the user belongs to. Handlers look the team in the URL up
+-- in here, so a user can only ever reach their own teams.
+--! get_teams : Team
+SELECT
+    t.id,
+    t.name,
+    m.role
+FROM teams t
+JOIN team_memberships m ON m.team_id = t.id
+WHERE m.user_id = :user_id
+ORDER BY m.joined_at, t.id;
+
+--! create_team
+INSERT INTO teams (name, created_by)
+VALUES (:name, :created_by)
+RETURNING id;
+
+--! add_team_member
+INSERT INTO team_memberships (team_id, user_id, role)
+VALUES (:team_id, :user_id, :role)
+ON CONFLICT (team_id, user_id) DO NOTHING;
+
+--! get_team_members : TeamMember
+SELECT
+    u.id,
+    u.email,
+    m.role,
+    m.joined_at
+FROM team_memberships m
+JOIN users u ON u.id = m.user_id
+WHERE m.team_id = :team_id
+ORDER BY m.joined_at, u.id;
+
+-- Inviting the same email again refreshes the invitation.
+--! create_invitation
+INSERT INTO invitations (team_id, email, role, invited_by)
+VALUES (:team_id, :email, :role, :invited_by)
+ON CONFLICT (team_id, email) DO UPDATE
+SET
+    role = EXCLUDED.role,
+    invited_by = EXCLUDED.invited_by,
+    created_at = NOW(),
+    accepted_at = NULL
+RETURNING id;
+
+--! get_invitations : Invitation
+SELECT
+    id,
+    email,
+    role,
+    created_at
+FROM invitations
+WHERE team_id = :team_id AND accepted_at IS NULL
+ORDER BY created_at DESC;
+
+--! get_invitations_for_email : InvitationForUser
+SELECT
+    i.id,
+    i.team_id,
+    t.name AS team_name,
+    i.role,
+    i.created_at
+FROM invitations i
+JOIN teams t ON t.id = i.team_id
+WHERE LOWER(i.email) = LOWER(:email) AND i.accepted_at IS NULL
+ORDER BY i.created_at DESC;
+
+-- Only the person who was invited can accept.
+--! accept_invitation : AcceptedInvitation
+UPDATE invitations
+SET accepted_at = NOW()
+WHERE id = :id AND LOWER(email) = LOWER(:email) AND accepted_at IS NULL
+RETURNING team_id, role;
+
+-- What the user can do in the team, from their role.
+--! get_permissions
+SELECT rp.permission
+FROM role_permissions rp
+JOIN team_memberships m ON m.role = rp.role
+WHERE m.team_id = :team_id AND m.user_id = :user_id
+ORDER BY rp.permission;
+
+-- Only the users an import created join straight away.
+--! add_imported_members
+INSERT INTO team_memberships (team_id, user_id, role)
+SELECT :team_id, id, 'Member' FROM unnest(:user_ids::INT[]) AS id;
+
+-- Anyone in the import with an account already who isn't in the team.
+-- Like create_invitation, inviting someone again refreshes it.
+--! invite_imported_users
+INSERT INTO invitations (team_id, email, role, invited_by)
+SELECT :team_id, u.email, 'Member', :invited_by
+FROM users u
+JOIN (SELECT DISTINCT email FROM user_import) i ON i.email = u.email
+WHERE NOT EXISTS (
+    SELECT 1 FROM team_memberships m
+    WHERE m.team_id = :team_id AND m.user_id = u.id
+)
+ON CONFLICT (team_id, email) DO UPDATE
+SET
+    role = EXCLUDED.role,
+    invited_by = EXCLUDED.invited_by,
+    created_at = NOW(),
+    accepted_at = NULL
+RETURNING email;
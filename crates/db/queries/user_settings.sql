@@ -0,0 +1,54 @@
+--: UserSettings(display_name?, encrypted_display_name?, locale?, avatar_id?)
+
+-- display_name is only there for names saved before they were encrypted.
+
+--! get_user_settings : UserSettings
+SELECT
+    u.id AS user_id,
+    u.email,
+    s.display_name,
+    s.encrypted_display_name,
+    COALESCE(s.timezone, 'UTC') AS timezone,
+    COALESCE(s.theme, 'System') AS theme,
+    COALESCE(s.notify_product_updates, false) AS notify_product_updates,
+    COALESCE(s.notify_security_alerts, true) AS notify_security_alerts,
+    s.locale,
+    (
+        SELECT f.id
+        FROM files f
+        WHERE f.user_id = u.id AND f.kind = 'Avatar'
+        ORDER BY f.created_at DESC, f.id DESC
+        LIMIT 1
+    ) AS avatar_id
+FROM users u
+LEFT JOIN user_settings s ON s.user_id = u.id
+WHERE u.id = :user_id;
+
+--! upsert_user_settings(encrypted_display_name?, locale?)
+INSERT INTO user_settings (
+    user_id,
+    encrypted_display_name,
+    timezone,
+    theme,
+    notify_product_updates,
+    notify_security_alerts,
+    locale
+)
+VALUES (
+    :user_id,
+    :encrypted_display_name,
+    :timezone,
+    :theme,
+    :notify_product_updates,
+    :notify_security_alerts,
+    :locale
+)
+ON CONFLICT (user_id) DO UPDATE SET
+    display_name = NULL,
+    encrypted_display_name = EXCLUDED.encrypted_display_name,
+    timezone = EXCLUDED.timezone,
+    theme = EXCLUDED.theme,
+    notify_product_updates = EXCLUDED.notify_product_updates,
+    notify_security_alerts = EXCLUDED.notify_security_alerts,
+    locale = EXCLUDED.locale,
+    updated_at = NOW();
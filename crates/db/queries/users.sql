@@ -3,12 +3,110 @@
 --! get_users : User
 SELECT 
     id, 
-    email
-FROM users;
+    email,
+    created_at::TIMESTAMPTZ AS created_at
+FROM users
+ORDER BY id;
 
 -- 👇 add `create_user` query
---! create_user
+--! create_user : User
 INSERT INTO 
     users (email)
 VALUES
-    (:email);
\ No newline at end of file
+    (:email)
+RETURNING 
+    id, 
+    email,
+    created_at::TIMESTAMPTZ AS created_at;
+
+-- Returns nothing when the email already has an account.
+--! create_new_user : User
+INSERT INTO 
+    users (email)
+VALUES
+    (:email)
+ON CONFLICT (email) DO NOTHING
+RETURNING 
+    id, 
+    email,
+    created_at::TIMESTAMPTZ AS created_at;
+
+-- Returns nothing when another first login is creating the same user at
+-- the same time, its row isn't visible to this statement. Look them up
+-- again with get_user_by_email once it's returned.
+--! get_or_create_user : User
+WITH inserted AS (
+    INSERT INTO 
+        users (email)
+    VALUES
+        (:email)
+    ON CONFLICT (email) DO NOTHING
+    RETURNING id, email, created_at
+)
+SELECT id, email, created_at::TIMESTAMPTZ AS created_at FROM inserted
+UNION ALL
+SELECT id, email, created_at::TIMESTAMPTZ AS created_at FROM users WHERE email = :email;
+
+--! get_user_by_email : User
+SELECT
+    id,
+    email,
+    created_at::TIMESTAMPTZ AS created_at
+FROM users
+WHERE email = :email;
+
+--! get_team_users : User
+SELECT 
+    u.id, 
+    u.email,
+    u.created_at::TIMESTAMPTZ AS created_at
+FROM users u
+JOIN team_memberships m ON m.user_id = u.id
+WHERE m.team_id = :team_id
+ORDER BY u.id;
+
+--! get_team_user : User
+SELECT 
+    u.id, 
+    u.email,
+    u.created_at::TIMESTAMPTZ AS created_at
+FROM users u
+JOIN team_memberships m ON m.user_id = u.id
+WHERE m.team_id = :team_id AND u.id = :id;
+
+--! get_team_users_after : User
+SELECT 
+    u.id, 
+    u.email,
+    u.created_at::TIMESTAMPTZ AS created_at
+FROM users u
+JOIN team_memberships m ON m.user_id = u.id
+WHERE m.team_id = :team_id AND u.id > :id
+ORDER BY u.id;
+
+-- The users page and its CSV export, optionally narrowed down by email.
+--! search_team_users(email?) : User
+SELECT 
+    u.id, 
+    u.email,
+    u.created_at::TIMESTAMPTZ AS created_at
+FROM users u
+JOIN team_memberships m ON m.user_id = u.id
+WHERE m.team_id = :team_id
+    AND (:email::TEXT IS NULL OR u.email ILIKE '%' || :email || '%')
+ORDER BY u.id;
+
+-- A bulk import goes through user_import, see db::import. Big ones are
+-- copied in instead of using stage_user_import.
+--! stage_user_import
+INSERT INTO user_import (email)
+SELECT unnest(:emails::VARCHAR[]);
+
+--! create_imported_users : User
+INSERT INTO users (email)
+SELECT DISTINCT email FROM user_import
+ON CONFLICT (email) DO NOTHING
+RETURNING id, email, created_at::TIMESTAMPTZ AS created_at;
+
+--! clear_user_import
+DELETE FROM user_import;
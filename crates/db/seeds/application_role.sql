@@ -0,0 +1,3 @@
+-- So the web server can log in as `application` with the password in
+-- .devcontainer/.env. Production sets a real one.
+ALTER ROLE application PASSWORD 'testpassword';
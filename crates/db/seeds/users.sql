@@ -0,0 +1,6 @@
+-- A few users to try things out with. Safe to run more than once.
+INSERT INTO users(email) VALUES
+    ('test1@test1.com'),
+    ('test2@test1.com'),
+    ('test3@test1.com')
+ON CONFLICT (email) DO NOTHING;
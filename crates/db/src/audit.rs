@@ -0,0 +1,69 @@
+use std::net::IpAddr;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::{queries, GenericClient, TokioPostgresError};
+
+/// Something a user did that we need to keep a record of.
+#[derive(Debug, Default)]
+pub struct AuditEvent<'a> {
+    pub team_id: Option<i32>,
+    pub actor_id: Option<i32>,
+    pub action: &'a str,
+    pub target_type: &'a str,
+    pub target_id: Option<i32>,
+    pub diff: Value,
+    pub ip_address: Option<IpAddr>,
+    pub user_agent: Option<&'a str>,
+}
+
+/// Write an event to the audit log. Pass the transaction that makes the
+/// change so we never keep one without the other.
+pub async fn record<C: GenericClient>(
+    client: &C,
+    event: AuditEvent<'_>,
+) -> Result<(), TokioPostgresError> {
+    queries::audit_events::insert_audit_event()
+        .bind(
+            client,
+            &event.team_id,
+            &event.actor_id,
+            &event.action,
+            &event.target_type,
+            &event.target_id,
+            &event.diff,
+            &event.ip_address,
+            &event.user_agent,
+        )
+        .await?;
+    Ok(())
+}
+
+/// The fields that changed between two versions of a record, as
+/// `{"field": {"old": .., "new": ..}}`. Use `None` for a record that's
+/// being created or deleted.
+pub fn diff<T: Serialize>(old: Option<&T>, new: Option<&T>) -> Value {
+    let old = fields(old);
+    let new = fields(new);
+
+    let mut changes = Map::new();
+    for key in old.keys().chain(new.keys()) {
+        let before = old.get(key).unwrap_or(&Value::Null);
+        let after = new.get(key).unwrap_or(&Value::Null);
+        if before != after && !changes.contains_key(key) {
+            let mut change = Map::new();
+            change.insert("old".to_string(), before.clone());
+            change.insert("new".to_string(), after.clone());
+            changes.insert(key.clone(), Value::Object(change));
+        }
+    }
+    Value::Object(changes)
+}
+
+fn fields<T: Serialize>(record: Option<&T>) -> Map<String, Value> {
+    match record.map(serde_json::to_value) {
+        Some(Ok(Value::Object(fields))) => fields,
+        _ => Map::new(),
+    }
+}
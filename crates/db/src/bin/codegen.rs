@@ -0,0 +1,88 @@
+//! Regenerate `generated/cornucopia.rs` from the queries, see `db::codegen`.
+//!
+//! ```sh
+//! cargo run --bin codegen             # rewrite the file
+//! cargo run --bin codegen -- --check  # fail if it's out of date
+//! ```
+//!
+//! The schema comes from running the migrations on a scratch database,
+//! made on the server in `DATABASE_URL`, so whatever state the database
+//! itself is in doesn't leak into the code. It needs the `cornucopia` CLI.
+
+use std::path::Path;
+
+use db::{codegen, connect::with_database, migrations};
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL not set");
+    let check = std::env::args().skip(1).any(|arg| arg == "--check");
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+    let scratch = format!("codegen_{}", std::process::id());
+    let admin = migrations::connect(&database_url)
+        .await
+        .unwrap_or_else(|e| fail(e));
+    admin
+        .batch_execute(&format!("CREATE DATABASE {scratch}"))
+        .await
+        .unwrap_or_else(|e| fail(e));
+
+    let generated = generate(crate_dir, &with_database(&database_url, &scratch)).await;
+
+    let _ = admin
+        .batch_execute(&format!("DROP DATABASE IF EXISTS {scratch} WITH (FORCE)"))
+        .await;
+
+    let generated = generated.unwrap_or_else(|e| fail(e));
+    let path = crate_dir.join(codegen::GENERATED);
+    if check {
+        let committed = std::fs::read_to_string(&path).unwrap_or_default();
+        if committed != generated {
+            eprintln!(
+                "{} is out of date, run `cargo run --bin codegen`",
+                codegen::GENERATED
+            );
+            std::process::exit(1);
+        }
+        println!("{} is up to date", codegen::GENERATED);
+    } else {
+        std::fs::write(&path, generated).unwrap_or_else(|e| fail(e));
+        println!("Wrote {}", codegen::GENERATED);
+    }
+}
+
+async fn generate(
+    crate_dir: &Path,
+    scratch_url: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut client = migrations::connect(scratch_url).await?;
+    migrations::up(&mut client, false).await?;
+    drop(client);
+
+    let output_dir = std::env::temp_dir().join(format!("codegen_{}", std::process::id()));
+    std::fs::create_dir_all(&output_dir)?;
+    let output = output_dir.join("cornucopia.rs");
+    let cornucopia = std::process::Command::new("cornucopia")
+        .arg("-q")
+        .arg(crate_dir.join(codegen::QUERIES))
+        .arg("--serialize")
+        .arg("-d")
+        .arg(&output)
+        .arg("live")
+        .arg(scratch_url)
+        .output()?;
+    if !cornucopia.status.success() {
+        return Err(String::from_utf8_lossy(&cornucopia.stderr).into());
+    }
+
+    let code = std::fs::read_to_string(&output)?;
+    let _ = std::fs::remove_dir_all(&output_dir);
+    let fingerprint = codegen::fingerprint(crate_dir)?;
+    Ok(codegen::fingerprint_line(&fingerprint) + &code)
+}
+
+fn fail(e: impl std::fmt::Display) -> ! {
+    eprintln!("{e}");
+    std::process::exit(1);
+}
@@ -0,0 +1,86 @@
+//! Apply or roll back the migrations built into the `db` crate, without
+//! needing dbmate.
+//!
+//! ```sh
+//! cargo run --bin migrate -- up [--dry-run]
+//! cargo run --bin migrate -- down [--dry-run]
+//! cargo run --bin migrate -- status
+//! cargo run --bin migrate -- seed
+//! ```
+
+use db::migrations;
+
+const USAGE: &str = "Usage: migrate up|down|status|seed [--dry-run]";
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL not set");
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let dry_run = args.iter().any(|arg| arg == "--dry-run");
+    let command = args.iter().find(|arg| !arg.starts_with("--"));
+
+    let mut client = migrations::connect(&database_url)
+        .await
+        .unwrap_or_else(|e| fail(e));
+
+    match command.map(String::as_str) {
+        Some("up") => {
+            let applied = migrations::up(&mut client, dry_run)
+                .await
+                .unwrap_or_else(|e| fail(e));
+            let verb = if dry_run { "Would apply" } else { "Applied" };
+            for migration in &applied {
+                println!("{verb}: {}", migration.name);
+            }
+            if applied.is_empty() {
+                println!("Nothing to apply");
+            }
+        }
+        Some("down") => {
+            let rolled_back = migrations::down(&mut client, dry_run)
+                .await
+                .unwrap_or_else(|e| fail(e));
+            let verb = if dry_run {
+                "Would roll back"
+            } else {
+                "Rolled back"
+            };
+            match rolled_back {
+                Some(migration) => println!("{verb}: {}", migration.name),
+                None => println!("Nothing to roll back"),
+            }
+        }
+        Some("status") => {
+            let status = migrations::status(&client)
+                .await
+                .unwrap_or_else(|e| fail(e));
+            // Laid out like `dbmate status`.
+            for status in &status {
+                let mark = if status.applied { "X" } else { " " };
+                println!("[{mark}] {}", status.migration.name);
+            }
+            let applied = status.iter().filter(|status| status.applied).count();
+            println!();
+            println!("Applied: {applied}");
+            println!("Pending: {}", status.len() - applied);
+        }
+        Some("seed") => {
+            let seeded = migrations::seed(&mut client)
+                .await
+                .unwrap_or_else(|e| fail(e));
+            for name in seeded {
+                println!("Seeded: {name}");
+            }
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn fail(e: migrations::MigrationError) -> ! {
+    eprintln!("{e}");
+    std::process::exit(1);
+}
@@ -0,0 +1,47 @@
+//! Rotate encryption keys for one or more columns.
+//!
+//! Put the new key first in `ENCRYPTION_KEYS`, keep the old ones after it,
+//! deploy, then run
+//!
+//! ```sh
+//! cargo run --bin reencrypt -- table.column [table.column ...]
+//! ```
+//!
+//! The encrypted columns so far are `user_settings.encrypted_display_name`.
+//!
+//! Once it reports nothing left to change the old keys can be removed.
+
+use db::encryption::{reencrypt, Keyring};
+
+#[tokio::main]
+async fn main() {
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL not set");
+    let keys = std::env::var("ENCRYPTION_KEYS").expect("ENCRYPTION_KEYS not set");
+    let keyring = Keyring::parse(&keys).expect("Invalid ENCRYPTION_KEYS");
+
+    let columns: Vec<String> = std::env::args().skip(1).collect();
+    if columns.is_empty() {
+        eprintln!("Usage: reencrypt table.column [table.column ...]");
+        std::process::exit(1);
+    }
+
+    let pool = db::create_pool(&database_url).expect("Invalid DATABASE_URL");
+    let client = pool.get().await.expect("Unable to connect to the database");
+
+    for column in columns {
+        let Some((table, column)) = column.split_once('.') else {
+            eprintln!("{column} should be written as table.column");
+            std::process::exit(1);
+        };
+        match reencrypt(&client, &keyring, table, column).await {
+            Ok(changed) => println!(
+                "{table}.{column}: {changed} values moved to key {}",
+                keyring.current_key_id()
+            ),
+            Err(e) => {
+                eprintln!("{table}.{column}: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use tokio_postgres::{CancelToken, Client};
+
+use crate::connect::Tls;
+
+tokio::task_local! {
+    static CANCELLER: QueryCanceller;
+}
+
+/// Remembers the connections a future has out of the pool, so whatever
+/// they're still doing can be cancelled if we stop waiting for it.
+/// Dropping a future doesn't stop its query, Postgres would carry on
+/// working for a request nobody is waiting for.
+#[derive(Clone, Default)]
+pub struct QueryCanceller {
+    tokens: Arc<Mutex<Tokens>>,
+}
+
+#[derive(Default)]
+struct Tokens {
+    next: u64,
+    // Cancelling connects again, the same way the pool did.
+    checked_out: HashMap<u64, (CancelToken, Tls)>,
+}
+
+impl QueryCanceller {
+    /// Run `future`, tracking the connections it gets from the pool.
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        CANCELLER.scope(self.clone(), future).await
+    }
+
+    /// Cancel anything still running on the connections the future has
+    /// out. Connections stop being tracked as they go back to the pool, so
+    /// call this before dropping the future, while it can't give any back.
+    pub async fn cancel(&self) {
+        let tokens = std::mem::take(&mut self.tokens.lock().unwrap().checked_out);
+        for (token, tls) in tokens.into_values() {
+            if let Err(e) = token.cancel_query(tls).await {
+                eprintln!("Unable to cancel query: {e}");
+            }
+        }
+    }
+}
+
+// Called by the pool each time it hands out a connection, the connection
+// is tracked until the `Checkout` is dropped.
+pub(crate) fn track(client: &Client, tls: &Tls) -> Option<Checkout> {
+    CANCELLER
+        .try_with(|canceller| {
+            let mut tokens = canceller.tokens.lock().unwrap();
+            let id = tokens.next;
+            tokens.next += 1;
+            tokens
+                .checked_out
+                .insert(id, (client.cancel_token(), tls.clone()));
+            Checkout {
+                canceller: canceller.clone(),
+                id,
+            }
+        })
+        .ok()
+}
+
+pub(crate) struct Checkout {
+    canceller: QueryCanceller,
+    id: u64,
+}
+
+impl Drop for Checkout {
+    fn drop(&mut self) {
+        let mut tokens = self.canceller.tokens.lock().unwrap();
+        tokens.checked_out.remove(&self.id);
+    }
+}
@@ -0,0 +1,86 @@
+//! The queries are turned into Rust by cornucopia ahead of time and the
+//! result is checked in as `generated/cornucopia.rs`, so building the crate
+//! doesn't need Postgres. Regenerate it after changing a query or a
+//! migration with
+//!
+//! ```sh
+//! cargo run --bin codegen
+//! ```
+//!
+//! The first line of the file is a fingerprint of the migrations and
+//! queries it was made from. A test compares it with the ones in the tree,
+//! so forgetting to regenerate fails `cargo test` without a database.
+//! `codegen --check` goes further and compares the whole file.
+
+use std::{io, path::Path};
+
+/// Where the migrations and queries are, relative to the crate.
+pub const MIGRATIONS: &str = "migrations";
+pub const QUERIES: &str = "queries";
+/// Where the generated code goes, relative to the crate.
+pub const GENERATED: &str = "generated/cornucopia.rs";
+
+const FINGERPRINT_PREFIX: &str = "// Fingerprint of the migrations and queries: ";
+
+/// A hash of every migration and query. It's only for noticing changes, so
+/// FNV does, and unlike `DefaultHasher` it's the same on every build.
+pub fn fingerprint(crate_dir: &Path) -> io::Result<String> {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut add = |bytes: &[u8]| {
+        for byte in bytes.iter().chain(&[0]) {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    };
+
+    for dir in [MIGRATIONS, QUERIES] {
+        for (name, sql) in sql_files(&crate_dir.join(dir))? {
+            add(name.as_bytes());
+            add(sql.as_bytes());
+        }
+    }
+
+    Ok(format!("{hash:016x}"))
+}
+
+/// The first line of the generated file.
+pub fn fingerprint_line(fingerprint: &str) -> String {
+    format!("{FINGERPRINT_PREFIX}{fingerprint}\n")
+}
+
+/// The fingerprint the generated file was made from.
+pub fn generated_fingerprint(generated: &str) -> Option<&str> {
+    generated
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix(FINGERPRINT_PREFIX))
+}
+
+fn sql_files(dir: &Path) -> io::Result<Vec<(String, String)>> {
+    let mut files = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "sql") {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            files.push((name.into_owned(), std::fs::read_to_string(&path)?));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_code_is_up_to_date() {
+        let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let generated = std::fs::read_to_string(crate_dir.join(GENERATED)).unwrap();
+        assert_eq!(
+            generated_fingerprint(&generated),
+            Some(fingerprint(crate_dir).unwrap().as_str()),
+            "The queries or migrations changed, run `cargo run --bin codegen`"
+        );
+    }
+}
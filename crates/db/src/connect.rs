@@ -0,0 +1,322 @@
+//! Turning a `DATABASE_URL` into connections. TLS follows libpq's
+//! `sslmode`, so the URL a managed Postgres hands out works as it is:
+//!
+//! - `disable` never encrypts.
+//! - `allow` and `prefer`, the default, encrypt when the server can.
+//! - `require` always encrypts. Like libpq it doesn't check the
+//!   certificate unless there's an `sslrootcert`.
+//! - `verify-ca` checks the certificate was issued by a trusted CA.
+//! - `verify-full` also checks it was issued for the host.
+//!
+//! Trusted CAs are the ones in `sslrootcert`, a PEM file, or the Mozilla
+//! roots when there isn't one or it's `system`.
+
+use std::{fmt, fs::File, io::BufReader, str::FromStr, sync::Arc, time::Duration};
+
+use rustls::{
+    client::{
+        danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+        WebPkiServerVerifier,
+    },
+    crypto::{ring, CryptoProvider},
+    pki_types::{CertificateDer, ServerName, UnixTime},
+    CertificateError, ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme,
+};
+use tokio_postgres::config::SslMode;
+use tokio_postgres_rustls::MakeRustlsConnect;
+
+/// What every connection is made with. When the URL says `disable` it's
+/// never used.
+pub type Tls = MakeRustlsConnect;
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Url(String),
+    Tls(String),
+    Pool(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConfigError::Url(ref cause) => write!(f, "Invalid database URL: {}", cause),
+            ConfigError::Tls(ref cause) => write!(f, "Unable to set up TLS: {}", cause),
+            ConfigError::Pool(ref cause) => write!(f, "Unable to build the pool: {}", cause),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// How big the pool gets and how long it waits. `None` waits for as long
+/// as it takes.
+#[derive(Clone, Debug)]
+pub struct PoolConfig {
+    pub max_size: usize,
+    /// For a connection to come free when they're all in use.
+    pub wait_timeout: Option<Duration>,
+    /// To open a new connection.
+    pub create_timeout: Option<Duration>,
+    /// To check a returned connection still works before handing it out.
+    pub recycle_timeout: Option<Duration>,
+    /// Postgres cancels any statement that runs for longer.
+    pub statement_timeout: Option<Duration>,
+}
+
+impl Default for PoolConfig {
+    fn default() -> PoolConfig {
+        PoolConfig {
+            max_size: deadpool_postgres::PoolConfig::default().max_size,
+            wait_timeout: None,
+            create_timeout: None,
+            recycle_timeout: None,
+            statement_timeout: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Verify {
+    Nothing,
+    Ca,
+    Full,
+}
+
+/// Parse the URL and build its TLS connector. Connect with
+/// `config.connect(tls)`.
+pub fn config(database_url: &str) -> Result<(tokio_postgres::Config, Tls), ConfigError> {
+    let (database_url, ssl_mode, root_cert) = split_tls_params(database_url);
+
+    // tokio_postgres only knows the first three.
+    let (ssl_mode, verify) = match ssl_mode.as_deref().unwrap_or("prefer") {
+        "disable" => (SslMode::Disable, Verify::Nothing),
+        "allow" | "prefer" => (SslMode::Prefer, Verify::Nothing),
+        "require" => (SslMode::Require, Verify::Nothing),
+        "verify-ca" => (SslMode::Require, Verify::Ca),
+        "verify-full" => (SslMode::Require, Verify::Full),
+        other => return Err(ConfigError::Url(format!("unknown sslmode {other}"))),
+    };
+    // libpq treats `require` with a root certificate as `verify-ca`.
+    let verify = match (verify, &root_cert) {
+        (Verify::Nothing, Some(_)) if ssl_mode == SslMode::Require => Verify::Ca,
+        _ => verify,
+    };
+
+    let mut config = tokio_postgres::Config::from_str(&database_url)
+        .map_err(|e| ConfigError::Url(e.to_string()))?;
+    config.ssl_mode(ssl_mode);
+
+    Ok((config, tls(verify, root_cert.as_deref())?))
+}
+
+/// The same server and options, another database.
+pub fn with_database(database_url: &str, database: &str) -> String {
+    let (base, query) = match database_url.split_once('?') {
+        Some((base, query)) => (base, Some(query)),
+        None => (database_url, None),
+    };
+    let server = base.rsplit_once('/').map_or(base, |(server, _)| server);
+    match query {
+        Some(query) => format!("{server}/{database}?{query}"),
+        None => format!("{server}/{database}"),
+    }
+}
+
+// Take sslmode and sslrootcert out of either form of connection string,
+// `postgres://host/db?sslmode=require` or `host=localhost sslmode=require`.
+fn split_tls_params(database_url: &str) -> (String, Option<String>, Option<String>) {
+    let mut ssl_mode = None;
+    let mut root_cert = None;
+    let mut take = |key: &str, value: &str| match key {
+        "sslmode" => {
+            ssl_mode = Some(value.to_string());
+            true
+        }
+        "sslrootcert" => {
+            root_cert = Some(value.to_string());
+            true
+        }
+        _ => false,
+    };
+
+    let rest = if database_url.contains("://") {
+        match database_url.split_once('?') {
+            Some((base, query)) => {
+                let query: Vec<&str> = query
+                    .split('&')
+                    .filter(|pair| {
+                        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                        !take(key, value)
+                    })
+                    .collect();
+                if query.is_empty() {
+                    base.to_string()
+                } else {
+                    format!("{base}?{}", query.join("&"))
+                }
+            }
+            None => database_url.to_string(),
+        }
+    } else {
+        database_url
+            .split_whitespace()
+            .filter(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                !take(key.trim(), value.trim().trim_matches('\''))
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    (rest, ssl_mode, root_cert)
+}
+
+fn tls(verify: Verify, root_cert: Option<&str>) -> Result<Tls, ConfigError> {
+    let provider = Arc::new(ring::default_provider());
+
+    let mut roots = RootCertStore::empty();
+    match root_cert {
+        Some(path) if path != "system" => {
+            let file = File::open(path).map_err(|e| ConfigError::Tls(format!("{path}: {e}")))?;
+            for cert in rustls_pemfile::certs(&mut BufReader::new(file)) {
+                let cert = cert.map_err(|e| ConfigError::Tls(format!("{path}: {e}")))?;
+                roots
+                    .add(cert)
+                    .map_err(|e| ConfigError::Tls(format!("{path}: {e}")))?;
+            }
+        }
+        _ => roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned()),
+    }
+
+    let webpki = WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+        .build()
+        .map_err(|e| ConfigError::Tls(e.to_string()))?;
+    let verifier = Verifier {
+        webpki,
+        verify,
+        provider: provider.clone(),
+    };
+
+    let config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| ConfigError::Tls(e.to_string()))?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(verifier))
+        .with_no_client_auth();
+
+    Ok(MakeRustlsConnect::new(config))
+}
+
+// The usual checks, less whatever the sslmode leaves out. The handshake is
+// always checked against the certificate, whatever the mode.
+#[derive(Debug)]
+struct Verifier {
+    webpki: Arc<WebPkiServerVerifier>,
+    verify: Verify,
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for Verifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        if self.verify == Verify::Nothing {
+            return Ok(ServerCertVerified::assertion());
+        }
+        let verified = self.webpki.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        );
+        match verified {
+            Err(rustls::Error::InvalidCertificate(
+                CertificateError::NotValidForName | CertificateError::NotValidForNameContext { .. },
+            )) if self.verify == Verify::Ca => Ok(ServerCertVerified::assertion()),
+            verified => verified,
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.webpki.supported_verify_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tls_params_are_taken_out_of_the_url() {
+        assert_eq!(
+            split_tls_params(
+                "postgres://app@db.example.com/app?sslmode=verify-full&sslrootcert=/ca.pem&application_name=web"
+            ),
+            (
+                "postgres://app@db.example.com/app?application_name=web".to_string(),
+                Some("verify-full".to_string()),
+                Some("/ca.pem".to_string())
+            )
+        );
+        assert_eq!(
+            split_tls_params("postgres://localhost/app?sslmode=disable"),
+            (
+                "postgres://localhost/app".to_string(),
+                Some("disable".to_string()),
+                None
+            )
+        );
+        assert_eq!(
+            split_tls_params("host=localhost dbname=app sslmode=require"),
+            (
+                "host=localhost dbname=app".to_string(),
+                Some("require".to_string()),
+                None
+            )
+        );
+
+        assert!(config("postgres://localhost/app?sslmode=verify-full").is_ok());
+        assert!(matches!(
+            config("postgres://localhost/app?sslmode=sometimes"),
+            Err(ConfigError::Url(_))
+        ));
+        assert!(matches!(
+            config("postgres://localhost/app?sslmode=verify-ca&sslrootcert=/missing.pem"),
+            Err(ConfigError::Tls(_))
+        ));
+    }
+}
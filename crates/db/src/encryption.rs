@@ -0,0 +1,346 @@
+use std::{collections::HashMap, fmt};
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    Aes256Gcm, Key, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bytes::BytesMut;
+use postgres_types::{accepts, to_sql_checked, FromSql, IsNull, ToSql, Type};
+
+use crate::GenericClient;
+
+const VERSION: u8 = 1;
+const NONCE_LEN: usize = 12;
+// A 32 byte data key plus the 16 byte tag from wrapping it.
+const WRAPPED_KEY_LEN: usize = 48;
+
+#[derive(Debug)]
+pub enum EncryptionError {
+    InvalidKey(String),
+    UnknownKey(String),
+    Malformed,
+    Decrypt,
+}
+
+impl fmt::Display for EncryptionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EncryptionError::InvalidKey(ref cause) => {
+                write!(f, "Invalid encryption key: {}", cause)
+            }
+            EncryptionError::UnknownKey(ref id) => write!(f, "No encryption key with id {}", id),
+            EncryptionError::Malformed => write!(f, "Ciphertext is malformed"),
+            EncryptionError::Decrypt => write!(f, "Unable to decrypt, wrong key or tampered data"),
+        }
+    }
+}
+
+impl std::error::Error for EncryptionError {}
+
+/// An encrypted value as stored in a `BYTEA` column.
+///
+/// Each value is encrypted with its own random data key, and that key is
+/// wrapped with one of the keys in the [`Keyring`]. The layout is
+/// `version | key id length | key id | nonce | wrapped data key | nonce | data`.
+///
+/// The data is bound to where it's stored, see [`context`], so it only
+/// decrypts there.
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ciphertext(Vec<u8>);
+
+struct Envelope<'a> {
+    key_id: &'a str,
+    wrap_nonce: &'a [u8],
+    wrapped_key: &'a [u8],
+    nonce: &'a [u8],
+    data: &'a [u8],
+}
+
+impl Ciphertext {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// The id of the key that wrapped this value.
+    pub fn key_id(&self) -> Result<&str, EncryptionError> {
+        Ok(self.envelope()?.key_id)
+    }
+
+    fn envelope(&self) -> Result<Envelope<'_>, EncryptionError> {
+        let bytes = &self.0;
+        if bytes.len() < 2 || bytes[0] != VERSION {
+            return Err(EncryptionError::Malformed);
+        }
+        let key_id_end = 2 + bytes[1] as usize;
+        let wrap_nonce_end = key_id_end + NONCE_LEN;
+        let wrapped_key_end = wrap_nonce_end + WRAPPED_KEY_LEN;
+        let nonce_end = wrapped_key_end + NONCE_LEN;
+        if bytes.len() < nonce_end {
+            return Err(EncryptionError::Malformed);
+        }
+        let key_id =
+            std::str::from_utf8(&bytes[2..key_id_end]).map_err(|_| EncryptionError::Malformed)?;
+        Ok(Envelope {
+            key_id,
+            wrap_nonce: &bytes[key_id_end..wrap_nonce_end],
+            wrapped_key: &bytes[wrap_nonce_end..wrapped_key_end],
+            nonce: &bytes[wrapped_key_end..nonce_end],
+            data: &bytes[nonce_end..],
+        })
+    }
+}
+
+impl From<Vec<u8>> for Ciphertext {
+    fn from(bytes: Vec<u8>) -> Ciphertext {
+        Ciphertext(bytes)
+    }
+}
+
+impl ToSql for Ciphertext {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        self.0.as_slice().to_sql(ty, out)
+    }
+
+    accepts!(BYTEA);
+
+    to_sql_checked!();
+}
+
+impl<'a> FromSql<'a> for Ciphertext {
+    fn from_sql(
+        ty: &Type,
+        raw: &'a [u8],
+    ) -> Result<Ciphertext, Box<dyn std::error::Error + Sync + Send>> {
+        Ok(Ciphertext(Vec::<u8>::from_sql(ty, raw)?))
+    }
+
+    accepts!(BYTEA);
+}
+
+// Lets cornucopia take a Ciphertext wherever a query has a BYTEA parameter.
+impl cornucopia_async::BytesSql for Ciphertext {}
+
+/// The keys we encrypt with. New values use the current key, older keys
+/// are kept so we can still decrypt until everything has been re-encrypted.
+#[derive(Clone)]
+pub struct Keyring {
+    current: String,
+    keys: HashMap<String, Key<Aes256Gcm>>,
+}
+
+// Never print the keys themselves.
+impl fmt::Debug for Keyring {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut ids: Vec<&String> = self.keys.keys().collect();
+        ids.sort();
+        f.debug_struct("Keyring")
+            .field("current", &self.current)
+            .field("keys", &ids)
+            .finish()
+    }
+}
+
+impl Keyring {
+    /// Parse keys in the form `id:base64,id:base64`. The first key is the
+    /// one new values are encrypted with. Keys are 32 random bytes, i.e.
+    /// `openssl rand -base64 32`.
+    pub fn parse(keys: &str) -> Result<Keyring, EncryptionError> {
+        let mut current = None;
+        let mut parsed = HashMap::new();
+        for entry in keys.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (id, key) = entry
+                .split_once(':')
+                .ok_or_else(|| EncryptionError::InvalidKey(format!("{entry} has no id")))?;
+            if id.is_empty() || id.len() > u8::MAX as usize {
+                return Err(EncryptionError::InvalidKey(format!("bad key id {id}")));
+            }
+            let key = STANDARD
+                .decode(key)
+                .map_err(|e| EncryptionError::InvalidKey(format!("{id}: {e}")))?;
+            if key.len() != 32 {
+                return Err(EncryptionError::InvalidKey(format!("{id} isn't 32 bytes")));
+            }
+            parsed.insert(id.to_string(), *Key::<Aes256Gcm>::from_slice(&key));
+            current.get_or_insert_with(|| id.to_string());
+        }
+        let current =
+            current.ok_or_else(|| EncryptionError::InvalidKey("no keys given".to_string()))?;
+        Ok(Keyring {
+            current,
+            keys: parsed,
+        })
+    }
+
+    /// The id of the key new values are encrypted with.
+    pub fn current_key_id(&self) -> &str {
+        &self.current
+    }
+
+    /// Encrypt a value stored at `context`, see [`context`].
+    pub fn encrypt(&self, plaintext: &[u8], context: &str) -> Ciphertext {
+        let data_key = Aes256Gcm::generate_key(OsRng);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let data = Aes256Gcm::new(&data_key)
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: context.as_bytes(),
+                },
+            )
+            .expect("AES-GCM encryption doesn't fail for in memory data");
+
+        self.seal(&self.current, &data_key, &nonce, &data)
+    }
+
+    pub fn encrypt_str(&self, plaintext: &str, context: &str) -> Ciphertext {
+        self.encrypt(plaintext.as_bytes(), context)
+    }
+
+    /// Decrypt a value read from `context`, which has to be where it was
+    /// encrypted for.
+    pub fn decrypt(
+        &self,
+        ciphertext: &Ciphertext,
+        context: &str,
+    ) -> Result<Vec<u8>, EncryptionError> {
+        let envelope = ciphertext.envelope()?;
+        let data_key = self.unwrap_key(&envelope)?;
+        Aes256Gcm::new(&data_key)
+            .decrypt(
+                Nonce::from_slice(envelope.nonce),
+                Payload {
+                    msg: envelope.data,
+                    aad: context.as_bytes(),
+                },
+            )
+            .map_err(|_| EncryptionError::Decrypt)
+    }
+
+    pub fn decrypt_string(
+        &self,
+        ciphertext: &Ciphertext,
+        context: &str,
+    ) -> Result<String, EncryptionError> {
+        String::from_utf8(self.decrypt(ciphertext, context)?)
+            .map_err(|_| EncryptionError::Malformed)
+    }
+
+    /// Wrap the data key again with the current key. The data itself isn't
+    /// touched, which is what makes rotating keys cheap, and why it doesn't
+    /// need the context.
+    pub fn rewrap(&self, ciphertext: &Ciphertext) -> Result<Ciphertext, EncryptionError> {
+        let envelope = ciphertext.envelope()?;
+        let data_key = self.unwrap_key(&envelope)?;
+        Ok(self.seal(
+            &self.current,
+            &data_key,
+            Nonce::from_slice(envelope.nonce),
+            envelope.data,
+        ))
+    }
+
+    fn key(&self, id: &str) -> Result<&Key<Aes256Gcm>, EncryptionError> {
+        self.keys
+            .get(id)
+            .ok_or_else(|| EncryptionError::UnknownKey(id.to_string()))
+    }
+
+    fn unwrap_key(&self, envelope: &Envelope) -> Result<Key<Aes256Gcm>, EncryptionError> {
+        let key = self.key(envelope.key_id)?;
+        let data_key = Aes256Gcm::new(key)
+            .decrypt(
+                Nonce::from_slice(envelope.wrap_nonce),
+                Payload {
+                    msg: envelope.wrapped_key,
+                    aad: envelope.key_id.as_bytes(),
+                },
+            )
+            .map_err(|_| EncryptionError::Decrypt)?;
+        Ok(*Key::<Aes256Gcm>::from_slice(&data_key))
+    }
+
+    fn seal(
+        &self,
+        key_id: &str,
+        data_key: &Key<Aes256Gcm>,
+        nonce: &Nonce<<Aes256Gcm as AeadCore>::NonceSize>,
+        data: &[u8],
+    ) -> Ciphertext {
+        let wrap_nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        // The key id is authenticated so it can't be swapped for another.
+        let wrapped_key = Aes256Gcm::new(self.key(key_id).expect("current key is in the keyring"))
+            .encrypt(
+                &wrap_nonce,
+                Payload {
+                    msg: data_key.as_slice(),
+                    aad: key_id.as_bytes(),
+                },
+            )
+            .expect("AES-GCM encryption doesn't fail for in memory data");
+
+        let mut bytes =
+            Vec::with_capacity(2 + key_id.len() + NONCE_LEN * 2 + WRAPPED_KEY_LEN + data.len());
+        bytes.push(VERSION);
+        bytes.push(key_id.len() as u8);
+        bytes.extend_from_slice(key_id.as_bytes());
+        bytes.extend_from_slice(&wrap_nonce);
+        bytes.extend_from_slice(&wrapped_key);
+        bytes.extend_from_slice(nonce);
+        bytes.extend_from_slice(data);
+        Ciphertext(bytes)
+    }
+}
+
+/// Where a value is stored, `table.column:row_id`. It's authenticated along
+/// with the value, so a value copied to another row or column won't
+/// decrypt. Rows have to have their id before their values are encrypted.
+pub fn context(table: &str, column: &str, row_id: impl fmt::Display) -> String {
+    format!("{table}.{column}:{row_id}")
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// Re-wrap every value in `table.column` that isn't using the current key.
+/// Runs in batches, so it can be stopped and started again. Returns how
+/// many values were changed.
+pub async fn reencrypt<C: GenericClient>(
+    client: &C,
+    keyring: &Keyring,
+    table: &str,
+    column: &str,
+) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+    let table = quote_identifier(table);
+    let column = quote_identifier(column);
+    let current = keyring.current_key_id().as_bytes();
+
+    // Byte 1 is the key id length and the id starts at byte 3 (1 based).
+    let select = format!(
+        "SELECT {column} FROM {table}
+        WHERE {column} IS NOT NULL
+            AND substring({column} FROM 3 FOR get_byte({column}, 1)) <> $1
+        LIMIT 500"
+    );
+    let update = format!("UPDATE {table} SET {column} = $1 WHERE {column} = $2");
+
+    let mut changed = 0;
+    loop {
+        let rows = client.query(&select, &[&current]).await?;
+        if rows.is_empty() {
+            return Ok(changed);
+        }
+        for row in rows {
+            let old: Ciphertext = row.get(0);
+            let new = keyring.rewrap(&old)?;
+            changed += client.execute(&update, &[&new, &old]).await?;
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Helpers for tests that need the database.
+//!
+//! Each test gets a transaction of its own from `TestDb` and never commits
+//! it, so whatever it adds is rolled back when it's dropped and tests can
+//! run at once against the same database. Data comes from the builders,
+//! which make up unique emails, so tests never wait on each other's rows.
+//!
+//! ```ignore
+//! let mut db = TestDb::connect().await;
+//! let transaction = db.transaction().await;
+//! let owner = fixtures::user().create(&transaction).await;
+//! let team_id = fixtures::team(&owner).create(&transaction).await;
+//! ```
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use tokio_postgres::{Client, Transaction};
+
+use crate::{connect, queries, TeamRole, User};
+
+static EMAILS: AtomicUsize = AtomicUsize::new(0);
+
+/// A connection of its own to `DATABASE_URL`.
+pub struct TestDb {
+    client: Client,
+}
+
+impl TestDb {
+    pub async fn connect() -> TestDb {
+        let db_url = std::env::var("DATABASE_URL").unwrap();
+        let (config, tls) = connect::config(&db_url).unwrap();
+        let (client, connection) = config.connect(tls).await.unwrap();
+        tokio::spawn(connection);
+        TestDb { client }
+    }
+
+    /// Don't commit it, it's rolled back when it's dropped.
+    pub async fn transaction(&mut self) -> Transaction<'_> {
+        self.client.transaction().await.unwrap()
+    }
+}
+
+/// An email nobody else has, in this test run or any other.
+pub fn unique_email(name: &str) -> String {
+    format!(
+        "{name}-{}-{}@fixtures.test",
+        std::process::id(),
+        EMAILS.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+pub fn user() -> UserBuilder {
+    UserBuilder {
+        email: unique_email("user"),
+    }
+}
+
+pub struct UserBuilder {
+    email: String,
+}
+
+impl UserBuilder {
+    pub async fn create(self, transaction: &Transaction<'_>) -> User {
+        let email = self.email;
+        queries::users::create_user()
+            .bind(transaction, &email)
+            .one()
+            .await
+            .unwrap()
+    }
+}
+
+/// A team with `owner` as its administrator.
+pub fn team(owner: &User) -> TeamBuilder {
+    TeamBuilder {
+        name: format!("Team of {}", owner.email),
+        members: vec![(owner.id, TeamRole::Administrator)],
+    }
+}
+
+pub struct TeamBuilder {
+    name: String,
+    members: Vec<(i32, TeamRole)>,
+}
+
+impl TeamBuilder {
+    pub fn name(mut self, name: &str) -> TeamBuilder {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn member(mut self, user: &User, role: TeamRole) -> TeamBuilder {
+        self.members.push((user.id, role));
+        self
+    }
+
+    /// Returns the team's id.
+    pub async fn create(self, transaction: &Transaction<'_>) -> i32 {
+        let (owner, _) = self.members[0];
+        let team_id = queries::teams::create_team()
+            .bind(transaction, &self.name, &owner)
+            .one()
+            .await
+            .unwrap();
+        for (user_id, role) in &self.members {
+            queries::teams::add_team_member()
+                .bind(transaction, &team_id, user_id, role)
+                .await
+                .unwrap();
+        }
+        team_id
+    }
+}
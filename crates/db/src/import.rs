@@ -0,0 +1,74 @@
+use futures::SinkExt;
+use tokio_postgres::Transaction;
+
+use crate::{queries, TokioPostgresError, User};
+
+/// Above this many rows we stream them in with `COPY` rather than sending
+/// them as one big array parameter.
+pub const COPY_THRESHOLD: usize = 1000;
+
+/// What an import did.
+#[derive(Debug, Default)]
+pub struct Imported {
+    /// New accounts, already in the team.
+    pub created: Vec<User>,
+    /// Emails of existing accounts that were invited instead, they decide
+    /// for themselves whether to join.
+    pub invited: Vec<String>,
+}
+
+/// Add users to a team in bulk, creating the ones that don't exist yet.
+/// Emails should already be validated. Only the users created here join the
+/// team, anyone who already had an account and isn't a member gets an
+/// invitation from `invited_by`, the same as adding them one at a time.
+///
+/// New users aren't visible under `rls` until they're in the team, so run
+/// this before `rls::set_tenant`.
+pub async fn import_users(
+    transaction: &Transaction<'_>,
+    team_id: i32,
+    invited_by: i32,
+    emails: &[String],
+) -> Result<Imported, TokioPostgresError> {
+    if emails.len() > COPY_THRESHOLD {
+        let mut csv = String::new();
+        for email in emails {
+            csv.push('"');
+            csv.push_str(&email.replace('"', "\"\""));
+            csv.push_str("\"\n");
+        }
+        let sink = transaction
+            .copy_in("COPY user_import (email) FROM STDIN WITH (FORMAT csv)")
+            .await?;
+        futures::pin_mut!(sink);
+        sink.send(bytes::Bytes::from(csv)).await?;
+        sink.finish().await?;
+    } else {
+        queries::users::stage_user_import()
+            .bind(transaction, &emails)
+            .await?;
+    }
+
+    let created = queries::users::create_imported_users()
+        .bind(transaction)
+        .all()
+        .await?;
+
+    let user_ids: Vec<i32> = created.iter().map(|user| user.id).collect();
+    queries::teams::add_imported_members()
+        .bind(transaction, &team_id, &user_ids)
+        .await?;
+
+    let invited = queries::teams::invite_imported_users()
+        .bind(transaction, &team_id, &invited_by)
+        .all()
+        .await?;
+
+    // Nobody else ever sees the rows, and the same transaction can import
+    // again.
+    queries::users::clear_user_import()
+        .bind(transaction)
+        .await?;
+
+    Ok(Imported { created, invited })
+}
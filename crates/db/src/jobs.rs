@@ -0,0 +1,42 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{queries, GenericClient, TokioPostgresError};
+
+/// Work we can queue up in the `jobs` table and run later in a worker.
+pub trait Job: Serialize + DeserializeOwned {
+    /// Stored with each row so the worker knows how to run it.
+    const KIND: &'static str;
+    const MAX_ATTEMPTS: i32 = 5;
+}
+
+/// Queue a job. Pass a transaction to only queue the job if the rest of
+/// the transaction commits. Jobs queued for a team show up on that
+/// team's failed jobs page.
+pub async fn enqueue<C: GenericClient, J: Job>(
+    client: &C,
+    team_id: Option<i32>,
+    job: &J,
+) -> Result<i32, TokioPostgresError> {
+    let payload = serde_json::to_value(job).expect("jobs serialize to JSON");
+
+    queries::jobs::enqueue_job()
+        .bind(client, &team_id, &J::KIND, &payload, &J::MAX_ATTEMPTS)
+        .one()
+        .await
+}
+
+/// Read the payload of a claimed job back into its type.
+pub fn payload<J: Job>(job: &queries::jobs::ClaimedJob) -> Result<J, serde_json::Error> {
+    serde_json::from_value(job.payload.clone())
+}
+
+/// Sent to every user we add.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WelcomeEmail {
+    pub user_id: i32,
+    pub email: String,
+}
+
+impl Job for WelcomeEmail {
+    const KIND: &'static str = "welcome_email";
+}
@@ -1,35 +1,670 @@
-use std::str::FromStr;
+pub mod audit;
+pub mod cancel;
+pub mod codegen;
+pub mod connect;
+pub mod encryption;
+#[cfg(test)]
+mod fixtures;
+pub mod import;
+pub mod jobs;
+pub mod listen;
+pub mod migrations;
+mod pool;
+mod replicas;
+pub mod rls;
+pub mod writes;
+
+use deadpool_postgres::Runtime;
 
-pub use cornucopia_async::Params;
-pub use deadpool_postgres::{Pool, PoolError, Transaction};
+pub use connect::{ConfigError, PoolConfig};
+pub use cornucopia_async::{GenericClient, Params};
+pub use deadpool_postgres::PoolError;
+pub use listen::{listen, notify, Notification, NotifyError};
+pub use pool::{Client, Pool, Transaction};
+pub use queries::feature_flags::FeatureFlag;
+pub use queries::teams::Team;
+pub use queries::user_settings::UserSettings;
 pub use queries::users::User;
+pub use replicas::Pools;
+pub use rls::PrivilegedPool;
 pub use tokio_postgres::Error as TokioPostgresError;
+pub use types::public::{FileKind, JobStatus, Permission, TeamRole, Theme};
 
-pub fn create_pool(database_url: &str) -> deadpool_postgres::Pool {
-    let config = tokio_postgres::Config::from_str(database_url).unwrap();
-    let manager = deadpool_postgres::Manager::new(config, tokio_postgres::NoTls);
-    deadpool_postgres::Pool::builder(manager).build().unwrap()
+/// A pool with the default settings, see `create_pool_with`.
+pub fn create_pool(database_url: &str) -> Result<Pool, ConfigError> {
+    create_pool_with(database_url, &PoolConfig::default())
 }
 
-include!(concat!(env!("OUT_DIR"), "/cornucopia.rs"));
+/// Connections use TLS as the URL's `sslmode` says, see `connect`.
+pub fn create_pool_with(database_url: &str, pool: &PoolConfig) -> Result<Pool, ConfigError> {
+    let (mut config, tls) = connect::config(database_url)?;
+    if let Some(timeout) = pool.statement_timeout {
+        let options = format!(
+            "{} -c statement_timeout={}",
+            config.get_options().unwrap_or_default(),
+            timeout.as_millis()
+        );
+        config.options(options.trim());
+    }
+
+    let manager = deadpool_postgres::Manager::new(config, tls.clone());
+    deadpool_postgres::Pool::builder(manager)
+        .max_size(pool.max_size)
+        .wait_timeout(pool.wait_timeout)
+        .create_timeout(pool.create_timeout)
+        .recycle_timeout(pool.recycle_timeout)
+        .runtime(Runtime::Tokio1)
+        .build()
+        .map(|pool| Pool::new(pool, tls))
+        .map_err(|e| ConfigError::Pool(e.to_string()))
+}
+
+/// A pool for the primary and one for each replica, all with the same
+/// settings.
+pub fn create_pools(
+    primary_url: &str,
+    replica_urls: &[String],
+    pool: &PoolConfig,
+) -> Result<Pools, ConfigError> {
+    let primary = create_pool_with(primary_url, pool)?;
+    let replicas = replica_urls
+        .iter()
+        .map(|url| create_pool_with(url, pool))
+        .collect::<Result<_, _>>()?;
+    Ok(Pools::new(primary, replicas))
+}
+
+// See `codegen`.
+include!("../generated/cornucopia.rs");
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use fixtures::TestDb;
+
     #[tokio::test]
     async fn load_users() {
+        let mut db = TestDb::connect().await;
+        let transaction = db.transaction().await;
+        let created = [
+            fixtures::user().create(&transaction).await,
+            fixtures::user().create(&transaction).await,
+        ];
+
+        let users = crate::queries::users::get_users()
+            .bind(&transaction)
+            .all()
+            .await
+            .unwrap();
+
+        for user in created {
+            assert!(users.contains(&user), "{} is missing", user.email);
+        }
+    }
+
+    #[tokio::test]
+    async fn racing_first_logins_find_the_user_again() {
+        let mut first = TestDb::connect().await;
+        let mut second = TestDb::connect().await;
+        // Unique, so a failed run leaves nothing behind that gets in the
+        // next one's way.
+        let email = fixtures::unique_email("race");
+
+        let winner = first.transaction().await;
+        queries::users::get_or_create_user()
+            .bind(&winner, &email.as_str())
+            .one()
+            .await
+            .unwrap();
+
+        // The loser waits on the winner's insert, then can't see its row.
+        let loser = second.transaction().await;
+        let (raced, ()) = tokio::join!(
+            async {
+                queries::users::get_or_create_user()
+                    .bind(&loser, &email.as_str())
+                    .opt()
+                    .await
+                    .unwrap()
+            },
+            async {
+                tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+                winner.commit().await.unwrap();
+            }
+        );
+        assert!(raced.is_none());
+
+        let user = queries::users::get_user_by_email()
+            .bind(&loser, &email.as_str())
+            .one()
+            .await
+            .unwrap();
+        assert_eq!(user.email, email);
+        drop(loser);
+
+        let cleanup = first.transaction().await;
+        cleanup
+            .execute("DELETE FROM users WHERE email = $1", &[&email])
+            .await
+            .unwrap();
+        cleanup.commit().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn pools_follow_their_config() {
         let db_url = std::env::var("DATABASE_URL").unwrap();
-        let pool = create_pool(&db_url);
+        let pool = create_pool_with(
+            &db_url,
+            &PoolConfig {
+                max_size: 1,
+                wait_timeout: Some(std::time::Duration::from_millis(50)),
+                statement_timeout: Some(std::time::Duration::from_millis(100)),
+                ..Default::default()
+            },
+        )
+        .unwrap();
 
         let client = pool.get().await.unwrap();
-        //let transaction = client.transaction().await.unwrap();
+        let error = client
+            .batch_execute("SELECT pg_sleep(1)")
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("statement timeout"), "{error}");
 
-        let users = crate::queries::users::get_users()
-            .bind(&client)
+        // The only connection is taken.
+        assert!(matches!(pool.get().await, Err(PoolError::Timeout(_))));
+        drop(client);
+        assert!(pool.get().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn cancelling_leaves_returned_connections_alone() {
+        let db_url = std::env::var("DATABASE_URL").unwrap();
+        let pool = create_pool_with(
+            &db_url,
+            &PoolConfig {
+                max_size: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // A request that's already given its connection back.
+        let canceller = cancel::QueryCanceller::default();
+        canceller
+            .scope(async {
+                pool.get()
+                    .await
+                    .unwrap()
+                    .batch_execute("SELECT 1")
+                    .await
+                    .unwrap();
+            })
+            .await;
+
+        // The same connection, now someone else's.
+        let client = pool.get().await.unwrap();
+        let (slow, ()) = tokio::join!(client.batch_execute("SELECT pg_sleep(0.5)"), async {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            canceller.cancel().await;
+        });
+        slow.unwrap();
+    }
+
+    // Whether or not the server does TLS, see `connects_over_tls`.
+    #[tokio::test]
+    async fn sslmode_disable_and_prefer_always_connect() {
+        let db_url = std::env::var("DATABASE_URL").unwrap();
+        let with_mode = |mode: &str| {
+            let separator = if db_url.contains('?') { '&' } else { '?' };
+            format!("{db_url}{separator}sslmode={mode}")
+        };
+
+        for mode in ["disable", "prefer"] {
+            let pool = create_pool(&with_mode(mode)).unwrap();
+            assert!(pool.get().await.is_ok(), "{mode}");
+        }
+
+        assert!(matches!(
+            create_pool("postgres://localhost:notaport/app"),
+            Err(ConfigError::Url(_))
+        ));
+    }
+
+    // Turn on TLS with a certificate for localhost and set TLS_ROOT_CERT to
+    // the CA that signed it, e.g.
+    // openssl req -x509 -newkey rsa:2048 -nodes -keyout ca.key -out ca.crt -subj "/CN=Test CA"
+    // then sign a server certificate with subjectAltName=DNS:localhost.
+    #[tokio::test]
+    #[ignore = "needs Postgres with TLS"]
+    async fn connects_over_tls() {
+        let db_url = std::env::var("DATABASE_URL").unwrap();
+        let root_cert = std::env::var("TLS_ROOT_CERT").unwrap();
+        let ip_url = db_url.replace("localhost", "127.0.0.1");
+        let with = |url: &str, params: &str| {
+            let separator = if url.contains('?') { '&' } else { '?' };
+            format!("{url}{separator}{params}")
+        };
+        let connects = |url: String| async move {
+            let pool = create_pool(&url).unwrap();
+            let Ok(client) = pool.get().await else {
+                return false;
+            };
+            client
+                .query_one(
+                    "SELECT ssl FROM pg_stat_ssl WHERE pid = pg_backend_pid()",
+                    &[],
+                )
+                .await
+                .unwrap()
+                .get::<_, bool>(0)
+        };
+
+        let trusted = format!("sslrootcert={root_cert}");
+        assert!(connects(with(&db_url, &format!("sslmode=verify-full&{trusted}"))).await);
+        assert!(connects(with(&db_url, "sslmode=require")).await);
+        assert!(connects(with(&db_url, "sslmode=prefer")).await);
+        // Not a CA we know without the root certificate
+        assert!(!connects(with(&db_url, "sslmode=verify-ca")).await);
+        // The certificate is for localhost, not the IP
+        assert!(connects(with(&ip_url, &format!("sslmode=verify-ca&{trusted}"))).await);
+        assert!(!connects(with(&ip_url, &format!("sslmode=verify-full&{trusted}"))).await);
+    }
+
+    #[tokio::test]
+    async fn reads_take_turns_across_the_replicas() {
+        let db_url = std::env::var("DATABASE_URL").unwrap();
+        let named = |name: &str| {
+            let separator = if db_url.contains('?') { '&' } else { '?' };
+            format!("{db_url}{separator}application_name={name}")
+        };
+        let name_of = |pool: Pool| async move {
+            let client = pool.get().await.unwrap();
+            let row = client
+                .query_one("SHOW application_name", &[])
+                .await
+                .unwrap();
+            row.get::<_, String>(0)
+        };
+
+        let pools = create_pools(
+            &named("primary"),
+            &[named("replica-a"), named("replica-b")],
+            &PoolConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(name_of(pools.write().clone()).await, "primary");
+        let mut reads = vec![];
+        for _ in 0..4 {
+            reads.push(name_of(pools.read().clone()).await);
+        }
+        reads.sort();
+        assert_eq!(reads, ["replica-a", "replica-a", "replica-b", "replica-b"]);
+
+        let pools = create_pools(&named("primary"), &[], &PoolConfig::default()).unwrap();
+        assert_eq!(name_of(pools.read().clone()).await, "primary");
+    }
+
+    #[test]
+    fn audit_diff_only_includes_changed_fields() {
+        let old = serde_json::json!({ "timezone": "UTC", "theme": "System" });
+        let new = serde_json::json!({ "timezone": "Europe/London", "theme": "System" });
+
+        let diff = audit::diff(Some(&old), Some(&new));
+
+        assert_eq!(
+            diff,
+            serde_json::json!({ "timezone": { "old": "UTC", "new": "Europe/London" } })
+        );
+        assert_eq!(
+            audit::diff(None, Some(&new))["theme"],
+            serde_json::json!({ "old": null, "new": "System" })
+        );
+    }
+
+    #[tokio::test]
+    async fn team_users_stay_in_their_team() {
+        let mut db = TestDb::connect().await;
+        let transaction = db.transaction().await;
+
+        let owner_a = fixtures::user().create(&transaction).await;
+        let member_a = fixtures::user().create(&transaction).await;
+        let team_a = fixtures::team(&owner_a)
+            .member(&member_a, TeamRole::Member)
+            .create(&transaction)
+            .await;
+        let user_b = fixtures::user().create(&transaction).await;
+        fixtures::team(&user_b).create(&transaction).await;
+        let user_b = user_b.id;
+
+        let users = queries::users::get_team_users()
+            .bind(&transaction, &team_a)
+            .all()
+            .await
+            .unwrap();
+        assert_eq!(users, [owner_a, member_a]);
+
+        let other = queries::users::get_team_user()
+            .bind(&transaction, &team_a, &user_b)
+            .opt()
+            .await
+            .unwrap();
+        assert!(other.is_none());
+
+        let teams_of_b = queries::teams::get_teams()
+            .bind(&transaction, &user_b)
             .all()
             .await
             .unwrap();
+        assert!(teams_of_b.iter().all(|team| team.id != team_a));
+    }
+
+    #[tokio::test]
+    async fn row_level_security_needs_a_tenant() {
+        let mut db = TestDb::connect().await;
+        let transaction = db.transaction().await;
+
+        let mut tenants = vec![];
+        for _ in 0..2 {
+            let user = fixtures::user().create(&transaction).await;
+            let team_id = fixtures::team(&user).create(&transaction).await;
+            jobs::enqueue(
+                &transaction,
+                Some(team_id),
+                &jobs::WelcomeEmail {
+                    user_id: user.id,
+                    email: user.email.clone(),
+                },
+            )
+            .await
+            .unwrap();
+            tenants.push(rls::Tenant {
+                user_id: user.id,
+                team_id,
+            });
+        }
+        let (a, b) = (tenants[0], tenants[1]);
 
-        dbg!(users);
+        // As the application role but without saying who we are.
+        transaction
+            .execute("SET LOCAL ROLE application", &[])
+            .await
+            .unwrap();
+        let users = queries::users::get_team_users()
+            .bind(&transaction, &a.team_id)
+            .all()
+            .await
+            .unwrap();
+        assert!(users.is_empty());
+        let teams = queries::teams::get_teams()
+            .bind(&transaction, &a.user_id)
+            .all()
+            .await
+            .unwrap();
+        assert!(teams.is_empty());
+        let jobs = transaction
+            .query("SELECT id FROM jobs WHERE team_id = $1", &[&a.team_id])
+            .await
+            .unwrap();
+        assert!(jobs.is_empty());
+
+        // Scoped to team A we see team A, and still nothing of team B.
+        rls::set_tenant(&transaction, a).await.unwrap();
+        let users = queries::users::get_team_users()
+            .bind(&transaction, &a.team_id)
+            .all()
+            .await
+            .unwrap();
+        assert_eq!(users.len(), 1);
+        let users = queries::users::get_team_users()
+            .bind(&transaction, &b.team_id)
+            .all()
+            .await
+            .unwrap();
+        assert!(users.is_empty());
+        let jobs = transaction
+            .query("SELECT team_id FROM jobs WHERE team_id IS NOT NULL", &[])
+            .await
+            .unwrap();
+        assert!(jobs.iter().all(|job| job.get::<_, i32>(0) == a.team_id));
+    }
+
+    #[test]
+    fn encryption_survives_key_rotation() {
+        let old = encryption::Keyring::parse("2024:AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=")
+            .unwrap();
+        let context = encryption::context("cards", "number", 1);
+        let ciphertext = old.encrypt_str("4111 1111 1111 1111", &context);
+        assert_eq!(ciphertext.key_id().unwrap(), "2024");
+        assert_eq!(
+            old.decrypt_string(&ciphertext, &context).unwrap(),
+            "4111 1111 1111 1111"
+        );
+
+        let mut tampered = ciphertext.as_bytes().to_vec();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(old.decrypt(&tampered.into(), &context).is_err());
+
+        // Copied to another row, or another column, it's no good.
+        for elsewhere in [
+            encryption::context("cards", "number", 2),
+            encryption::context("cards", "name", 1),
+        ] {
+            assert!(old.decrypt(&ciphertext, &elsewhere).is_err());
+        }
+
+        let new = encryption::Keyring::parse(
+            "2025:AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE=,\
+            2024:AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+        )
+        .unwrap();
+        let rewrapped = new.rewrap(&ciphertext).unwrap();
+        assert_eq!(rewrapped.key_id().unwrap(), "2025");
+        assert_eq!(
+            new.decrypt_string(&rewrapped, &context).unwrap(),
+            "4111 1111 1111 1111"
+        );
+        assert!(old.decrypt(&rewrapped, &context).is_err());
+    }
+
+    #[tokio::test]
+    async fn reencrypt_moves_a_column_to_the_current_key() {
+        let mut db = TestDb::connect().await;
+        let transaction = db.transaction().await;
+
+        let old = encryption::Keyring::parse("2024:AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=")
+            .unwrap();
+        let new = encryption::Keyring::parse(
+            "2025:AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE=,\
+            2024:AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+        )
+        .unwrap();
+
+        transaction
+            .execute("CREATE TEMP TABLE secrets (id INT, value BYTEA)", &[])
+            .await
+            .unwrap();
+        for (id, secret) in [(1, "one"), (2, "two"), (3, "three")] {
+            let context = encryption::context("secrets", "value", id);
+            transaction
+                .execute(
+                    "INSERT INTO secrets VALUES ($1, $2)",
+                    &[&id, &old.encrypt_str(secret, &context)],
+                )
+                .await
+                .unwrap();
+        }
+
+        let changed = encryption::reencrypt(&transaction, &new, "secrets", "value")
+            .await
+            .unwrap();
+        assert_eq!(changed, 3);
+
+        let rows = transaction
+            .query("SELECT id, value FROM secrets", &[])
+            .await
+            .unwrap();
+        for row in rows {
+            let value: encryption::Ciphertext = row.get(1);
+            let context = encryption::context("secrets", "value", row.get::<_, i32>(0));
+            assert_eq!(value.key_id().unwrap(), "2025");
+            assert!(new.decrypt_string(&value, &context).is_ok());
+        }
+
+        // Nothing left to do the second time around.
+        let changed = encryption::reencrypt(&transaction, &new, "secrets", "value")
+            .await
+            .unwrap();
+        assert_eq!(changed, 0);
+    }
+
+    #[tokio::test]
+    async fn import_users_copies_large_files() {
+        let mut db = TestDb::connect().await;
+        let transaction = db.transaction().await;
+
+        let owner = fixtures::user().create(&transaction).await;
+        let team_id = fixtures::team(&owner)
+            .name("Import")
+            .create(&transaction)
+            .await;
+
+        // Enough rows to go through COPY, with the owner and a duplicate in there.
+        let mut emails: Vec<String> = (0..import::COPY_THRESHOLD + 10)
+            .map(|_| fixtures::unique_email("import"))
+            .collect();
+        emails.push(owner.email.clone());
+        emails.push(emails[0].clone());
+
+        let imported = import::import_users(&transaction, team_id, owner.id, &emails)
+            .await
+            .unwrap();
+        assert_eq!(imported.created.len(), import::COPY_THRESHOLD + 10);
+        assert!(imported.invited.is_empty());
+
+        let members = queries::users::get_team_users()
+            .bind(&transaction, &team_id)
+            .all()
+            .await
+            .unwrap();
+        assert_eq!(members.len(), import::COPY_THRESHOLD + 11);
+
+        // A second import finds everyone already there.
+        let imported = import::import_users(&transaction, team_id, owner.id, &emails[..5])
+            .await
+            .unwrap();
+        assert!(imported.created.is_empty());
+        assert!(imported.invited.is_empty());
+    }
+
+    #[tokio::test]
+    async fn import_users_invites_existing_accounts() {
+        let mut db = TestDb::connect().await;
+        let transaction = db.transaction().await;
+
+        let owner = fixtures::user().create(&transaction).await;
+        let team_id = fixtures::team(&owner)
+            .name("Import")
+            .create(&transaction)
+            .await;
+        // Someone with an account in another tenant.
+        let outsider = fixtures::user().create(&transaction).await;
+        fixtures::team(&outsider)
+            .name("Elsewhere")
+            .create(&transaction)
+            .await;
+
+        let new_email = fixtures::unique_email("import");
+        let emails = [outsider.email.clone(), new_email.clone()];
+        let imported = import::import_users(&transaction, team_id, owner.id, &emails)
+            .await
+            .unwrap();
+        assert_eq!(imported.created.len(), 1);
+        assert_eq!(imported.created[0].email, new_email);
+        assert_eq!(imported.invited, [outsider.email.as_str()]);
+
+        let members: Vec<String> = queries::users::get_team_users()
+            .bind(&transaction, &team_id)
+            .all()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|user| user.email)
+            .collect();
+        assert!(!members.contains(&outsider.email));
+        assert!(members.contains(&new_email));
+
+        let invitations = queries::teams::get_invitations_for_email()
+            .bind(&transaction, &outsider.email)
+            .all()
+            .await
+            .unwrap();
+        assert_eq!(invitations.len(), 1);
+        assert_eq!(invitations[0].team_id, team_id);
+    }
+
+    #[tokio::test]
+    async fn notifications_arrive_on_commit_and_after_reconnecting() {
+        use futures::StreamExt;
+        use std::time::Duration;
+
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct Ping {
+            n: i32,
+        }
+
+        let db_url = std::env::var("DATABASE_URL").unwrap();
+        let channel = format!("listen_test_{}", std::process::id());
+        let mut notifications = std::pin::pin!(listen::<Ping>(&db_url, &[&channel]));
+        let pool = create_pool(&db_url).unwrap();
+        let mut client = pool.get().await.unwrap();
+
+        // The listener connects in the background, keep sending until it's
+        // heard one.
+        macro_rules! until_heard {
+            ($n:expr) => {
+                let mut heard = None;
+                for _ in 0..50 {
+                    notify(&client, &channel, &Ping { n: $n }).await.unwrap();
+                    let next =
+                        tokio::time::timeout(Duration::from_millis(200), notifications.next());
+                    if let Ok(notification) = next.await {
+                        heard = notification;
+                        break;
+                    }
+                }
+                let heard = heard.expect("no notification");
+                assert_eq!(heard.channel, channel);
+                assert_eq!(heard.payload, Ping { n: $n });
+            };
+        }
+        until_heard!(1);
+
+        // Only once the transaction commits, and anything that isn't a Ping
+        // is skipped.
+        let transaction = client.transaction().await.unwrap();
+        transaction
+            .execute("SELECT pg_notify($1, 'not a ping')", &[&channel])
+            .await
+            .unwrap();
+        notify(&transaction, &channel, &Ping { n: 2 })
+            .await
+            .unwrap();
+        let early = tokio::time::timeout(Duration::from_millis(300), notifications.next());
+        assert!(early.await.is_err());
+        transaction.commit().await.unwrap();
+        let heard = tokio::time::timeout(Duration::from_secs(5), notifications.next());
+        assert_eq!(heard.await.unwrap().unwrap().payload, Ping { n: 2 });
+
+        // Cut the listener off, it comes back by itself.
+        client
+            .execute(
+                "SELECT pg_terminate_backend(pid) FROM pg_stat_activity \
+                WHERE query LIKE '%' || $1 || '%' AND pid <> pg_backend_pid()",
+                &[&channel],
+            )
+            .await
+            .unwrap();
+        until_heard!(3);
     }
 }
@@ -0,0 +1,152 @@
+//! Postgres notifications as a stream of typed payloads.
+//!
+//! Pooled connections get recycled, so a listener holds a connection of its
+//! own. When it drops, the listener reconnects, backing off up to
+//! `MAX_BACKOFF`, and listens again. Anything sent while it's reconnecting
+//! is missed, so don't rely on notifications for anything that can't be
+//! read again from the tables.
+
+use std::{fmt, time::Duration};
+
+use futures::{Stream, StreamExt};
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::mpsc;
+use tokio_postgres::AsyncMessage;
+
+use crate::{connect, GenericClient, TokioPostgresError};
+
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// A payload and the channel it came on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Notification<T> {
+    pub channel: String,
+    pub payload: T,
+}
+
+/// Listen on the channels until the stream is dropped. Payloads are JSON,
+/// ones that don't deserialize into `T` are logged and skipped.
+pub fn listen<T>(database_url: &str, channels: &[&str]) -> impl Stream<Item = Notification<T>>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    let database_url = database_url.to_string();
+    let channels: Vec<String> = channels.iter().map(|channel| channel.to_string()).collect();
+    let (sender, receiver) = mpsc::channel(128);
+
+    tokio::spawn(async move {
+        let mut backoff = Duration::from_secs(1);
+        loop {
+            match listen_once(&database_url, &channels, &sender).await {
+                // Nobody's reading any more.
+                Ok(()) if sender.is_closed() => return,
+                Ok(()) => backoff = Duration::from_secs(1),
+                Err(e) => eprintln!("Listening for {} failed: {e}", channels.join(", ")),
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(backoff) => {}
+                _ = sender.closed() => return,
+            }
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    });
+
+    futures::stream::unfold(receiver, |mut receiver| async move {
+        let notification = receiver.recv().await?;
+        Some((notification, receiver))
+    })
+}
+
+/// Send a notification, which listeners get when the transaction `client`
+/// is in commits, or straight away outside of one. Postgres turns away
+/// payloads of 8000 bytes or more, so send ids rather than whole rows.
+pub async fn notify<C, T>(client: &C, channel: &str, payload: &T) -> Result<(), NotifyError>
+where
+    C: GenericClient,
+    T: Serialize,
+{
+    let payload = serde_json::to_string(payload).map_err(NotifyError::Serialize)?;
+    client
+        .execute("SELECT pg_notify($1, $2)", &[&channel, &payload])
+        .await
+        .map_err(NotifyError::Database)?;
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum NotifyError {
+    Serialize(serde_json::Error),
+    Database(TokioPostgresError),
+}
+
+impl fmt::Display for NotifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NotifyError::Serialize(ref cause) => write!(f, "Unable to serialize: {}", cause),
+            NotifyError::Database(ref cause) => write!(f, "Unable to notify: {}", cause),
+        }
+    }
+}
+
+impl std::error::Error for NotifyError {}
+
+// One connection's worth, returns when it drops.
+async fn listen_once<T: DeserializeOwned>(
+    database_url: &str,
+    channels: &[String],
+    sender: &mpsc::Sender<Notification<T>>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // With the same TLS as the pool
+    let (config, tls) = connect::config(database_url)?;
+    let (client, mut connection) = config.connect(tls).await?;
+
+    // The connection only delivers notifications while something polls it.
+    let (message_tx, mut messages) = mpsc::unbounded_channel();
+    let driver = tokio::spawn(async move {
+        let mut stream = futures::stream::poll_fn(move |cx| connection.poll_message(cx));
+        while let Some(message) = stream.next().await {
+            if message_tx.send(message).is_err() {
+                break;
+            }
+        }
+    });
+
+    let listens: Vec<String> = channels
+        .iter()
+        .map(|channel| format!("LISTEN \"{}\";", channel.replace('"', "\"\"")))
+        .collect();
+    client.batch_execute(&listens.join("\n")).await?;
+
+    let result = async {
+        loop {
+            let message = tokio::select! {
+                message = messages.recv() => message,
+                _ = sender.closed() => break,
+            };
+            let Some(message) = message else { break };
+            match message? {
+                AsyncMessage::Notification(notification) => {
+                    match serde_json::from_str::<T>(notification.payload()) {
+                        Ok(payload) => {
+                            let notification = Notification {
+                                channel: notification.channel().to_string(),
+                                payload,
+                            };
+                            if sender.send(notification).await.is_err() {
+                                break;
+                            }
+                        }
+                        Err(e) => eprintln!("Unexpected {} payload: {e}", notification.channel()),
+                    }
+                }
+                AsyncMessage::Notice(notice) => eprintln!("{notice}"),
+                _ => {}
+            }
+        }
+        Ok::<_, TokioPostgresError>(())
+    }
+    .await;
+
+    driver.abort();
+    Ok(result?)
+}
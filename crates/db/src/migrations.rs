@@ -0,0 +1,429 @@
+//! Runs the migrations in `crates/db/migrations`, which are built into the
+//! crate, so a deploy doesn't need dbmate. It reads dbmate's format and
+//! keeps dbmate's `schema_migrations` table, so either can be used on the
+//! same database.
+//!
+//! ```sql
+//! -- migrate:up transaction:false
+//! CREATE INDEX CONCURRENTLY ...
+//!
+//! -- migrate:down
+//! DROP INDEX ...
+//! ```
+//!
+//! Each migration runs in a transaction of its own unless it says
+//! `transaction:false`. Running them one at a time matters for things like
+//! `ALTER TYPE ... ADD VALUE`, where the value can't be used until it's
+//! committed.
+//!
+//! Migrations only change the schema. Data to try things out with goes in
+//! `crates/db/seeds`, which `seed` adds to a development database.
+
+use std::{collections::HashSet, fmt, sync::LazyLock};
+
+use tokio_postgres::Client;
+
+use crate::{connect, ConfigError, TokioPostgresError};
+
+include!(concat!(env!("OUT_DIR"), "/migrations.rs"));
+
+// Held while migrating, so replicas starting at the same time take turns.
+// Any number will do, as long as nothing else locks it.
+const MIGRATION_LOCK: i64 = 7_297_002;
+
+#[derive(Debug)]
+pub enum MigrationError {
+    Config(ConfigError),
+    Parse(String),
+    Database(TokioPostgresError),
+    Failed(&'static str, TokioPostgresError),
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MigrationError::Config(ref cause) => write!(f, "{}", cause),
+            MigrationError::Parse(ref cause) => write!(f, "Invalid migration: {}", cause),
+            MigrationError::Database(ref cause) => write!(f, "Database error: {}", cause),
+            MigrationError::Failed(name, ref cause) => {
+                write!(f, "Migration {} failed: {}", name, cause)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+impl From<ConfigError> for MigrationError {
+    fn from(err: ConfigError) -> MigrationError {
+        MigrationError::Config(err)
+    }
+}
+
+impl From<TokioPostgresError> for MigrationError {
+    fn from(err: TokioPostgresError) -> MigrationError {
+        MigrationError::Database(err)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Migration {
+    /// The digits the file name starts with, what dbmate records.
+    pub version: &'static str,
+    /// The file name.
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+    pub transaction: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Status {
+    pub migration: Migration,
+    pub applied: bool,
+}
+
+static MIGRATIONS: LazyLock<Result<Vec<Migration>, String>> = LazyLock::new(|| {
+    EMBEDDED
+        .iter()
+        .map(|(name, sql)| parse(name, sql).map_err(|e| e.to_string()))
+        .collect()
+});
+
+/// Every migration, oldest first.
+pub fn migrations() -> Result<&'static [Migration], MigrationError> {
+    MIGRATIONS
+        .as_deref()
+        .map_err(|e| MigrationError::Parse(e.clone()))
+}
+
+/// Split a dbmate migration into its up and down halves.
+pub fn parse(name: &'static str, sql: &'static str) -> Result<Migration, MigrationError> {
+    let invalid = |reason: &str| MigrationError::Parse(format!("{name}: {reason}"));
+
+    let digits = name
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(name.len());
+    if digits == 0 {
+        return Err(invalid("the file name should start with a version"));
+    }
+    let version = &name[..digits];
+
+    let (before, options, up) =
+        split_at_marker(sql, "-- migrate:up").ok_or_else(|| invalid("there's no -- migrate:up"))?;
+    // dbmate would quietly skip anything up there.
+    let stray = before
+        .lines()
+        .map(str::trim)
+        .any(|line| !line.is_empty() && !line.starts_with("--"));
+    if stray {
+        return Err(invalid("statements come before -- migrate:up"));
+    }
+    let (up, down) = match split_at_marker(up, "-- migrate:down") {
+        Some((up, _, down)) => (up, down),
+        None => (up, ""),
+    };
+
+    let mut transaction = true;
+    for option in options.split_whitespace() {
+        match option.split_once(':') {
+            Some(("transaction", "true")) => transaction = true,
+            Some(("transaction", "false")) => transaction = false,
+            _ => return Err(invalid(&format!("unknown option {option}"))),
+        }
+    }
+
+    Ok(Migration {
+        version,
+        name,
+        up: up.trim(),
+        down: down.trim(),
+        transaction,
+    })
+}
+
+// Find the line starting with `marker` and return what comes before it,
+// the rest of that line and what comes after it.
+fn split_at_marker<'a>(sql: &'a str, marker: &str) -> Option<(&'a str, &'a str, &'a str)> {
+    let mut offset = 0;
+    for line in sql.split_inclusive('\n') {
+        if let Some(options) = line.strip_prefix(marker) {
+            let end = offset + line.len();
+            return Some((&sql[..offset], options.trim(), &sql[end..]));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// A connection of its own, migrations shouldn't be held to the pool's
+/// statement timeout.
+pub async fn connect(database_url: &str) -> Result<Client, MigrationError> {
+    let (config, tls) = connect::config(database_url)?;
+    let (client, connection) = config.connect(tls).await?;
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("Migration connection error: {}", e);
+        }
+    });
+    Ok(client)
+}
+
+/// Run everything in `crates/db/seeds`, in one transaction. Seeds should
+/// be safe to run more than once, and never on production.
+pub async fn seed(client: &mut Client) -> Result<Vec<&'static str>, MigrationError> {
+    let transaction = client.transaction().await?;
+    for (name, sql) in SEEDS {
+        transaction
+            .batch_execute(sql)
+            .await
+            .map_err(|e| MigrationError::Failed(name, e))?;
+    }
+    transaction.commit().await?;
+    Ok(SEEDS.iter().map(|(name, _)| *name).collect())
+}
+
+/// Every migration and whether it's been applied.
+pub async fn status(client: &Client) -> Result<Vec<Status>, MigrationError> {
+    let applied = applied(client).await?;
+    Ok(migrations()?
+        .iter()
+        .map(|migration| Status {
+            migration: *migration,
+            applied: applied.contains(migration.version),
+        })
+        .collect())
+}
+
+/// Apply everything that hasn't been, oldest first, and return what was.
+/// A dry run only returns what would be.
+pub async fn up(client: &mut Client, dry_run: bool) -> Result<Vec<Migration>, MigrationError> {
+    if dry_run {
+        return pending(client).await;
+    }
+
+    lock(client).await?;
+    let applied = async {
+        client
+            .batch_execute(
+                "CREATE TABLE IF NOT EXISTS schema_migrations (version varchar(128) PRIMARY KEY)",
+            )
+            .await?;
+        let pending = pending(client).await?;
+        for migration in &pending {
+            apply(
+                client,
+                migration,
+                migration.up,
+                "INSERT INTO schema_migrations (version) VALUES ($1)",
+            )
+            .await?;
+        }
+        Ok::<_, MigrationError>(pending)
+    }
+    .await;
+    unlock(client).await?;
+    applied
+}
+
+/// Roll back the latest migration that was applied, if there is one. A dry
+/// run only returns which it would be.
+pub async fn down(client: &mut Client, dry_run: bool) -> Result<Option<Migration>, MigrationError> {
+    if dry_run {
+        return latest(client).await;
+    }
+
+    lock(client).await?;
+    let rolled_back = async {
+        let latest = latest(client).await?;
+        if let Some(migration) = &latest {
+            apply(
+                client,
+                migration,
+                migration.down,
+                "DELETE FROM schema_migrations WHERE version = $1",
+            )
+            .await?;
+        }
+        Ok::<_, MigrationError>(latest)
+    }
+    .await;
+    unlock(client).await?;
+    rolled_back
+}
+
+async fn lock(client: &Client) -> Result<(), MigrationError> {
+    client
+        .execute("SELECT pg_advisory_lock($1)", &[&MIGRATION_LOCK])
+        .await?;
+    Ok(())
+}
+
+async fn unlock(client: &Client) -> Result<(), MigrationError> {
+    client
+        .execute("SELECT pg_advisory_unlock($1)", &[&MIGRATION_LOCK])
+        .await?;
+    Ok(())
+}
+
+async fn pending(client: &Client) -> Result<Vec<Migration>, MigrationError> {
+    Ok(status(client)
+        .await?
+        .into_iter()
+        .filter(|status| !status.applied)
+        .map(|status| status.migration)
+        .collect())
+}
+
+async fn latest(client: &Client) -> Result<Option<Migration>, MigrationError> {
+    Ok(status(client)
+        .await?
+        .into_iter()
+        .rev()
+        .find(|status| status.applied)
+        .map(|status| status.migration))
+}
+
+async fn applied(client: &Client) -> Result<HashSet<String>, MigrationError> {
+    let exists = client
+        .query_one("SELECT to_regclass('schema_migrations') IS NOT NULL", &[])
+        .await?
+        .get::<_, bool>(0);
+    if !exists {
+        return Ok(HashSet::new());
+    }
+    let rows = client
+        .query("SELECT version::text FROM schema_migrations", &[])
+        .await?;
+    Ok(rows.iter().map(|row| row.get(0)).collect())
+}
+
+// Run one half of a migration and record that it ran, both or neither
+// when it's in a transaction.
+async fn apply(
+    client: &mut Client,
+    migration: &Migration,
+    sql: &str,
+    record: &str,
+) -> Result<(), MigrationError> {
+    let failed = |e| MigrationError::Failed(migration.name, e);
+    if migration.transaction {
+        let transaction = client.transaction().await.map_err(failed)?;
+        transaction.batch_execute(sql).await.map_err(failed)?;
+        transaction
+            .execute(record, &[&migration.version])
+            .await
+            .map_err(failed)?;
+        transaction.commit().await.map_err(failed)?;
+    } else {
+        client.batch_execute(sql).await.map_err(failed)?;
+        client
+            .execute(record, &[&migration.version])
+            .await
+            .map_err(failed)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrations_are_split_like_dbmate_does() {
+        let migration = parse(
+            "20250101000000_things.sql",
+            "-- A comment is fine up here.\n\
+            -- migrate:up\n\
+            CREATE TABLE things ();\n\
+            \n\
+            -- migrate:down\n\
+            DROP TABLE things;\n",
+        )
+        .unwrap();
+        assert_eq!(migration.version, "20250101000000");
+        assert_eq!(migration.up, "CREATE TABLE things ();");
+        assert_eq!(migration.down, "DROP TABLE things;");
+        assert!(migration.transaction);
+
+        let migration = parse(
+            "20250101000001_index.sql",
+            "-- migrate:up transaction:false\nCREATE INDEX CONCURRENTLY things_id ON things (id);\n",
+        )
+        .unwrap();
+        assert!(!migration.transaction);
+        assert_eq!(migration.down, "");
+
+        for (name, sql) in [
+            ("things.sql", "-- migrate:up\nSELECT 1;"),
+            ("20250101000002_things.sql", "SELECT 1;"),
+            (
+                "20250101000003_things.sql",
+                "SELECT 1;\n-- migrate:up\nSELECT 2;",
+            ),
+            (
+                "20250101000004_things.sql",
+                "-- migrate:up later:true\nSELECT 1;",
+            ),
+        ] {
+            assert!(
+                matches!(parse(name, sql), Err(MigrationError::Parse(_))),
+                "{name}"
+            );
+        }
+
+        // Everything we ship is valid.
+        assert!(!migrations().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn migrations_go_up_and_down() {
+        let db_url = std::env::var("DATABASE_URL").unwrap();
+        let admin = connect(&db_url).await.unwrap();
+        let database = format!("migrations_test_{}", std::process::id());
+        admin
+            .batch_execute(&format!("DROP DATABASE IF EXISTS {database} WITH (FORCE)"))
+            .await
+            .unwrap();
+        admin
+            .batch_execute(&format!("CREATE DATABASE {database}"))
+            .await
+            .unwrap();
+        let mut client = connect(&crate::connect::with_database(&db_url, &database))
+            .await
+            .unwrap();
+
+        let all = migrations().unwrap();
+        assert_eq!(up(&mut client, true).await.unwrap(), all);
+        assert_eq!(up(&mut client, false).await.unwrap(), all);
+        assert!(up(&mut client, false).await.unwrap().is_empty());
+
+        let last = all.last().copied();
+        assert_eq!(down(&mut client, true).await.unwrap(), last);
+        assert_eq!(down(&mut client, false).await.unwrap(), last);
+        let status = status(&client).await.unwrap();
+        assert!(status[..all.len() - 1].iter().all(|status| status.applied));
+        assert!(!status[all.len() - 1].applied);
+
+        assert_eq!(up(&mut client, false).await.unwrap(), [all[all.len() - 1]]);
+
+        // Migrations leave the tables empty, seeds fill them and can run again.
+        let count = "SELECT COUNT(*) FROM users";
+        let users = client.query_one(count, &[]).await.unwrap();
+        assert_eq!(users.get::<_, i64>(0), 0);
+        for _ in 0..2 {
+            assert_eq!(
+                seed(&mut client).await.unwrap(),
+                ["application_role.sql", "users.sql"]
+            );
+        }
+        let users = client.query_one(count, &[]).await.unwrap();
+        assert_eq!(users.get::<_, i64>(0), 3);
+
+        drop(client);
+        admin
+            .batch_execute(&format!("DROP DATABASE {database} WITH (FORCE)"))
+            .await
+            .unwrap();
+    }
+}
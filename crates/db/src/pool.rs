@@ -0,0 +1,263 @@
+//! The pool hands out our own `Client` and `Transaction` rather than
+//! deadpool's, so that checkouts and queries show up in traces. They deref
+//! to deadpool's types, so everything else works as it always did.
+//!
+//! Spans and metrics go to whichever OpenTelemetry providers are installed
+//! globally. Without any, which is the default, they cost next to nothing.
+
+use std::{
+    ops::{Deref, DerefMut},
+    sync::{LazyLock, Mutex},
+    time::Instant,
+};
+
+use async_trait::async_trait;
+use opentelemetry::{
+    global,
+    metrics::Histogram,
+    trace::{Span, SpanKind, Status, Tracer},
+    KeyValue,
+};
+use tokio_postgres::{
+    types::{BorrowToSql, ToSql},
+    Error, Row, RowStream, Statement, ToStatement,
+};
+
+use crate::{
+    cancel::{self, Checkout},
+    connect::Tls,
+    writes, GenericClient,
+};
+
+// Instruments are made once, so install the meter provider before the
+// first checkout.
+struct Metrics {
+    checkout: Histogram<f64>,
+    operation: Histogram<f64>,
+}
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(|| {
+    let meter = global::meter("db");
+    Metrics {
+        checkout: meter
+            .f64_histogram("db.client.connection.wait_time")
+            .with_unit("s")
+            .with_description("How long it took to get a connection from the pool")
+            .build(),
+        operation: meter
+            .f64_histogram("db.client.operation.duration")
+            .with_unit("s")
+            .with_description("How long queries took")
+            .build(),
+    }
+});
+
+#[derive(Clone)]
+pub struct Pool {
+    pool: deadpool_postgres::Pool,
+    // For cancelling queries, see `cancel`.
+    tls: Tls,
+}
+
+impl Pool {
+    pub(crate) fn new(pool: deadpool_postgres::Pool, tls: Tls) -> Pool {
+        Pool { pool, tls }
+    }
+
+    /// Wait for a connection. The wait is its own span, a long one means
+    /// the pool is too small for the load.
+    pub async fn get(&self) -> Result<Client, deadpool_postgres::PoolError> {
+        let tracer = global::tracer("db");
+        let mut span = tracer.start("pool.checkout");
+        let started = Instant::now();
+
+        let client = self.pool.get().await;
+
+        METRICS
+            .checkout
+            .record(started.elapsed().as_secs_f64(), &[]);
+        if let Err(e) = &client {
+            span.set_status(Status::error(e.to_string()));
+        }
+        span.end();
+
+        client.map(|client| {
+            let checkout = cancel::track(&client, &self.tls);
+            Traced {
+                _checkout: checkout,
+                ..Traced::new(client)
+            }
+        })
+    }
+}
+
+pub type Client = Traced<deadpool_postgres::Client>;
+pub type Transaction<'a> = Traced<deadpool_postgres::Transaction<'a>>;
+
+/// A client or transaction that traces its queries.
+pub struct Traced<C> {
+    // Fields drop in order, so a connection stops being cancellable before
+    // `inner` goes back to the pool for someone else.
+    _checkout: Option<Checkout>,
+    inner: C,
+    // Generated queries prepare their statement right before they run it,
+    // and a `Statement` can't tell us its SQL, so we keep it from there.
+    // Queries given as a string are prepared inside `tokio_postgres` and
+    // are only traced as `postgresql`.
+    prepared: Mutex<Option<String>>,
+}
+
+impl<C> Traced<C> {
+    fn new(inner: C) -> Traced<C> {
+        Traced {
+            _checkout: None,
+            inner,
+            prepared: Mutex::new(None),
+        }
+    }
+
+    fn start(&self) -> (opentelemetry::global::BoxedSpan, Instant) {
+        let statement = self.prepared.lock().unwrap().take();
+        // e.g. "SELECT", the SQL itself is too long for a span name.
+        let operation = statement
+            .as_deref()
+            .and_then(|sql| sql.split_whitespace().next())
+            .map(str::to_uppercase)
+            .unwrap_or_else(|| "postgresql".to_string());
+
+        let tracer = global::tracer("db");
+        let mut attributes = vec![KeyValue::new("db.system", "postgresql")];
+        if let Some(statement) = statement {
+            attributes.push(KeyValue::new("db.statement", statement));
+        }
+        let span = tracer
+            .span_builder(operation)
+            .with_kind(SpanKind::Client)
+            .with_attributes(attributes)
+            .start(&tracer);
+
+        (span, Instant::now())
+    }
+}
+
+fn finish<T>(
+    (mut span, started): (opentelemetry::global::BoxedSpan, Instant),
+    result: Result<T, Error>,
+) -> Result<T, Error> {
+    let ok = result.is_ok();
+    METRICS.operation.record(
+        started.elapsed().as_secs_f64(),
+        &[
+            KeyValue::new("db.system", "postgresql"),
+            KeyValue::new("error", !ok),
+        ],
+    );
+    if let Err(e) = &result {
+        span.set_status(Status::error(e.to_string()));
+    }
+    span.end();
+    result
+}
+
+impl Client {
+    pub async fn transaction(&mut self) -> Result<Transaction<'_>, Error> {
+        let transaction = self.inner.transaction().await?;
+        Ok(Traced::new(transaction))
+    }
+}
+
+impl Transaction<'_> {
+    /// Postgres only gives a transaction an id once it changes something,
+    /// which is how a `WriteTracker` knows.
+    pub async fn commit(self) -> Result<(), Error> {
+        let wrote = writes::watching()
+            && self
+                .inner
+                .query_one("SELECT pg_current_xact_id_if_assigned() IS NOT NULL", &[])
+                .await?
+                .get(0);
+        self.inner.commit().await?;
+        if wrote {
+            writes::wrote();
+        }
+        Ok(())
+    }
+
+    pub async fn rollback(self) -> Result<(), Error> {
+        self.inner.rollback().await
+    }
+}
+
+impl<C> Deref for Traced<C> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        &self.inner
+    }
+}
+
+impl<C> DerefMut for Traced<C> {
+    fn deref_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+}
+
+#[async_trait]
+impl<C: GenericClient> GenericClient for Traced<C> {
+    async fn prepare(&self, query: &str) -> Result<Statement, Error> {
+        *self.prepared.lock().unwrap() = Some(query.to_string());
+        self.inner.prepare(query).await
+    }
+
+    async fn execute<T>(&self, query: &T, params: &[&(dyn ToSql + Sync)]) -> Result<u64, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        let span = self.start();
+        finish(span, self.inner.execute(query, params).await)
+    }
+
+    async fn query_one<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Row, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        let span = self.start();
+        finish(span, self.inner.query_one(statement, params).await)
+    }
+
+    async fn query_opt<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Option<Row>, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        let span = self.start();
+        finish(span, self.inner.query_opt(statement, params).await)
+    }
+
+    async fn query<T>(&self, query: &T, params: &[&(dyn ToSql + Sync)]) -> Result<Vec<Row>, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+    {
+        let span = self.start();
+        finish(span, self.inner.query(query, params).await)
+    }
+
+    // The span ends once the rows start coming, not when they're all read.
+    async fn query_raw<T, P, I>(&self, statement: &T, params: I) -> Result<RowStream, Error>
+    where
+        T: ?Sized + ToStatement + Sync + Send,
+        P: BorrowToSql,
+        I: IntoIterator<Item = P> + Sync + Send,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let span = self.start();
+        finish(span, self.inner.query_raw(statement, params).await)
+    }
+}
@@ -0,0 +1,42 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use crate::Pool;
+
+/// The primary and any read replicas. Writes go to the primary, and so do
+/// reads that need to see them, replicas can be a little behind.
+#[derive(Clone)]
+pub struct Pools {
+    primary: Pool,
+    replicas: Arc<[Pool]>,
+    next: Arc<AtomicUsize>,
+}
+
+impl Pools {
+    pub fn new(primary: Pool, replicas: Vec<Pool>) -> Pools {
+        Pools {
+            primary,
+            replicas: replicas.into(),
+            next: Default::default(),
+        }
+    }
+
+    pub fn write(&self) -> &Pool {
+        &self.primary
+    }
+
+    /// The replicas take turns, without any it's the primary.
+    pub fn read(&self) -> &Pool {
+        if self.replicas.is_empty() {
+            return &self.primary;
+        }
+        let next = self.next.fetch_add(1, Ordering::Relaxed);
+        &self.replicas[next % self.replicas.len()]
+    }
+
+    pub fn has_replicas(&self) -> bool {
+        !self.replicas.is_empty()
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{Client, GenericClient, Pool, TokioPostgresError, Transaction};
+
+/// A pool that logs in as the owner of the tables, so row level security
+/// doesn't apply to it. Requests get a pool that logs in as `application`,
+/// which it always does. This one is for the few things that can't have a
+/// tenant, like finding out who a request is for in the first place,
+/// creating users who aren't in any team yet, and background work.
+#[derive(Clone)]
+pub struct PrivilegedPool(pub Pool);
+
+/// Who a transaction is acting for. Row level security policies only
+/// show rows belonging to this user and team.
+#[derive(Clone, Copy, Debug)]
+pub struct Tenant {
+    pub user_id: i32,
+    pub team_id: i32,
+}
+
+/// Scope an open transaction to the tenant, so everything after this sees
+/// only the tenant's rows. It all resets when the transaction ends, so
+/// pooled connections are safe. On the `PrivilegedPool` it also switches
+/// to the `application` role, which requests are logged in as already.
+pub async fn set_tenant<C: GenericClient>(
+    client: &C,
+    tenant: Tenant,
+) -> Result<(), TokioPostgresError> {
+    client.execute("SET LOCAL ROLE application", &[]).await?;
+    client
+        .execute(
+            "SELECT set_config('app.user_id', $1, true), set_config('app.team_id', $2, true)",
+            &[&tenant.user_id.to_string(), &tenant.team_id.to_string()],
+        )
+        .await?;
+    Ok(())
+}
+
+/// Start a transaction on a pooled client that can only see the tenant's rows.
+pub async fn transaction(
+    client: &mut Client,
+    tenant: Tenant,
+) -> Result<Transaction<'_>, TokioPostgresError> {
+    let transaction = client.transaction().await?;
+    set_tenant(&transaction, tenant).await?;
+    Ok(transaction)
+}
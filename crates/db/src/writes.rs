@@ -0,0 +1,42 @@
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+tokio::task_local! {
+    static TRACKER: WriteTracker;
+}
+
+/// Notices when a future commits a transaction that changed something, so
+/// whoever made the request can stop reading from replicas that might not
+/// have the change yet.
+#[derive(Clone, Default)]
+pub struct WriteTracker {
+    wrote: Arc<AtomicBool>,
+}
+
+impl WriteTracker {
+    /// Run `future`, watching the transactions it commits.
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        TRACKER.scope(self.clone(), future).await
+    }
+
+    pub fn wrote(&self) -> bool {
+        self.wrote.load(Ordering::Relaxed)
+    }
+}
+
+// Finding out costs a query, so only when the answer is still wanted.
+pub(crate) fn watching() -> bool {
+    TRACKER
+        .try_with(|tracker| !tracker.wrote())
+        .unwrap_or(false)
+}
+
+// Called by a transaction once it's committed a change.
+pub(crate) fn wrote() {
+    let _ = TRACKER.try_with(|tracker| tracker.wrote.store(true, Ordering::Relaxed));
+}
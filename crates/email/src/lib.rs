@@ -0,0 +1,110 @@
+mod mailer;
+pub mod templates;
+
+pub use mailer::{EmailError, Mailer, OUTBOX_LIMIT};
+
+use dioxus::prelude::*;
+
+// Wide enough that html2text doesn't wrap most lines.
+const TEXT_WIDTH: usize = 78;
+
+/// A rendered email ready to hand to a [`Mailer`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Email {
+    pub from: String,
+    pub to: String,
+    pub subject: String,
+    pub html: String,
+    pub text: String,
+}
+
+/// Render a template into the HTML and plain text versions of an email.
+pub fn render(body: Element) -> (String, String) {
+    let html = dioxus_ssr::render_element(body);
+    let html = format!("<!DOCTYPE html><html lang='en'>{}</html>", html);
+    let text = html2text::from_read(html.as_bytes(), TEXT_WIDTH)
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    (html, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use templates::Welcome;
+
+    #[test]
+    fn renders_html_and_text() {
+        let (html, text) = render(rsx! {
+            Welcome {
+                email: "ian@test.com"
+            }
+        });
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h1>"));
+        assert!(text.contains("Welcome To Your Application"));
+        assert!(text.contains("ian@test.com"));
+        assert!(!text.contains("<p>"));
+    }
+
+    #[tokio::test]
+    async fn memory_transport_keeps_sent_emails() {
+        let mailer = Mailer::memory("app@test.com");
+
+        let email = mailer.compose(
+            "ian@test.com",
+            "Welcome",
+            rsx! { Welcome { email: "ian@test.com" } },
+        );
+        mailer.send(email).await.unwrap();
+
+        let outbox = mailer.outbox().unwrap();
+        assert_eq!(outbox.len(), 1);
+        assert_eq!(outbox[0].to, "ian@test.com");
+        assert_eq!(outbox[0].subject, "Welcome");
+    }
+
+    #[tokio::test]
+    async fn memory_transport_rejects_bad_addresses() {
+        let mailer = Mailer::memory("app@test.com");
+
+        let email = mailer.compose("not an email", "Welcome", rsx! { p { "Hi" } });
+
+        assert!(mailer.send(email).await.is_err());
+        assert!(mailer.outbox().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn memory_transport_forgets_the_oldest_emails() {
+        let mailer = Mailer::memory("app@test.com");
+
+        for i in 0..OUTBOX_LIMIT + 5 {
+            let email = mailer.compose("ian@test.com", &format!("Email {i}"), rsx! { p { "Hi" } });
+            mailer.send(email).await.unwrap();
+        }
+
+        let outbox = mailer.outbox().unwrap();
+        assert_eq!(outbox.len(), OUTBOX_LIMIT);
+        assert_eq!(outbox[0].subject, format!("Email {}", OUTBOX_LIMIT + 4));
+        assert_eq!(outbox[OUTBOX_LIMIT - 1].subject, "Email 5");
+    }
+
+    // Start Mailpit with `docker run -p 1025:1025 -p 8025:8025 axllent/mailpit`
+    // and set SMTP_URL=smtp://localhost:1025 to run this.
+    #[tokio::test]
+    #[ignore = "needs an SMTP server such as Mailpit"]
+    async fn sends_over_smtp() {
+        let url = std::env::var("SMTP_URL").unwrap();
+        let mailer = Mailer::smtp(&url, "app@test.com").unwrap();
+
+        let email = mailer.compose(
+            "ian@test.com",
+            "Welcome",
+            rsx! { Welcome { email: "ian@test.com" } },
+        );
+
+        mailer.send(email).await.unwrap();
+    }
+}
@@ -0,0 +1,60 @@
+#![allow(non_snake_case)]
+use dioxus::prelude::*;
+
+use crate::{Email, Mailer};
+
+// Email clients ignore stylesheets, so everything is styled inline.
+#[component]
+pub fn EmailLayout(title: String, children: Element) -> Element {
+    rsx! {
+        head {
+            meta {
+                charset: "utf-8"
+            }
+            title {
+                "{title}"
+            }
+        }
+        body {
+            style: "font-family: sans-serif; background-color: #f4f4f5; padding: 24px;",
+            div {
+                style: "max-width: 560px; margin: 0 auto; background-color: #ffffff; padding: 24px; border-radius: 8px;",
+                {children}
+            }
+            p {
+                style: "max-width: 560px; margin: 16px auto; font-size: 12px; color: #71717a;",
+                "You received this email because you have an account with Your Application."
+            }
+        }
+    }
+}
+
+#[component]
+pub fn Welcome(email: String) -> Element {
+    rsx! {
+        EmailLayout {
+            title: "Welcome",
+            h1 {
+                "Welcome To Your Application"
+            }
+            p {
+                "An account has been created for {email}."
+            }
+            p {
+                "You can change your display name, timezone and notifications from the settings page."
+            }
+        }
+    }
+}
+
+pub fn welcome(mailer: &Mailer, to: &str) -> Email {
+    mailer.compose(
+        to,
+        "Welcome To Your Application",
+        rsx! {
+            Welcome {
+                email: to
+            }
+        },
+    )
+}
@@ -0,0 +1,157 @@
+//! Where uploaded files are kept. Everything goes through [`Storage`], so
+//! the rest of the app doesn't know whether that's a directory on disk or
+//! an S3 bucket.
+
+mod local;
+mod s3;
+
+use std::{fmt, sync::Arc};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+pub use local::LocalStorage;
+pub use s3::S3Storage;
+
+#[derive(Debug)]
+pub enum StorageError {
+    Config(String),
+    InvalidKey(String),
+    NotFound(String),
+    Backend(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StorageError::Config(ref cause) => write!(f, "Invalid storage config: {}", cause),
+            StorageError::InvalidKey(ref key) => write!(f, "Invalid storage key: {}", key),
+            StorageError::NotFound(ref key) => write!(f, "No file stored at {}", key),
+            StorageError::Backend(ref cause) => write!(f, "Storage failed: {}", cause),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+/// Somewhere to keep files by key. Keys look like paths, `team/abc123`,
+/// and only use letters, digits, `-`, `_`, `.` and `/`.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn put(&self, key: &str, content_type: &str, bytes: Bytes) -> Result<(), StorageError>;
+
+    async fn get(&self, key: &str) -> Result<Bytes, StorageError>;
+
+    /// Deleting a key that isn't there isn't an error.
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+}
+
+/// Pick a backend from a URL such as `file:///var/lib/uploads` or
+/// `s3://uploads?region=eu-west-1`. Add `endpoint=http://localhost:9000`
+/// for MinIO or anything else that speaks S3. Credentials come from
+/// `AWS_ACCESS_KEY_ID` and `AWS_SECRET_ACCESS_KEY`.
+pub fn from_url(url: &str) -> Result<Arc<dyn Storage>, StorageError> {
+    let parsed = url::Url::parse(url).map_err(|e| StorageError::Config(e.to_string()))?;
+    match parsed.scheme() {
+        "file" => {
+            let root = parsed
+                .to_file_path()
+                .map_err(|_| StorageError::Config(format!("{url} isn't a directory")))?;
+            Ok(Arc::new(LocalStorage::new(root)))
+        }
+        "s3" => Ok(Arc::new(S3Storage::from_url(&parsed)?)),
+        scheme => Err(StorageError::Config(format!(
+            "{scheme} storage isn't supported"
+        ))),
+    }
+}
+
+// Keys are made by the app, but check them anyway so that one can never
+// point outside the storage.
+fn check_key(key: &str) -> Result<(), StorageError> {
+    let valid = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'))
+        && key
+            .split('/')
+            .all(|part| !part.is_empty() && part != "." && part != "..");
+    if valid {
+        Ok(())
+    } else {
+        Err(StorageError::InvalidKey(key.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+
+    async fn round_trip(storage: &dyn Storage, key: &str) {
+        let bytes = Bytes::from_static(b"%PDF-1.7 not really");
+        storage
+            .put(key, "application/pdf", bytes.clone())
+            .await
+            .unwrap();
+        assert_eq!(storage.get(key).await.unwrap(), bytes);
+
+        storage.delete(key).await.unwrap();
+        assert!(matches!(
+            storage.get(key).await,
+            Err(StorageError::NotFound(_))
+        ));
+        storage.delete(key).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn local_storage_keeps_files_under_its_root() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("storage-{nanos}"));
+        let storage = from_url(&format!("file://{}", root.display())).unwrap();
+
+        round_trip(storage.as_ref(), "1/report.pdf").await;
+
+        for key in [
+            "",
+            "../etc/passwd",
+            "1/../../x",
+            "/etc/passwd",
+            "1//x",
+            "a b",
+        ] {
+            assert!(
+                matches!(
+                    storage.put(key, "text/plain", Bytes::new()).await,
+                    Err(StorageError::InvalidKey(_))
+                ),
+                "{key:?} should be rejected"
+            );
+        }
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn urls_pick_the_backend() {
+        assert!(from_url("file:///tmp/uploads").is_ok());
+        assert!(from_url("ftp://example.com/uploads").is_err());
+        assert!(from_url("not a url").is_err());
+    }
+
+    // Start MinIO with `docker run -p 9000:9000 minio/minio server /data`,
+    // create a bucket called uploads and set
+    // STORAGE_URL=s3://uploads?endpoint=http://localhost:9000 along with
+    // AWS_ACCESS_KEY_ID=minioadmin and AWS_SECRET_ACCESS_KEY=minioadmin.
+    #[tokio::test]
+    #[ignore = "needs an S3 server such as MinIO"]
+    async fn s3_storage_keeps_files_in_the_bucket() {
+        let storage = from_url(&std::env::var("STORAGE_URL").unwrap()).unwrap();
+
+        round_trip(storage.as_ref(), "1/report.pdf").await;
+    }
+}
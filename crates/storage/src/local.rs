@@ -0,0 +1,60 @@
+use std::{io, path::PathBuf};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use crate::{check_key, Storage, StorageError};
+
+/// Files in a directory, which is created when the first one is stored.
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> LocalStorage {
+        LocalStorage { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, StorageError> {
+        check_key(key)?;
+        Ok(self.root.join(key))
+    }
+}
+
+fn backend(e: io::Error) -> StorageError {
+    StorageError::Backend(e.to_string())
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    // The content type lives with the rest of the file's details in
+    // Postgres, there's nowhere to put it on disk.
+    async fn put(&self, key: &str, _content_type: &str, bytes: Bytes) -> Result<(), StorageError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(backend)?;
+        }
+        // Written to the side and renamed, so a half written file is never
+        // served.
+        let partial = path.with_extension("partial");
+        tokio::fs::write(&partial, &bytes).await.map_err(backend)?;
+        tokio::fs::rename(&partial, &path).await.map_err(backend)
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, StorageError> {
+        match tokio::fs::read(self.path(key)?).await {
+            Ok(bytes) => Ok(bytes.into()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                Err(StorageError::NotFound(key.to_string()))
+            }
+            Err(e) => Err(backend(e)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(backend(e)),
+            _ => Ok(()),
+        }
+    }
+}
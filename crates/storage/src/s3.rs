@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use reqwest::{header::CONTENT_TYPE, StatusCode};
+use rusty_s3::{Bucket, Credentials, S3Action, UrlStyle};
+use url::Url;
+
+use crate::{check_key, Storage, StorageError};
+
+// Requests are signed just before they're sent, so this only needs to
+// cover the request itself.
+const SIGNED_FOR: Duration = Duration::from_secs(60);
+
+/// Files in an S3 bucket, or anything that speaks the same API like MinIO.
+pub struct S3Storage {
+    bucket: Bucket,
+    credentials: Credentials,
+    client: reqwest::Client,
+}
+
+impl S3Storage {
+    pub fn new(bucket: Bucket, credentials: Credentials) -> S3Storage {
+        S3Storage {
+            bucket,
+            credentials,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// See `storage::from_url`.
+    pub fn from_url(url: &Url) -> Result<S3Storage, StorageError> {
+        let name = url
+            .host_str()
+            .ok_or_else(|| StorageError::Config("No bucket in the storage URL".into()))?;
+        let query = |key: &str| {
+            url.query_pairs()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.into_owned())
+        };
+        let region = query("region").unwrap_or_else(|| "us-east-1".to_string());
+
+        // MinIO and friends don't do bucket subdomains.
+        let (endpoint, style) = match query("endpoint") {
+            Some(endpoint) => (endpoint, UrlStyle::Path),
+            None => (
+                format!("https://s3.{region}.amazonaws.com"),
+                UrlStyle::VirtualHost,
+            ),
+        };
+        let endpoint = endpoint
+            .parse()
+            .map_err(|e: url::ParseError| StorageError::Config(e.to_string()))?;
+        let bucket = Bucket::new(endpoint, style, name.to_string(), region)
+            .map_err(|e| StorageError::Config(e.to_string()))?;
+
+        let credentials = Credentials::from_env().ok_or_else(|| {
+            StorageError::Config("AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY not set".into())
+        })?;
+
+        Ok(S3Storage::new(bucket, credentials))
+    }
+}
+
+fn backend(e: reqwest::Error) -> StorageError {
+    StorageError::Backend(e.to_string())
+}
+
+async fn check(response: reqwest::Response, key: &str) -> Result<reqwest::Response, StorageError> {
+    match response.status() {
+        status if status.is_success() => Ok(response),
+        StatusCode::NOT_FOUND => Err(StorageError::NotFound(key.to_string())),
+        status => {
+            let body = response.text().await.unwrap_or_default();
+            Err(StorageError::Backend(format!("{status}: {body}")))
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for S3Storage {
+    async fn put(&self, key: &str, content_type: &str, bytes: Bytes) -> Result<(), StorageError> {
+        check_key(key)?;
+        let mut action = self.bucket.put_object(Some(&self.credentials), key);
+        action.headers_mut().insert("content-type", content_type);
+        let url = action.sign(SIGNED_FOR);
+
+        let response = self
+            .client
+            .put(url)
+            .header(CONTENT_TYPE, content_type)
+            .body(bytes)
+            .send()
+            .await
+            .map_err(backend)?;
+        check(response, key).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, StorageError> {
+        check_key(key)?;
+        let url = self
+            .bucket
+            .get_object(Some(&self.credentials), key)
+            .sign(SIGNED_FOR);
+
+        let response = self.client.get(url).send().await.map_err(backend)?;
+        check(response, key).await?.bytes().await.map_err(backend)
+    }
+
+    // S3 answers 204 whether or not the key was there.
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        check_key(key)?;
+        let url = self
+            .bucket
+            .delete_object(Some(&self.credentials), key)
+            .sign(SIGNED_FOR);
+
+        let response = self.client.delete(url).send().await.map_err(backend)?;
+        check(response, key).await?;
+        Ok(())
+    }
+}
@@ -11,5 +11,10 @@ module.exports = {
   plugins: [
     require("daisyui"),
     require('@tailwindcss/typography')
-  ]
+  ],
+  /** Picked by data-theme on <html>, with dark for users whose system asks for it */
+  daisyui: {
+    themes: ["light", "dark"],
+    darkTheme: "dark"
+  }
 }
\ No newline at end of file
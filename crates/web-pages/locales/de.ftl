@@ -0,0 +1,194 @@
+## Layout
+
+app-name = Deine Anwendung
+nav-menu = Dein Menü
+nav-users = Benutzer
+nav-team = Team
+nav-jobs = Fehlgeschlagene Jobs
+nav-audit = Audit-Log
+nav-files = Dateien
+nav-settings = Einstellungen
+nav-feature-flags = Feature-Flags
+signed-in-as = Angemeldet als { $name }
+
+## Shared
+
+email = E-Mail
+email-placeholder = z. B. ian@test.com
+name = Name
+role = Rolle
+role-administrator = Administrator
+role-member = Mitglied
+role-read-only = Nur Lesen
+cancel = Abbrechen
+submit = Absenden
+save = Speichern
+search = Suchen
+filter = Filtern
+
+## Users
+
+users-title = Benutzertabelle
+users-welcome-heading = Willkommen in deiner Anwendung
+users-welcome-description = Das ist erst der Anfang
+users-search-placeholder = Nach E-Mail suchen
+users-export = CSV exportieren
+users-id = ID
+users-created = Erstellt
+users-add = Benutzer hinzufügen
+users-email-help = Bitte gib eine E-Mail-Adresse ein
+users-import = Benutzer importieren
+users-import-help = Eine CSV-Datei mit einer Spalte email, wie beim Export. Wer schon ein Konto hat, wird eingeladen statt hinzugefügt.
+users-import-preview = Vorschau
+users-import-errors = Zeilen mit Fehlern
+users-import-line = Zeile
+users-import-problem = Problem
+users-import-no-email-column = Die erste Zeile braucht eine Spalte email
+users-import-unreadable = Die Zeile ist kein gültiges CSV
+users-import-missing-email = Die Zeile hat keine E-Mail-Adresse
+users-import-invalid-email = { $email } ist keine gültige E-Mail-Adresse
+users-import-ready = Bereit zum Import
+users-import-summary =
+    { $count ->
+        [one] Eine gültige Zeile wird dem Team hinzugefügt. Zeilen mit Fehlern werden übersprungen.
+       *[other] { $rows } gültige Zeilen werden dem Team hinzugefügt. Zeilen mit Fehlern werden übersprungen.
+    }
+users-import-confirm =
+    { $count ->
+        [one] 1 Benutzer importieren
+       *[other] { $rows } Benutzer importieren
+    }
+
+## Team
+
+team-title = Team
+team-switcher-new = Team erstellen oder beitreten
+team-members = Mitglieder
+team-joined = Beigetreten
+team-pending-invitations = Offene Einladungen
+team-invited = Eingeladen
+team-invite = Jemanden einladen
+team-invite-help = Die Einladung kann auf der Team-Seite angenommen werden, nachdem man sich mit dieser E-Mail angemeldet hat
+team-invite-send = Einladung senden
+team-your-invitations = Deine Einladungen
+team-team = Team
+team-accept = Annehmen
+team-create = Team erstellen
+team-name-placeholder = z. B. Acme GmbH
+team-create-button = Team erstellen
+
+## Failed jobs
+
+jobs-title = Fehlgeschlagene Jobs
+jobs-empty-heading = Keine fehlgeschlagenen Jobs
+jobs-empty-description = Jobs mit Fehlern erscheinen hier, solange sie wiederholt werden oder wenn sie aufgegeben wurden
+jobs-id = ID
+jobs-kind = Art
+jobs-status = Status
+jobs-attempts = Versuche
+jobs-error = Fehler
+jobs-updated = Aktualisiert
+jobs-next-run = Nächster Lauf
+jobs-dead = Aufgegeben
+jobs-retrying = Wird wiederholt
+jobs-retry = Wiederholen
+
+## Audit log
+
+audit-title = Audit-Log
+audit-actor = Akteur
+audit-actor-system = System
+audit-target = Ziel
+audit-target-any = Alle
+audit-target-id = Ziel-ID
+audit-from = Von
+audit-to = Bis
+audit-when = Wann
+audit-action = Aktion
+audit-changes = Änderungen
+audit-ip = IP
+audit-user-agent = User-Agent
+
+## Settings
+
+settings-title = Einstellungen
+settings-preferences = Präferenzen
+settings-display-name = Anzeigename
+settings-display-name-placeholder = z. B. Ian
+settings-display-name-help = So wird dein Name anderen Benutzern angezeigt
+settings-timezone = Zeitzone
+settings-timezone-help = Datum und Uhrzeit werden in dieser Zeitzone angezeigt
+settings-language = Sprache
+settings-language-help = Seiten werden in dieser Sprache angezeigt
+settings-language-automatic = Wie mein Browser
+settings-theme = Design
+settings-theme-system = System
+settings-theme-light = Hell
+settings-theme-dark = Dunkel
+settings-notify-product-updates = Per E-Mail über Produktneuigkeiten informieren
+settings-notify-security-alerts = Per E-Mail über Sicherheitswarnungen informieren
+settings-error-display_name = Der Anzeigename darf höchstens 100 Zeichen lang sein
+settings-error-timezone = Bitte wähle eine gültige Zeitzone
+settings-error-theme = Bitte wähle ein gültiges Design
+settings-error-locale = Bitte wähle eine gültige Sprache
+settings-avatar = Profilbild
+settings-avatar-help = Ein PNG-, JPEG-, GIF- oder WebP-Bild mit bis zu 2 MB
+settings-avatar-upload = Profilbild hochladen
+api-keys-title = API-Schlüssel
+api-keys-created = Kopiere deinen neuen Schlüssel jetzt, er wird nicht noch einmal angezeigt.
+api-keys-key = Schlüssel
+api-keys-scopes = Berechtigungen
+api-keys-created-at = Erstellt
+api-keys-last-used = Zuletzt benutzt
+api-keys-never = Nie
+api-keys-revoke = Widerrufen
+api-keys-name-placeholder = z. B. Deploy-Skript
+api-keys-create = API-Schlüssel erstellen
+
+## Feature flags
+
+feature-flags-title = Feature-Flags
+feature-flags-empty-heading = Noch keine Feature-Flags
+feature-flags-empty-description = Mit Flags kannst du Funktionen für einige Benutzer freischalten, bevor alle sie bekommen
+feature-flags-on-for-you = Für dich an
+feature-flags-off-for-you = Für dich aus
+feature-flags-updated = Aktualisiert
+feature-flags-enabled = Für alle an
+feature-flags-rollout = Rollout %
+feature-flags-rollout-help = Anteil der Benutzer, die es sehen, jedes Mal dieselben
+feature-flags-user-ids = Benutzer-IDs
+feature-flags-team-ids = Team-IDs
+feature-flags-ids-help = Durch Kommas getrennt, für diese immer an
+feature-flags-new = Neues Feature-Flag
+feature-flags-name-placeholder = z. B. new-dashboard
+feature-flags-description = Beschreibung
+feature-flags-create = Feature-Flag erstellen
+
+## Files
+
+files-title = Dateien
+files-empty-heading = Noch keine Dateien
+files-empty-description = Dateien, die ins Team hochgeladen werden, erscheinen hier
+files-type = Typ
+files-size = Größe
+files-uploaded-by = Hochgeladen von
+files-uploaded = Hochgeladen
+files-upload = Datei hochladen
+files-upload-help = Bilder, PDFs, Text, CSV, Zip und Office-Dokumente
+
+## Mailbox
+
+mailbox-title = Postfach
+mailbox-empty-heading = Noch keine E-Mails
+mailbox-empty-description = In der Entwicklung gesendete E-Mails landen hier, statt zugestellt zu werden
+mailbox-from = Von: { $from }
+mailbox-to = An: { $to }
+mailbox-plain-text = Nur Text
+
+## Errors
+
+error-server-heading = Etwas ist schiefgelaufen
+error-server-description = Wir haben das Problem protokolliert, bitte versuche es noch einmal.
+error-timeout-heading = Das hat zu lange gedauert
+error-timeout-description = Wir haben aufgehört, auf diese Seite zu warten, bitte versuche es gleich noch einmal.
+error-request-id = Request-ID:
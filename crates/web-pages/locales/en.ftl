@@ -0,0 +1,194 @@
+## Layout
+
+app-name = Your Application
+nav-menu = Your Menu
+nav-users = Users
+nav-team = Team
+nav-jobs = Failed Jobs
+nav-audit = Audit Log
+nav-files = Files
+nav-settings = Settings
+nav-feature-flags = Feature Flags
+signed-in-as = Signed in as { $name }
+
+## Shared
+
+email = Email
+email-placeholder = e.g. ian@test.com
+name = Name
+role = Role
+role-administrator = Administrator
+role-member = Member
+role-read-only = Read Only
+cancel = Cancel
+submit = Submit
+save = Save
+search = Search
+filter = Filter
+
+## Users
+
+users-title = Users Table
+users-welcome-heading = Welcome To Your Application
+users-welcome-description = This is just the beginning
+users-search-placeholder = Search by email
+users-export = Export CSV
+users-id = ID
+users-created = Created
+users-add = Add User
+users-email-help = Please enter an email address
+users-import = Import Users
+users-import-help = A CSV file with an email column, like the export. People who already have an account are invited rather than added.
+users-import-preview = Preview
+users-import-errors = Rows With Errors
+users-import-line = Line
+users-import-problem = Problem
+users-import-no-email-column = The first line needs an email column
+users-import-unreadable = The line couldn't be read as CSV
+users-import-missing-email = The line has no email
+users-import-invalid-email = { $email } isn't a valid email address
+users-import-ready = Ready To Import
+users-import-summary =
+    { $count ->
+        [one] One valid row will be added to the team. Rows with errors are skipped.
+       *[other] { $rows } valid rows will be added to the team. Rows with errors are skipped.
+    }
+users-import-confirm =
+    { $count ->
+        [one] Import 1 User
+       *[other] Import { $rows } Users
+    }
+
+## Team
+
+team-title = Team
+team-switcher-new = Create or join a team
+team-members = Members
+team-joined = Joined
+team-pending-invitations = Pending Invitations
+team-invited = Invited
+team-invite = Invite Someone
+team-invite-help = They can accept from the Team page after signing in with this email
+team-invite-send = Send Invitation
+team-your-invitations = Your Invitations
+team-team = Team
+team-accept = Accept
+team-create = Create a Team
+team-name-placeholder = e.g. Acme Inc
+team-create-button = Create Team
+
+## Failed jobs
+
+jobs-title = Failed Jobs
+jobs-empty-heading = No failed jobs
+jobs-empty-description = Jobs that error show up here while they retry or once they give up
+jobs-id = ID
+jobs-kind = Kind
+jobs-status = Status
+jobs-attempts = Attempts
+jobs-error = Error
+jobs-updated = Updated
+jobs-next-run = Next Run
+jobs-dead = Dead
+jobs-retrying = Retrying
+jobs-retry = Retry
+
+## Audit log
+
+audit-title = Audit Log
+audit-actor = Actor
+audit-actor-system = System
+audit-target = Target
+audit-target-any = Any
+audit-target-id = Target ID
+audit-from = From
+audit-to = To
+audit-when = When
+audit-action = Action
+audit-changes = Changes
+audit-ip = IP
+audit-user-agent = User Agent
+
+## Settings
+
+settings-title = Settings
+settings-preferences = Preferences
+settings-display-name = Display Name
+settings-display-name-placeholder = e.g. Ian
+settings-display-name-help = How your name is shown to other users
+settings-timezone = Timezone
+settings-timezone-help = Dates and times are shown in this timezone
+settings-language = Language
+settings-language-help = Pages are shown in this language
+settings-language-automatic = Same as my browser
+settings-theme = Theme
+settings-theme-system = System
+settings-theme-light = Light
+settings-theme-dark = Dark
+settings-notify-product-updates = Email me about product updates
+settings-notify-security-alerts = Email me about security alerts
+settings-error-display_name = Display name must be 100 characters or less
+settings-error-timezone = Please choose a valid timezone
+settings-error-theme = Please choose a valid theme
+settings-error-locale = Please choose a valid language
+settings-avatar = Avatar
+settings-avatar-help = A PNG, JPEG, GIF or WebP image of up to 2 MB
+settings-avatar-upload = Upload Avatar
+api-keys-title = API Keys
+api-keys-created = Copy your new key now, you won't be able to see it again.
+api-keys-key = Key
+api-keys-scopes = Scopes
+api-keys-created-at = Created
+api-keys-last-used = Last Used
+api-keys-never = Never
+api-keys-revoke = Revoke
+api-keys-name-placeholder = e.g. Deploy script
+api-keys-create = Create API Key
+
+## Feature flags
+
+feature-flags-title = Feature Flags
+feature-flags-empty-heading = No feature flags yet
+feature-flags-empty-description = Flags let you turn features on for some users before everyone
+feature-flags-on-for-you = On for you
+feature-flags-off-for-you = Off for you
+feature-flags-updated = Updated
+feature-flags-enabled = On for everyone
+feature-flags-rollout = Rollout %
+feature-flags-rollout-help = Share of users who see it, the same users each time
+feature-flags-user-ids = User IDs
+feature-flags-team-ids = Team IDs
+feature-flags-ids-help = Separated by commas, always on for these
+feature-flags-new = New Feature Flag
+feature-flags-name-placeholder = e.g. new-dashboard
+feature-flags-description = Description
+feature-flags-create = Create Feature Flag
+
+## Files
+
+files-title = Files
+files-empty-heading = No files yet
+files-empty-description = Files uploaded to the team show up here
+files-type = Type
+files-size = Size
+files-uploaded-by = Uploaded By
+files-uploaded = Uploaded
+files-upload = Upload File
+files-upload-help = Images, PDFs, text, CSV, zip and Office documents
+
+## Mailbox
+
+mailbox-title = Mailbox
+mailbox-empty-heading = No emails yet
+mailbox-empty-description = Emails sent in development are captured here instead of being delivered
+mailbox-from = From: { $from }
+mailbox-to = To: { $to }
+mailbox-plain-text = Plain text
+
+## Errors
+
+error-server-heading = Something went wrong
+error-server-description = We've logged the problem, please try again.
+error-timeout-heading = That took too long
+error-timeout-description = We gave up waiting for this page, please try again in a moment.
+error-request-id = Request ID:
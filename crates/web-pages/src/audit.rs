@@ -0,0 +1,181 @@
+use crate::{
+    datetime::format_timestamp,
+    i18n::Locale,
+    layout::{Layout, SideBar},
+    render,
+    teams::Teams,
+};
+use daisy_rsx::*;
+use db::{queries::audit_events::AuditLogEntry, UserSettings};
+use dioxus::prelude::*;
+
+/// The filters currently applied, so the form can show them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AuditFilters {
+    pub actor: String,
+    pub target_type: String,
+    pub target_id: String,
+    pub from: String,
+    pub to: String,
+}
+
+pub fn index(
+    events: Vec<AuditLogEntry>,
+    target_types: Vec<String>,
+    filters: AuditFilters,
+    locale: Locale,
+    settings: UserSettings,
+    teams: Teams,
+) -> String {
+    let team_id = teams.current.id;
+    let theme = settings.theme;
+    let timezone = settings.timezone.clone();
+    let page = rsx! {
+        Layout {    // <-- Use our layout
+            title: locale.t("audit-title"),
+            selected_item: SideBar::Audit,
+            locale,
+            settings,
+            teams,
+            Card {
+                class: "card-bordered",
+                CardHeader {
+                    class: "p-3 border-b",
+                    title: locale.t("filter")
+                }
+                CardBody {
+                    class: "p-3",
+                    form {
+                        class: "grid grid-cols-1 md:grid-cols-5 gap-4 items-end",
+                        action: "/teams/{team_id}/audit",
+                        method: "GET",
+                        div {
+                            class: "flex flex-col",
+                            Input {
+                                input_type: InputType::Text,
+                                placeholder: locale.t("email-placeholder"),
+                                label: locale.t("audit-actor"),
+                                name: "actor",
+                                value: filters.actor
+                            }
+                        }
+                        div {
+                            class: "flex flex-col",
+                            Select {
+                                label: locale.t("audit-target"),
+                                name: "target_type",
+                                value: filters.target_type.clone(),
+                                SelectOption {
+                                    value: "",
+                                    selected_value: filters.target_type.clone(),
+                                    {locale.t("audit-target-any")}
+                                }
+                                for target_type in target_types {
+                                    SelectOption {
+                                        value: target_type.clone(),
+                                        selected_value: filters.target_type.clone(),
+                                        "{target_type}"
+                                    }
+                                }
+                            }
+                        }
+                        div {
+                            class: "flex flex-col",
+                            Input {
+                                input_type: InputType::Number,
+                                label: locale.t("audit-target-id"),
+                                name: "target_id",
+                                value: filters.target_id
+                            }
+                        }
+                        div {
+                            class: "flex flex-col",
+                            label { {locale.t("audit-from")} }
+                            input {
+                                class: "input input-bordered input-sm",
+                                "type": "date",
+                                name: "from",
+                                value: filters.from
+                            }
+                        }
+                        div {
+                            class: "flex flex-col",
+                            label { {locale.t("audit-to")} }
+                            input {
+                                class: "input input-bordered input-sm",
+                                "type": "date",
+                                name: "to",
+                                value: filters.to
+                            }
+                        }
+                        Button {
+                            button_type: ButtonType::Submit,
+                            button_scheme: ButtonScheme::Primary,
+                            {locale.t("filter")}
+                        }
+                    }
+                }
+            }
+            Card {
+                class: "card-bordered mt-12 has-data-table",
+                CardHeader {
+                    class: "p-3 border-b",
+                    title: locale.t("audit-title")
+                }
+                CardBody {
+                    class: "p-0",
+                    table {
+                        class: "table table-sm",
+                        thead {
+                            tr {
+                                th { {locale.t("audit-when")} }
+                                th { {locale.t("audit-actor")} }
+                                th { {locale.t("audit-action")} }
+                                th { {locale.t("audit-target")} }
+                                th { {locale.t("audit-changes")} }
+                                th { {locale.t("audit-ip")} }
+                                th { {locale.t("audit-user-agent")} }
+                            }
+                        }
+                        tbody {
+                            for event in events {
+                                tr {
+                                    td {
+                                        {format_timestamp(event.created_at, &timezone, locale)}
+                                    }
+                                    td {
+                                        {event.actor_email.unwrap_or_else(|| locale.t("audit-actor-system"))}
+                                    }
+                                    td {
+                                        "{event.action}"
+                                    }
+                                    td {
+                                        "{event.target_type}"
+                                        if let Some(target_id) = event.target_id {
+                                            " #{target_id}"
+                                        }
+                                    }
+                                    td {
+                                        code {
+                                            class: "text-xs",
+                                            "{event.diff}"
+                                        }
+                                    }
+                                    td {
+                                        {event.ip_address.map(|ip| ip.to_string()).unwrap_or_default()}
+                                    }
+                                    td {
+                                        class: "text-xs",
+                                        {event.user_agent.unwrap_or_default()}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    render(page, locale, theme)
+}
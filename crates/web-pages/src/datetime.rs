@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use time::OffsetDateTime;
+
+use crate::i18n::Locale;
+
+/// Every timezone name a user can pick on the settings page.
+pub fn timezones() -> impl Iterator<Item = &'static str> {
+    chrono_tz::TZ_VARIANTS.iter().map(|tz| tz.name())
+}
+
+/// Render a timestamp in the users timezone and the order their locale
+/// writes dates in, falling back to UTC if we don't recognise the timezone
+/// name.
+pub fn format_timestamp(timestamp: OffsetDateTime, timezone: &str, locale: Locale) -> String {
+    let tz: Tz = timezone.parse().unwrap_or(Tz::UTC);
+    let format = match locale {
+        Locale::En => "%Y-%m-%d %H:%M %Z",
+        Locale::De => "%d.%m.%Y %H:%M %Z",
+    };
+
+    match DateTime::<Utc>::from_timestamp(timestamp.unix_timestamp(), 0) {
+        Some(utc) => utc.with_timezone(&tz).format(format).to_string(),
+        None => timestamp.to_string(),
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{i18n::Locale, render};
+use daisy_rsx::*;
+use db::Theme;
+use dioxus::prelude::*;
+use web_assets::files::{favicon_svg, tailwind_css};
+
+// These pages are shown when we can't trust anything else to work, so
+// they don't use the Layout, which needs the user and their teams.
+fn error_page(
+    title: &str,
+    message: &str,
+    request_id: Option<&str>,
+    locale: Locale,
+    theme: Theme,
+) -> String {
+    let page = rsx! {
+        head {
+            title { "{title}" }
+            meta {
+                charset: "utf-8"
+            }
+            link {
+                rel: "stylesheet",
+                href: tailwind_css.name,
+                "type": "text/css"
+            }
+        }
+        body {
+            div {
+                class: "flex h-screen items-center justify-center",
+                div {
+                    BlankSlate {
+                        heading: title,
+                        visual: favicon_svg.name,
+                        description: message,
+                    }
+                    if let Some(request_id) = request_id {
+                        p {
+                            class: "mt-4 text-center text-xs",
+                            {locale.t("error-request-id")}
+                            " "
+                            code { "{request_id}" }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    render(page, locale, theme)
+}
+
+/// The 500 page. Quote the request ID to find the error in the logs.
+pub fn server_error(request_id: Option<&str>, locale: Locale, theme: Theme) -> String {
+    error_page(
+        &locale.t("error-server-heading"),
+        &locale.t("error-server-description"),
+        request_id,
+        locale,
+        theme,
+    )
+}
+
+pub fn timed_out(request_id: Option<&str>, locale: Locale, theme: Theme) -> String {
+    error_page(
+        &locale.t("error-timeout-heading"),
+        &locale.t("error-timeout-description"),
+        request_id,
+        locale,
+        theme,
+    )
+}
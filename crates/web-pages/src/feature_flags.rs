@@ -0,0 +1,204 @@
+#![allow(non_snake_case)]
+use std::collections::BTreeSet;
+
+use crate::{
+    datetime::format_timestamp,
+    i18n::Locale,
+    layout::{Layout, SideBar},
+    render,
+    teams::Teams,
+};
+use daisy_rsx::*;
+use db::{FeatureFlag, UserSettings};
+use dioxus::prelude::*;
+use web_assets::files::favicon_svg;
+
+/// The flags that are on for whoever is looking at the page. The server
+/// works this out, pages only ask.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Flags {
+    enabled: BTreeSet<String>,
+}
+
+impl Flags {
+    /// Flags that don't exist are off, so a page can check for one before
+    /// it's been created.
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.enabled.contains(name)
+    }
+}
+
+impl FromIterator<String> for Flags {
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Flags {
+        Flags {
+            enabled: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Shows its children only when the flag is on, and `fallback`, if there
+/// is one, when it's off.
+#[component]
+pub fn Feature(
+    flags: Flags,
+    name: String,
+    fallback: Option<Element>,
+    children: Element,
+) -> Element {
+    if flags.is_enabled(&name) {
+        children
+    } else {
+        fallback.unwrap_or_else(|| rsx! {})
+    }
+}
+
+/// Every flag, with a form for each. Only site admins get here, flags
+/// aren't owned by a team.
+pub fn index(
+    feature_flags: Vec<FeatureFlag>,
+    flags: Flags,
+    locale: Locale,
+    settings: UserSettings,
+    teams: Teams,
+) -> String {
+    let team_id = teams.current.id;
+    let theme = settings.theme;
+    let timezone = settings.timezone.clone();
+    let page = rsx! {
+        Layout {    // <-- Use our layout
+            title: locale.t("feature-flags-title"),
+            selected_item: SideBar::FeatureFlags,
+            locale,
+            settings,
+            teams,
+            if feature_flags.is_empty() {
+                BlankSlate {
+                    heading: locale.t("feature-flags-empty-heading"),
+                    visual: favicon_svg.name,
+                    description: locale.t("feature-flags-empty-description"),
+                }
+            }
+            for flag in feature_flags {
+                Card {
+                    class: "card-bordered mt-12",
+                    CardHeader {
+                        class: "p-3 border-b",
+                        title: flag.name.clone(),
+                        Feature {
+                            flags: flags.clone(),
+                            name: flag.name.clone(),
+                            fallback: rsx!(
+                                Label {
+                                    {locale.t("feature-flags-off-for-you")}
+                                }
+                            ),
+                            Label {
+                                label_role: LabelRole::Success,
+                                {locale.t("feature-flags-on-for-you")}
+                            }
+                        }
+                    }
+                    CardBody {
+                        class: "p-3",
+                        p {
+                            class: "text-sm",
+                            "{flag.description}"
+                        }
+                        p {
+                            class: "text-xs mb-4",
+                            {locale.t("feature-flags-updated")}
+                            " "
+                            {format_timestamp(flag.updated_at, &timezone, locale)}
+                        }
+                        form {
+                            class: "flex flex-col",
+                            action: "/teams/{team_id}/feature_flags/{flag.id}",
+                            method: "POST",
+
+                            label {
+                                class: "label cursor-pointer justify-start gap-2",
+                                CheckBox {
+                                    name: "enabled",
+                                    value: "true",
+                                    checked: flag.enabled,
+                                }
+                                span { {locale.t("feature-flags-enabled")} }
+                            }
+                            Input {
+                                input_type: InputType::Number,
+                                label: locale.t("feature-flags-rollout"),
+                                help_text: locale.t("feature-flags-rollout-help"),
+                                name: "rollout_percentage",
+                                value: flag.rollout_percentage.to_string(),
+                                required: true
+                            }
+                            Input {
+                                input_type: InputType::Text,
+                                label: locale.t("feature-flags-user-ids"),
+                                help_text: locale.t("feature-flags-ids-help"),
+                                name: "user_ids",
+                                value: join_ids(&flag.user_ids)
+                            }
+                            Input {
+                                input_type: InputType::Text,
+                                label: locale.t("feature-flags-team-ids"),
+                                help_text: locale.t("feature-flags-ids-help"),
+                                name: "team_ids",
+                                value: join_ids(&flag.team_ids)
+                            }
+                            Button {
+                                class: "mt-4",
+                                button_type: ButtonType::Submit,
+                                button_scheme: ButtonScheme::Primary,
+                                {locale.t("save")}
+                            }
+                        }
+                    }
+                }
+            }
+            Card {
+                class: "card-bordered mt-12",
+                CardHeader {
+                    class: "p-3 border-b",
+                    title: locale.t("feature-flags-new")
+                }
+                CardBody {
+                    class: "p-3",
+                    form {
+                        class: "flex flex-col",
+                        action: "/teams/{team_id}/feature_flags",
+                        method: "POST",
+
+                        Input {
+                            input_type: InputType::Text,
+                            placeholder: locale.t("feature-flags-name-placeholder"),
+                            required: true,
+                            label: locale.t("name"),
+                            name: "name"
+                        }
+                        Input {
+                            input_type: InputType::Text,
+                            label: locale.t("feature-flags-description"),
+                            name: "description"
+                        }
+                        Button {
+                            class: "mt-4",
+                            button_type: ButtonType::Submit,
+                            button_scheme: ButtonScheme::Primary,
+                            {locale.t("feature-flags-create")}
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    render(page, locale, theme)
+}
+
+fn join_ids(ids: &[i32]) -> String {
+    ids.iter()
+        .map(i32::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,149 @@
+use crate::{
+    datetime::format_timestamp,
+    i18n::Locale,
+    layout::{Layout, SideBar},
+    render,
+    teams::Teams,
+};
+use daisy_rsx::*;
+use db::{queries::files::File, Permission, UserSettings};
+use dioxus::prelude::*;
+use web_assets::files::favicon_svg;
+
+/// A file attached to the team and a link to download it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attachment {
+    pub file: File,
+    /// Signed, so it stops working after a while.
+    pub url: String,
+}
+
+pub fn index(
+    attachments: Vec<Attachment>,
+    locale: Locale,
+    settings: UserSettings,
+    teams: Teams,
+) -> String {
+    let team_id = teams.current.id;
+    let can_upload = teams.can(Permission::UploadFiles);
+    let theme = settings.theme;
+    let timezone = settings.timezone.clone();
+    let page = rsx! {
+        Layout {    // <-- Use our layout
+            title: locale.t("files-title"),
+            selected_item: SideBar::Files,
+            locale,
+            settings,
+            teams,
+            if attachments.is_empty() {
+                BlankSlate {
+                    heading: locale.t("files-empty-heading"),
+                    visual: favicon_svg.name,
+                    description: locale.t("files-empty-description"),
+                }
+            } else {
+                Card {
+                    class: "card-bordered mt-12 has-data-table",
+                    CardHeader {
+                        class: "p-3 border-b",
+                        title: locale.t("files-title")
+                    }
+                    CardBody {
+                        class: "p-0",
+                        table {
+                            class: "table table-sm",
+                            thead {
+                                tr {
+                                    th { {locale.t("name")} }
+                                    th { {locale.t("files-type")} }
+                                    th { {locale.t("files-size")} }
+                                    th { {locale.t("files-uploaded-by")} }
+                                    th { {locale.t("files-uploaded")} }
+                                }
+                            }
+                            tbody {
+                                for attachment in attachments {
+                                    tr {
+                                        td {
+                                            a {
+                                                class: "link",
+                                                href: attachment.url,
+                                                "{attachment.file.file_name}"
+                                            }
+                                        }
+                                        td {
+                                            class: "text-xs",
+                                            "{attachment.file.content_type}"
+                                        }
+                                        td {
+                                            {format_size(attachment.file.size_bytes)}
+                                        }
+                                        td {
+                                            {attachment.file.uploaded_by.unwrap_or_default()}
+                                        }
+                                        td {
+                                            {format_timestamp(attachment.file.created_at, &timezone, locale)}
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            if can_upload {
+                Card {
+                    class: "card-bordered mt-12",
+                    CardHeader {
+                        class: "p-3 border-b",
+                        title: locale.t("files-upload")
+                    }
+                    CardBody {
+                        class: "p-3",
+                        form {
+                            class: "flex flex-col",
+                            action: "/teams/{team_id}/files",
+                            method: "POST",
+                            enctype: "multipart/form-data",
+
+                            label {
+                                class: "text-sm",
+                                {locale.t("files-upload-help")}
+                            }
+                            input {
+                                class: "file-input file-input-bordered file-input-sm mt-2",
+                                "type": "file",
+                                name: "file",
+                                required: true
+                            }
+                            Button {
+                                class: "mt-4",
+                                button_type: ButtonType::Submit,
+                                button_scheme: ButtonScheme::Primary,
+                                {locale.t("files-upload")}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    render(page, locale, theme)
+}
+
+// e.g. "1.5 MB"
+fn format_size(bytes: i64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
@@ -0,0 +1,206 @@
+//! Translated strings for every page, in Fluent's `.ftl` format under
+//! `locales/`. Adding a language means adding a file there and a variant
+//! to `Locale`, the tests check the new file has every message.
+
+use std::sync::LazyLock;
+
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource, FluentValue};
+use unic_langid::LanguageIdentifier;
+
+const SOURCES: [&str; 2] = [
+    include_str!("../locales/en.ftl"),
+    include_str!("../locales/de.ftl"),
+];
+
+static BUNDLES: LazyLock<Vec<FluentBundle<FluentResource>>> = LazyLock::new(|| {
+    Locale::ALL
+        .iter()
+        .map(|locale| {
+            let language: LanguageIdentifier = locale.code().parse().unwrap();
+            let mut bundle = FluentBundle::new_concurrent(vec![language]);
+            // The isolation marks around arguments end up in the HTML.
+            bundle.set_use_isolating(false);
+            let resource = FluentResource::try_new(SOURCES[*locale as usize].to_string())
+                .unwrap_or_else(|_| panic!("locales/{}.ftl doesn't parse", locale.code()));
+            bundle
+                .add_resource(resource)
+                .unwrap_or_else(|_| panic!("locales/{}.ftl has duplicates", locale.code()));
+            bundle
+        })
+        .collect()
+});
+
+/// The language a page is rendered in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::De];
+
+    /// What's stored in `user_settings.locale` and the `locale` cookie.
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::De => "de",
+        }
+    }
+
+    /// The language's name for itself, for the settings page.
+    pub fn name(self) -> &'static str {
+        match self {
+            Locale::En => "English",
+            Locale::De => "Deutsch",
+        }
+    }
+
+    /// Only the language counts, so "de-AT" is German.
+    pub fn parse(code: &str) -> Option<Locale> {
+        let language = code.trim().split(['-', '_']).next()?;
+        Locale::ALL
+            .into_iter()
+            .find(|locale| locale.code().eq_ignore_ascii_case(language))
+    }
+
+    /// The best match for an `Accept-Language` header, going by its
+    /// q-values.
+    pub fn from_accept_language(header: &str) -> Option<Locale> {
+        let mut wanted: Vec<(f32, Locale)> = header
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let locale = Locale::parse(parts.next()?)?;
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.parse::<f32>().ok())?;
+                (quality > 0.0).then_some((quality, locale))
+            })
+            .collect();
+        // Stable, so equal q-values keep the browser's order.
+        wanted.sort_by(|a, b| b.0.total_cmp(&a.0));
+        wanted.first().map(|(_, locale)| *locale)
+    }
+
+    /// The user's setting wins, then the cookie, then whatever the browser
+    /// asks for.
+    pub fn negotiate(
+        setting: Option<&str>,
+        cookie: Option<&str>,
+        accept_language: Option<&str>,
+    ) -> Locale {
+        setting
+            .and_then(Locale::parse)
+            .or_else(|| cookie.and_then(Locale::parse))
+            .or_else(|| accept_language.and_then(Locale::from_accept_language))
+            .unwrap_or_default()
+    }
+
+    /// The message with this id. A missing message shows its id rather
+    /// than failing the whole page.
+    pub fn t(self, id: &str) -> String {
+        self.format(id, None)
+    }
+
+    pub fn t_args(self, id: &str, args: &[(&str, FluentValue)]) -> String {
+        let args: FluentArgs = args.iter().cloned().collect();
+        self.format(id, Some(&args))
+    }
+
+    fn format(self, id: &str, args: Option<&FluentArgs>) -> String {
+        let bundle = &BUNDLES[self as usize];
+        let Some(pattern) = bundle.get_message(id).and_then(|message| message.value()) else {
+            return id.to_string();
+        };
+        let mut errors = vec![];
+        bundle
+            .format_pattern(pattern, args, &mut errors)
+            .into_owned()
+    }
+
+    /// A whole number with the thousands grouped the local way.
+    pub fn number(self, number: i64) -> String {
+        let separator = match self {
+            Locale::En => ',',
+            Locale::De => '.',
+        };
+        let digits = number.unsigned_abs().to_string();
+        let mut grouped = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                grouped.push(separator);
+            }
+            grouped.push(digit);
+        }
+        if number < 0 {
+            grouped.insert(0, '-');
+        }
+        grouped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use fluent_bundle::FluentResource;
+    use fluent_syntax::ast::Entry;
+
+    use super::*;
+
+    fn message_ids(source: &str) -> BTreeSet<String> {
+        let resource = FluentResource::try_new(source.to_string()).unwrap();
+        resource
+            .entries()
+            .filter_map(|entry| match entry {
+                Entry::Message(message) => Some(message.id.name.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_locale_has_every_message() {
+        let english = message_ids(SOURCES[Locale::En as usize]);
+        for locale in Locale::ALL {
+            let ids = message_ids(SOURCES[locale as usize]);
+            let missing: Vec<_> = english.difference(&ids).collect();
+            let extra: Vec<_> = ids.difference(&english).collect();
+            assert!(
+                missing.is_empty() && extra.is_empty(),
+                "locales/{}.ftl is missing {missing:?} and has extra {extra:?}",
+                locale.code()
+            );
+        }
+        // Parses and has no duplicates.
+        assert_eq!(BUNDLES.len(), Locale::ALL.len());
+    }
+
+    #[test]
+    fn setting_then_cookie_then_browser() {
+        let browser = Some("fr-CH, fr;q=0.9, de;q=0.8, en;q=0.7");
+        assert_eq!(Locale::negotiate(None, None, browser), Locale::De);
+        assert_eq!(Locale::negotiate(None, Some("en"), browser), Locale::En);
+        assert_eq!(
+            Locale::negotiate(Some("de-AT"), Some("en"), browser),
+            Locale::De
+        );
+        assert_eq!(Locale::negotiate(None, None, Some("de;q=0")), Locale::En);
+        assert_eq!(Locale::negotiate(None, None, None), Locale::En);
+    }
+
+    #[test]
+    fn messages_and_numbers_are_local() {
+        assert_eq!(Locale::De.t("nav-settings"), "Einstellungen");
+        assert_eq!(
+            Locale::En.t_args("signed-in-as", &[("name", "Ian".into())]),
+            "Signed in as Ian"
+        );
+        assert_eq!(Locale::En.t("no-such-message"), "no-such-message");
+        assert_eq!(Locale::En.number(1234567), "1,234,567");
+        assert_eq!(Locale::De.number(-1234), "-1.234");
+        assert_eq!(Locale::De.number(999), "999");
+    }
+}
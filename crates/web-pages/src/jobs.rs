@@ -0,0 +1,121 @@
+use crate::{
+    datetime::format_timestamp,
+    i18n::Locale,
+    layout::{Layout, SideBar},
+    render,
+    teams::Teams,
+};
+use daisy_rsx::*;
+use db::{queries::jobs::FailedJob, JobStatus, Permission, UserSettings};
+use dioxus::prelude::*;
+use web_assets::files::favicon_svg;
+
+pub fn index(
+    failed_jobs: Vec<FailedJob>,
+    locale: Locale,
+    settings: UserSettings,
+    teams: Teams,
+) -> String {
+    let team_id = teams.current.id;
+    let can_retry = teams.can(Permission::RetryJobs);
+    let theme = settings.theme;
+    let timezone = settings.timezone.clone();
+    let page = rsx! {
+        Layout {    // <-- Use our layout
+            title: locale.t("jobs-title"),
+            selected_item: SideBar::Jobs,
+            locale,
+            settings,
+            teams,
+            if failed_jobs.is_empty() {
+                BlankSlate {
+                    heading: locale.t("jobs-empty-heading"),
+                    visual: favicon_svg.name,
+                    description: locale.t("jobs-empty-description"),
+                }
+            } else {
+                Card {
+                    class: "card-bordered mt-12 has-data-table",
+                    CardHeader {
+                        class: "p-3 border-b",
+                        title: locale.t("jobs-title")
+                    }
+                    CardBody {
+                        class: "p-0",
+                        table {
+                            class: "table table-sm",
+                            thead {
+                                tr {
+                                    th { {locale.t("jobs-id")} }
+                                    th { {locale.t("jobs-kind")} }
+                                    th { {locale.t("jobs-status")} }
+                                    th { {locale.t("jobs-attempts")} }
+                                    th { {locale.t("jobs-error")} }
+                                    th { {locale.t("jobs-updated")} }
+                                    th { {locale.t("jobs-next-run")} }
+                                    th { }
+                                }
+                            }
+                            tbody {
+                                for job in failed_jobs {
+                                    tr {
+                                        td {
+                                            strong {
+                                                "{job.id}"
+                                            }
+                                        }
+                                        td {
+                                            "{job.kind}"
+                                        }
+                                        td {
+                                            if job.status == JobStatus::Dead {
+                                                Label {
+                                                    label_role: LabelRole::Danger,
+                                                    {locale.t("jobs-dead")}
+                                                }
+                                            } else {
+                                                Label {
+                                                    label_role: LabelRole::Warning,
+                                                    {locale.t("jobs-retrying")}
+                                                }
+                                            }
+                                        }
+                                        td {
+                                            "{job.attempts} / {job.max_attempts}"
+                                        }
+                                        td {
+                                            {job.last_error.unwrap_or_default()}
+                                        }
+                                        td {
+                                            {format_timestamp(job.updated_at, &timezone, locale)}
+                                        }
+                                        td {
+                                            if job.status != JobStatus::Dead {
+                                                {format_timestamp(job.run_at, &timezone, locale)}
+                                            }
+                                        }
+                                        td {
+                                            if job.status == JobStatus::Dead && can_retry {
+                                                form {
+                                                    action: "/teams/{team_id}/jobs/{job.id}/retry",
+                                                    method: "POST",
+                                                    Button {
+                                                        button_type: ButtonType::Submit,
+                                                        button_size: ButtonSize::Small,
+                                                        {locale.t("jobs-retry")}
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    render(page, locale, theme)
+}
@@ -1,5 +1,10 @@
 #![allow(non_snake_case)]
+use crate::{
+    i18n::Locale,
+    teams::{TeamSwitcher, Teams},
+};
 use daisy_rsx::*;
+use db::{Permission, Theme, UserSettings};
 use dioxus::prelude::*;
 use web_assets::files::*;
 use web_csr::HelloWorld;
@@ -7,7 +12,14 @@ use web_csr::HelloWorld;
 #[derive(PartialEq, Clone, Eq, Debug)]
 pub enum SideBar {
     Users,
+    Team,
+    Jobs,
+    Audit,
+    Files,
     Settings,
+    FeatureFlags,
+    // Only exists in development, so it isn't in the menu
+    Mailbox,
 }
 
 impl std::fmt::Display for SideBar {
@@ -17,12 +29,23 @@ impl std::fmt::Display for SideBar {
 }
 
 #[component]
-pub fn Layout(title: String, children: Element, selected_item: SideBar) -> Element {
+pub fn Layout(
+    title: String,
+    children: Element,
+    selected_item: SideBar,
+    locale: Locale,
+    settings: UserSettings,
+    teams: Teams,
+) -> Element {
+    let team_id = teams.current.id;
+    let theme = settings.theme;
+    let signed_in_as = settings.display_name.unwrap_or(settings.email);
+    let signed_in_as = locale.t_args("signed-in-as", &[("name", signed_in_as.into())]);
     rsx! {
         BaseLayout {
             title,
             stylesheets: vec![tailwind_css.name.to_string()],
-            js_href: htmx_2_0_3_js.name,
+            scripts: vec![htmx_2_0_3_js.name.to_string(), htmx_ext_sse_js.name.to_string()],
             header: rsx!(
                 nav {
                     aria_label: "breadcrumb",
@@ -31,7 +54,7 @@ pub fn Layout(title: String, children: Element, selected_item: SideBar) -> Eleme
                         class: "flex flex-wrap items-center gap-1.5 break-words text-sm sm:gap-2.5",
                         li {
                             class: "ml-3 items-center gap-1.5 hidden md:block",
-                            "Your Application"
+                            {locale.t("app-name")}
                         }
                         li {
                             ">"
@@ -49,24 +72,76 @@ pub fn Layout(title: String, children: Element, selected_item: SideBar) -> Eleme
                         }
                     }
                 }
+                ThemeSwitcher {
+                    team_id,
+                    theme,
+                    locale
+                }
             ),
             sidebar: rsx!(
                 NavGroup {
-                    heading: "Your Menu",
+                    heading: locale.t("nav-menu"),
                     content:  rsx!(
-                        NavItem {
-                            id: SideBar::Users.to_string(),
-                            selected_item_id: selected_item.to_string(),
-                            href: "/",
-                            icon: favicon_svg.name,
-                            title: "Users"
+                        if teams.can(Permission::ViewUsers) {
+                            NavItem {
+                                id: SideBar::Users.to_string(),
+                                selected_item_id: selected_item.to_string(),
+                                href: "/teams/{team_id}",
+                                icon: favicon_svg.name,
+                                title: locale.t("nav-users")
+                            }
+                        }
+                        if teams.can(Permission::ViewTeam) {
+                            NavItem {
+                                id: SideBar::Team.to_string(),
+                                selected_item_id: selected_item.to_string(),
+                                href: "/teams/{team_id}/team",
+                                icon: favicon_svg.name,
+                                title: locale.t("nav-team")
+                            }
+                        }
+                        if teams.can(Permission::ViewJobs) {
+                            NavItem {
+                                id: SideBar::Jobs.to_string(),
+                                selected_item_id: selected_item.to_string(),
+                                href: "/teams/{team_id}/jobs",
+                                icon: favicon_svg.name,
+                                title: locale.t("nav-jobs")
+                            }
+                        }
+                        if teams.can(Permission::ViewAuditLog) {
+                            NavItem {
+                                id: SideBar::Audit.to_string(),
+                                selected_item_id: selected_item.to_string(),
+                                href: "/teams/{team_id}/audit",
+                                icon: favicon_svg.name,
+                                title: locale.t("nav-audit")
+                            }
+                        }
+                        if teams.can(Permission::ViewFiles) {
+                            NavItem {
+                                id: SideBar::Files.to_string(),
+                                selected_item_id: selected_item.to_string(),
+                                href: "/teams/{team_id}/files",
+                                icon: favicon_svg.name,
+                                title: locale.t("nav-files")
+                            }
                         }
                         NavItem {
                             id: SideBar::Settings.to_string(),
                             selected_item_id: selected_item.to_string(),
-                            href: "/settings",
+                            href: "/teams/{team_id}/settings",
                             icon: favicon_svg.name,
-                            title: "Settings"
+                            title: locale.t("nav-settings")
+                        }
+                        if teams.site_admin {
+                            NavItem {
+                                id: SideBar::FeatureFlags.to_string(),
+                                selected_item_id: selected_item.to_string(),
+                                href: "/teams/{team_id}/feature_flags",
+                                icon: favicon_svg.name,
+                                title: locale.t("nav-feature-flags")
+                            }
                         }
                     )
                 }
@@ -100,22 +175,15 @@ pub fn Layout(title: String, children: Element, selected_item: SideBar) -> Eleme
                         }
                     }
                 }
-                div {
-                    class: "ml-3 flex flex-col gap-0.5 leading-none",
-                    span {
-                        class: "font-semibold uppercase",
-                        "Your Application"
-                    }
-                    span {
-                        class: "",
-                        "v1.0.1"
-                    }
+                TeamSwitcher {
+                    locale,
+                    teams: teams.clone()
                 }
             ),
             sidebar_footer: rsx!(
                 div {
                     class: "text-center text-sm",
-                    "You can place items at the bottom"
+                    "{signed_in_as}"
                 }
             ),
             div {
@@ -126,12 +194,46 @@ pub fn Layout(title: String, children: Element, selected_item: SideBar) -> Eleme
     }
 }
 
+// Sits at the end of the header. Each option is a submit button, so it
+// works without JavaScript like the team switcher.
+#[component]
+fn ThemeSwitcher(team_id: i32, theme: Theme, locale: Locale) -> Element {
+    let options = [
+        (Theme::System, "settings-theme-system"),
+        (Theme::Light, "settings-theme-light"),
+        (Theme::Dark, "settings-theme-dark"),
+    ];
+    rsx! {
+        details {
+            class: "dropdown dropdown-end ml-auto",
+            summary {
+                class: "btn btn-sm btn-ghost",
+                {locale.t("settings-theme")}
+            }
+            form {
+                class: "dropdown-content menu bg-base-100 rounded-box z-30 w-40 p-2 shadow",
+                action: "/teams/{team_id}/theme",
+                method: "POST",
+                for (option, name) in options {
+                    button {
+                        class: if option == theme { "btn btn-sm btn-ghost justify-start btn-active" } else { "btn btn-sm btn-ghost justify-start" },
+                        "type": "submit",
+                        name: "theme",
+                        value: "{option:?}",
+                        {locale.t(name)}
+                    }
+                }
+            }
+        }
+    }
+}
+
 #[derive(Props, Clone, PartialEq)]
 pub struct BaseLayoutProps {
     title: String,
     fav_icon_src: Option<String>,
     stylesheets: Vec<String>,
-    js_href: Option<String>,
+    scripts: Vec<String>,
     header: Element,
     children: Element,
     sidebar: Element,
@@ -165,10 +267,12 @@ pub fn BaseLayout(props: BaseLayoutProps) -> Element {
                     "type": "text/css"
                 }
             }
-            if let Some(js_href) = props.js_href {
+            // Classic deferred scripts run in order, so extensions can find
+            // the global `htmx` object.
+            for src in &props.scripts {
                 script {
-                    "type": "module",
-                    src: "{js_href}"
+                    defer: true,
+                    src: "{src}"
                 }
             }
             if let Some(fav_icon_src) = props.fav_icon_src {
@@ -1,9 +1,32 @@
+pub mod audit;
+pub mod datetime;
+pub mod errors;
+pub mod feature_flags;
+pub mod files;
+pub mod i18n;
+pub mod jobs;
 mod layout;
+pub mod mailbox;
 pub mod root;
 pub mod settings;
+pub mod teams;
+use db::Theme;
 use dioxus::prelude::*;
+use i18n::Locale;
 
-pub fn render(page: Element) -> String {
+/// The theme goes on `<html>` here rather than being set by a script, so
+/// the page never flashes the wrong colours. `System` leaves it off and
+/// daisyUI follows `prefers-color-scheme`.
+pub fn render(page: Element, locale: Locale, theme: Theme) -> String {
     let html = dioxus_ssr::render_element(page);
-    format!("<!DOCTYPE html><html lang='en'>{}</html>", html)
+    let data_theme = match theme {
+        Theme::System => "",
+        Theme::Light => " data-theme='light'",
+        Theme::Dark => " data-theme='dark'",
+    };
+    format!(
+        "<!DOCTYPE html><html lang='{}'{data_theme}>{}</html>",
+        locale.code(),
+        html
+    )
 }
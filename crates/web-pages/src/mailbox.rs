@@ -0,0 +1,67 @@
+use crate::{
+    i18n::Locale,
+    layout::{Layout, SideBar},
+    render,
+    teams::Teams,
+};
+use daisy_rsx::*;
+use db::UserSettings;
+use dioxus::prelude::*;
+use email::Email;
+use web_assets::files::favicon_svg;
+
+pub fn index(emails: Vec<Email>, locale: Locale, settings: UserSettings, teams: Teams) -> String {
+    let theme = settings.theme;
+    let page = rsx! {
+        Layout {    // <-- Use our layout
+            title: locale.t("mailbox-title"),
+            selected_item: SideBar::Mailbox,
+            locale,
+            settings,
+            teams,
+            if emails.is_empty() {
+                BlankSlate {
+                    heading: locale.t("mailbox-empty-heading"),
+                    visual: favicon_svg.name,
+                    description: locale.t("mailbox-empty-description"),
+                }
+            }
+            for email in emails {
+                Card {
+                    class: "card-bordered mt-12",
+                    CardHeader {
+                        class: "p-3 border-b",
+                        title: "{email.subject}"
+                    }
+                    CardBody {
+                        class: "p-3",
+                        p {
+                            class: "text-sm",
+                            {locale.t_args("mailbox-from", &[("from", email.from.as_str().into())])}
+                        }
+                        p {
+                            class: "text-sm",
+                            {locale.t_args("mailbox-to", &[("to", email.to.as_str().into())])}
+                        }
+                        // The email brings its own markup, so keep it away from ours
+                        iframe {
+                            class: "w-full h-96 mt-4 border",
+                            srcdoc: "{email.html}",
+                            "sandbox": ""
+                        }
+                        details {
+                            class: "mt-4",
+                            summary { {locale.t("mailbox-plain-text")} }
+                            pre {
+                                class: "whitespace-pre-wrap text-sm",
+                                "{email.text}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    render(page, locale, theme)
+}
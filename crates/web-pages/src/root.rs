@@ -1,48 +1,283 @@
 use crate::{
+    datetime::format_timestamp,
+    i18n::Locale,
     layout::{Layout, SideBar},
     render,
+    teams::Teams,
 };
 use daisy_rsx::*;
-use db::User;
+use db::{Permission, User, UserSettings};
 use dioxus::prelude::*;
 use web_assets::files::favicon_svg;
 
-pub fn index(users: Vec<User>) -> String {
+#[component]
+pub fn UserRow(user: User, timezone: String, locale: Locale, swap_oob: Option<String>) -> Element {
+    rsx! {
+        tr {
+            id: "user-{user.id}",
+            "hx-swap-oob": swap_oob,
+            td {
+                strong {
+                    "{user.id}"
+                }
+            }
+            td {
+                "{user.email}"
+            }
+            td {
+                {format_timestamp(user.created_at, &timezone, locale)}
+            }
+        }
+    }
+}
+
+/// A single row of the users table, used to update the table over SSE.
+pub fn user_row(user: User, timezone: String, locale: Locale) -> String {
+    dioxus_ssr::render_element(rsx! {
+        UserRow {
+            user,
+            timezone,
+            locale
+        }
+    })
+}
+
+/// Replace a row that's already in the users table.
+pub fn user_row_updated(user: User, timezone: String, locale: Locale) -> String {
+    dioxus_ssr::render_element(rsx! {
+        UserRow {
+            user,
+            timezone,
+            locale,
+            swap_oob: "true"
+        }
+    })
+}
+
+/// Tell the users table to drop a row that no longer exists.
+pub fn user_row_removed(id: i32) -> String {
+    dioxus_ssr::render_element(rsx! {
+        tr {
+            id: "user-{id}",
+            "hx-swap-oob": "delete"
+        }
+    })
+}
+
+/// A row of an uploaded CSV that can't be imported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportError {
+    pub line: u64,
+    pub message: String,
+}
+
+pub fn index(
+    users: Vec<User>,
+    email_filter: Option<String>,
+    locale: Locale,
+    settings: UserSettings,
+    teams: Teams,
+) -> String {
+    let team_id = teams.current.id;
+    let theme = settings.theme;
+    // Live updates would add users that don't match the search.
+    let live = email_filter.is_none();
+    let email_filter = email_filter.unwrap_or_default();
+    let can_create_users = teams.can(Permission::CreateUsers);
+    let timezone = settings.timezone.clone();
     let page = rsx! {
         Layout {    // <-- Use our layout
-            title: "Users Table",
+            title: locale.t("users-title"),
             selected_item: SideBar::Users,
+            locale,
+            settings,
+            teams,
             BlankSlate {
-                heading: "Welcome To Your Application",
+                heading: locale.t("users-welcome-heading"),
                 visual: favicon_svg.name,
-                description: "This is just the beginning",
+                description: locale.t("users-welcome-description"),
             }
             Card {
                 class: "card-bordered mt-12 has-data-table",
                 CardHeader {
                     class: "p-3 border-b",
-                    title: "Users"
+                    title: locale.t("nav-users")
                 }
                 CardBody {
                     class: "p-0",
+                    form {
+                        class: "flex gap-2 items-end p-3 border-b",
+                        action: "/teams/{team_id}",
+                        method: "GET",
+                        Input {
+                            input_type: InputType::Text,
+                            placeholder: locale.t("users-search-placeholder"),
+                            name: "email",
+                            value: email_filter.clone()
+                        }
+                        Button {
+                            button_type: ButtonType::Submit,
+                            {locale.t("search")}
+                        }
+                        a {
+                            class: "btn btn-sm ml-auto",
+                            href: "/teams/{team_id}/users/export.csv?email={email_filter}",
+                            {locale.t("users-export")}
+                        }
+                    }
                     table {
                         class: "table table-sm",
                         thead {
                             tr {
-                                th { "ID" }
-                                th { "Email" }
+                                th { {locale.t("users-id")} }
+                                th { {locale.t("email")} }
+                                th { {locale.t("users-created")} }
                             }
                         }
+                        // New rows arrive over SSE when anyone adds a user
                         tbody {
+                            "hx-ext": if live { "sse" },
+                            "sse-connect": if live { "/teams/{team_id}/users/events" },
+                            "sse-swap": if live { "users" },
+                            "hx-swap": if live { "beforeend" },
                             for user in users {
+                                UserRow {
+                                    user,
+                                    timezone: timezone.clone(),
+                                    locale
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if can_create_users {
+                Card {
+                    class: "card-bordered mt-12",
+                    CardHeader {
+                        class: "p-3 border-b",
+                        title: locale.t("users-add")
+                    }
+                    CardBody {
+                        class: "p-3",
+                        form {
+                            "hx-boost": "true",
+                            class: "flex flex-col",
+                            action: "/teams/{team_id}/new_user",
+                            method: "POST",
+
+                            Input {
+                                input_type: InputType::Email,
+                                placeholder: locale.t("email-placeholder"),
+                                help_text: locale.t("users-email-help"),
+                                required: true,
+                                label: locale.t("email"),
+                                name: "email"
+                            }
+                            Button {
+                                class: "mt-4",
+                                button_type: ButtonType::Submit,
+                                button_scheme: ButtonScheme::Primary,
+                                {locale.t("submit")}
+                            }
+                        }
+                    }
+                }
+            }
+
+            if can_create_users {
+                Card {
+                    class: "card-bordered mt-12",
+                    CardHeader {
+                        class: "p-3 border-b",
+                        title: locale.t("users-import")
+                    }
+                    CardBody {
+                        class: "p-3",
+                        form {
+                            class: "flex flex-col",
+                            action: "/teams/{team_id}/users/import/preview",
+                            method: "POST",
+                            enctype: "multipart/form-data",
+
+                            label {
+                                class: "text-sm",
+                                {locale.t("users-import-help")}
+                            }
+                            input {
+                                class: "file-input file-input-bordered file-input-sm mt-2",
+                                "type": "file",
+                                name: "file",
+                                accept: ".csv,text/csv",
+                                required: true
+                            }
+                            Button {
+                                class: "mt-4",
+                                button_type: ButtonType::Submit,
+                                button_scheme: ButtonScheme::Primary,
+                                {locale.t("users-import-preview")}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    render(page, locale, theme)
+}
+
+/// What an upload would do, so the user can fix the file or go ahead
+/// with the rows that are valid.
+pub fn import_preview(
+    emails: Vec<String>,
+    errors: Vec<ImportError>,
+    locale: Locale,
+    settings: UserSettings,
+    teams: Teams,
+) -> String {
+    let team_id = teams.current.id;
+    let theme = settings.theme;
+    let valid = emails.len();
+    let count = [
+        ("count", valid.into()),
+        ("rows", locale.number(valid as i64).into()),
+    ];
+    let emails = emails.join("\n");
+    let page = rsx! {
+        Layout {    // <-- Use our layout
+            title: locale.t("users-import"),
+            selected_item: SideBar::Users,
+            locale,
+            settings,
+            teams,
+            if !errors.is_empty() {
+                Card {
+                    class: "card-bordered has-data-table",
+                    CardHeader {
+                        class: "p-3 border-b",
+                        title: locale.t("users-import-errors")
+                    }
+                    CardBody {
+                        class: "p-0",
+                        table {
+                            class: "table table-sm",
+                            thead {
                                 tr {
-                                    td {
-                                        strong {
-                                            "{user.id}"
+                                    th { {locale.t("users-import-line")} }
+                                    th { {locale.t("users-import-problem")} }
+                                }
+                            }
+                            tbody {
+                                for error in errors {
+                                    tr {
+                                        td {
+                                            "{error.line}"
+                                        }
+                                        td {
+                                            "{error.message}"
                                         }
-                                    }
-                                    td {
-                                        "{user.email}"
                                     }
                                 }
                             }
@@ -50,34 +285,39 @@ pub fn index(users: Vec<User>) -> String {
                     }
                 }
             }
-
             Card {
                 class: "card-bordered mt-12",
                 CardHeader {
                     class: "p-3 border-b",
-                    title: "Add User"
+                    title: locale.t("users-import-ready")
                 }
                 CardBody {
                     class: "p-3",
                     form {
-                        "hx-boost": "true",
                         class: "flex flex-col",
-                        action: "/new_user",
+                        action: "/teams/{team_id}/users/import",
                         method: "POST",
-
-                        Input {
-                            input_type: InputType::Email,
-                            placeholder: "e.g. ian@test.com",
-                            help_text: "Please enter an email address",
-                            required: true,
-                            label: "Email",
-                            name: "email"
+                        p {
+                            {locale.t_args("users-import-summary", &count)}
                         }
-                        Button {
-                            class: "mt-4",
-                            button_type: ButtonType::Submit,
-                            button_scheme: ButtonScheme::Primary,
-                            "Submit"
+                        textarea {
+                            class: "hidden",
+                            name: "emails",
+                            "{emails}"
+                        }
+                        div {
+                            class: "flex gap-2 mt-4",
+                            Button {
+                                button_type: ButtonType::Submit,
+                                button_scheme: ButtonScheme::Primary,
+                                disabled: valid == 0,
+                                {locale.t_args("users-import-confirm", &count)}
+                            }
+                            a {
+                                class: "btn btn-sm",
+                                href: "/teams/{team_id}",
+                                {locale.t("cancel")}
+                            }
                         }
                     }
                 }
@@ -85,5 +325,5 @@ pub fn index(users: Vec<User>) -> String {
         }
     };
 
-    render(page)
+    render(page, locale, theme)
 }
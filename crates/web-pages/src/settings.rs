@@ -1,58 +1,304 @@
 use crate::{
+    datetime::{format_timestamp, timezones},
+    i18n::Locale,
     layout::{Layout, SideBar},
     render,
+    teams::Teams,
 };
 use daisy_rsx::*;
-use db::User;
+use db::{queries::api_keys::ApiKey, Theme, UserSettings};
 use dioxus::prelude::*;
-use web_assets::files::favicon_svg;
 
-pub fn index(users: Vec<User>) -> String {
+/// The user's API keys for the current team.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ApiKeys {
+    pub keys: Vec<ApiKey>,
+    /// A key that was just created. We only store a hash, so this is the
+    /// only time it can be shown.
+    pub created: Option<String>,
+}
+
+pub fn index(
+    settings: UserSettings,
+    errors: Vec<String>,
+    api_keys: ApiKeys,
+    locale: Locale,
+    teams: Teams,
+) -> String {
+    let team_id = teams.current.id;
+    let permissions = teams.permissions.clone();
+    let display_name = settings.display_name.clone().unwrap_or_default();
+    let timezone = settings.timezone.clone();
+    let current_theme = settings.theme;
+    let theme = format!("{:?}", settings.theme);
+    // Empty for "same as my browser"
+    let chosen_locale = settings.locale.clone().unwrap_or_default();
+    let notify_product_updates = settings.notify_product_updates;
+    let notify_security_alerts = settings.notify_security_alerts;
+    let tz = settings.timezone.clone();
+    let has_avatar = settings.avatar_id.is_some();
+
     let page = rsx! {
         Layout {    // <-- Use our layout
-            title: "Users Table",
-            selected_item: SideBar::Users,
-            BlankSlate {
-                heading: "Welcome To Your Application",
-                visual: favicon_svg.name,
-                description: "This is just the beginning",
+            title: locale.t("settings-title"),
+            selected_item: SideBar::Settings,
+            locale,
+            settings,
+            teams,
+            if !errors.is_empty() {
+                Alert {
+                    alert_color: AlertColor::Error,
+                    ul {
+                        for error in errors {
+                            li { "{error}" }
+                        }
+                    }
+                }
+            }
+            Card {
+                class: "card-bordered mt-12",
+                CardHeader {
+                    class: "p-3 border-b",
+                    title: locale.t("settings-preferences")
+                }
+                CardBody {
+                    class: "p-3",
+                    form {
+                        class: "flex flex-col",
+                        action: "/teams/{team_id}/settings",
+                        method: "POST",
+
+                        Input {
+                            input_type: InputType::Text,
+                            placeholder: locale.t("settings-display-name-placeholder"),
+                            help_text: locale.t("settings-display-name-help"),
+                            label: locale.t("settings-display-name"),
+                            name: "display_name",
+                            value: display_name
+                        }
+                        Select {
+                            label: locale.t("settings-timezone"),
+                            label_class: "mt-4",
+                            help_text: locale.t("settings-timezone-help"),
+                            name: "timezone",
+                            value: timezone.clone(),
+                            for tz in timezones() {
+                                SelectOption {
+                                    value: tz,
+                                    selected_value: timezone.clone(),
+                                    "{tz}"
+                                }
+                            }
+                        }
+                        Select {
+                            label: locale.t("settings-language"),
+                            label_class: "mt-4",
+                            help_text: locale.t("settings-language-help"),
+                            name: "locale",
+                            value: chosen_locale.clone(),
+                            SelectOption {
+                                value: "",
+                                selected_value: chosen_locale.clone(),
+                                {locale.t("settings-language-automatic")}
+                            }
+                            for option in Locale::ALL {
+                                SelectOption {
+                                    value: option.code(),
+                                    selected_value: chosen_locale.clone(),
+                                    "{option.name()}"
+                                }
+                            }
+                        }
+                        Select {
+                            label: locale.t("settings-theme"),
+                            label_class: "mt-4",
+                            name: "theme",
+                            value: theme.clone(),
+                            for (option, name) in [
+                                (Theme::System, "settings-theme-system"),
+                                (Theme::Light, "settings-theme-light"),
+                                (Theme::Dark, "settings-theme-dark"),
+                            ] {
+                                SelectOption {
+                                    value: format!("{:?}", option),
+                                    selected_value: theme.clone(),
+                                    {locale.t(name)}
+                                }
+                            }
+                        }
+                        label {
+                            class: "label cursor-pointer justify-start gap-2 mt-4",
+                            CheckBox {
+                                name: "notify_product_updates",
+                                value: "true",
+                                checked: notify_product_updates,
+                            }
+                            span { {locale.t("settings-notify-product-updates")} }
+                        }
+                        label {
+                            class: "label cursor-pointer justify-start gap-2",
+                            CheckBox {
+                                name: "notify_security_alerts",
+                                value: "true",
+                                checked: notify_security_alerts,
+                            }
+                            span { {locale.t("settings-notify-security-alerts")} }
+                        }
+                        Button {
+                            class: "mt-4",
+                            button_type: ButtonType::Submit,
+                            button_scheme: ButtonScheme::Primary,
+                            {locale.t("save")}
+                        }
+                    }
+                }
+            }
+            Card {
+                class: "card-bordered mt-12",
+                CardHeader {
+                    class: "p-3 border-b",
+                    title: locale.t("settings-avatar")
+                }
+                CardBody {
+                    class: "p-3",
+                    if has_avatar {
+                        img {
+                            class: "size-24 rounded-full object-cover mb-4",
+                            src: "/teams/{team_id}/avatar",
+                            alt: locale.t("settings-avatar")
+                        }
+                    }
+                    form {
+                        class: "flex flex-col",
+                        action: "/teams/{team_id}/avatar",
+                        method: "POST",
+                        enctype: "multipart/form-data",
+
+                        label {
+                            class: "text-sm",
+                            {locale.t("settings-avatar-help")}
+                        }
+                        input {
+                            class: "file-input file-input-bordered file-input-sm mt-2",
+                            "type": "file",
+                            name: "file",
+                            accept: "image/png,image/jpeg,image/gif,image/webp",
+                            required: true
+                        }
+                        Button {
+                            class: "mt-4",
+                            button_type: ButtonType::Submit,
+                            button_scheme: ButtonScheme::Primary,
+                            {locale.t("settings-avatar-upload")}
+                        }
+                    }
+                }
             }
             Card {
                 class: "card-bordered mt-12 has-data-table",
                 CardHeader {
                     class: "p-3 border-b",
-                    title: "Users"
+                    title: locale.t("api-keys-title")
                 }
                 CardBody {
                     class: "p-0",
+                    if let Some(created) = api_keys.created {
+                        Alert {
+                            alert_color: AlertColor::Success,
+                            class: "m-3",
+                            div {
+                                p { {locale.t("api-keys-created")} }
+                                code {
+                                    class: "select-all break-all",
+                                    "{created}"
+                                }
+                            }
+                        }
+                    }
                     table {
                         class: "table table-sm",
                         thead {
                             tr {
-                                th { "ID" }
-                                th { "Email" }
+                                th { {locale.t("name")} }
+                                th { {locale.t("api-keys-key")} }
+                                th { {locale.t("api-keys-scopes")} }
+                                th { {locale.t("api-keys-created-at")} }
+                                th { {locale.t("api-keys-last-used")} }
+                                th { }
                             }
                         }
                         tbody {
-                            for user in users {
+                            for key in api_keys.keys {
                                 tr {
                                     td {
-                                        strong {
-                                            "{user.id}"
-                                        }
+                                        "{key.name}"
+                                    }
+                                    td {
+                                        code { "{key.prefix}…" }
+                                    }
+                                    td {
+                                        class: "text-xs",
+                                        {key.scopes.iter().map(|scope| format!("{scope:?}")).collect::<Vec<_>>().join(", ")}
+                                    }
+                                    td {
+                                        {format_timestamp(key.created_at, &tz, locale)}
                                     }
                                     td {
-                                        "{user.email}"
+                                        {key.last_used_at.map(|used| format_timestamp(used, &tz, locale)).unwrap_or_else(|| locale.t("api-keys-never"))}
+                                    }
+                                    td {
+                                        form {
+                                            action: "/teams/{team_id}/api_keys/{key.id}/revoke",
+                                            method: "POST",
+                                            Button {
+                                                button_type: ButtonType::Submit,
+                                                button_size: ButtonSize::Small,
+                                                button_scheme: ButtonScheme::Danger,
+                                                {locale.t("api-keys-revoke")}
+                                            }
+                                        }
                                     }
                                 }
                             }
                         }
                     }
+                    form {
+                        class: "flex flex-col p-3 border-t",
+                        action: "/teams/{team_id}/api_keys",
+                        method: "POST",
+
+                        Input {
+                            input_type: InputType::Text,
+                            placeholder: locale.t("api-keys-name-placeholder"),
+                            required: true,
+                            label: locale.t("name"),
+                            name: "name"
+                        }
+                        span {
+                            class: "mt-4 text-sm",
+                            {locale.t("api-keys-scopes")}
+                        }
+                        for permission in permissions {
+                            label {
+                                class: "label cursor-pointer justify-start gap-2",
+                                CheckBox {
+                                    name: "scopes",
+                                    value: format!("{permission:?}"),
+                                }
+                                span { "{permission:?}" }
+                            }
+                        }
+                        Button {
+                            class: "mt-4",
+                            button_type: ButtonType::Submit,
+                            button_scheme: ButtonScheme::Primary,
+                            {locale.t("api-keys-create")}
+                        }
+                    }
                 }
             }
-
         }
     };
 
-    render(page)
+    render(page, locale, current_theme)
 }
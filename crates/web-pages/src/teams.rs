@@ -0,0 +1,313 @@
+#![allow(non_snake_case)]
+use crate::{
+    datetime::format_timestamp,
+    i18n::Locale,
+    layout::{Layout, SideBar},
+    render,
+};
+use daisy_rsx::*;
+use db::{
+    queries::teams::{Invitation, InvitationForUser, TeamMember},
+    Permission, Team, TeamRole, UserSettings,
+};
+use dioxus::prelude::*;
+
+/// The team in the URL along with every team the user can switch to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Teams {
+    pub current: Team,
+    pub all: Vec<Team>,
+    /// What the user's role lets them do in the current team.
+    pub permissions: Vec<Permission>,
+    /// Listed in `ADMIN_EMAILS`, so can change things that aren't owned by
+    /// any team, like feature flags.
+    pub site_admin: bool,
+}
+
+impl Teams {
+    /// Use this to hide anything the user isn't allowed to use. The server
+    /// checks again, this just keeps them from hitting a 403.
+    pub fn can(&self, permission: Permission) -> bool {
+        self.permissions.contains(&permission)
+    }
+}
+
+fn role_name(role: TeamRole, locale: Locale) -> String {
+    match role {
+        TeamRole::Administrator => locale.t("role-administrator"),
+        TeamRole::Member => locale.t("role-member"),
+        TeamRole::ReadOnly => locale.t("role-read-only"),
+    }
+}
+
+// Sits in the sidebar header. A details element opens the menu without
+// needing any JavaScript.
+#[component]
+pub fn TeamSwitcher(locale: Locale, teams: Teams) -> Element {
+    let current = teams.current;
+    rsx! {
+        details {
+            class: "dropdown ml-3 flex-1",
+            summary {
+                class: "flex flex-col gap-0.5 leading-none cursor-pointer list-none",
+                span {
+                    class: "font-semibold",
+                    "{current.name}"
+                }
+                span {
+                    class: "text-xs",
+                    {role_name(current.role, locale)}
+                }
+            }
+            ul {
+                class: "dropdown-content menu bg-base-100 rounded-box z-30 w-56 p-2 shadow",
+                for team in teams.all {
+                    li {
+                        a {
+                            class: if team.id == current.id { "active" },
+                            href: "/teams/{team.id}",
+                            "{team.name}"
+                        }
+                    }
+                }
+                li {
+                    a {
+                        href: "/teams/{current.id}/team",
+                        {locale.t("team-switcher-new")}
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn index(
+    members: Vec<TeamMember>,
+    invitations: Vec<Invitation>,
+    my_invitations: Vec<InvitationForUser>,
+    locale: Locale,
+    settings: UserSettings,
+    teams: Teams,
+) -> String {
+    let team_id = teams.current.id;
+    let can_invite = teams.can(Permission::ManageTeam);
+    let theme = settings.theme;
+    let timezone = settings.timezone.clone();
+    let page = rsx! {
+        Layout {    // <-- Use our layout
+            title: locale.t("team-title"),
+            selected_item: SideBar::Team,
+            locale,
+            settings,
+            teams,
+            Card {
+                class: "card-bordered has-data-table",
+                CardHeader {
+                    class: "p-3 border-b",
+                    title: locale.t("team-members")
+                }
+                CardBody {
+                    class: "p-0",
+                    table {
+                        class: "table table-sm",
+                        thead {
+                            tr {
+                                th { {locale.t("email")} }
+                                th { {locale.t("role")} }
+                                th { {locale.t("team-joined")} }
+                            }
+                        }
+                        tbody {
+                            for member in members {
+                                tr {
+                                    td {
+                                        "{member.email}"
+                                    }
+                                    td {
+                                        {role_name(member.role, locale)}
+                                    }
+                                    td {
+                                        {format_timestamp(member.joined_at, &timezone, locale)}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if !invitations.is_empty() {
+                Card {
+                    class: "card-bordered mt-12 has-data-table",
+                    CardHeader {
+                        class: "p-3 border-b",
+                        title: locale.t("team-pending-invitations")
+                    }
+                    CardBody {
+                        class: "p-0",
+                        table {
+                            class: "table table-sm",
+                            thead {
+                                tr {
+                                    th { {locale.t("email")} }
+                                    th { {locale.t("role")} }
+                                    th { {locale.t("team-invited")} }
+                                }
+                            }
+                            tbody {
+                                for invitation in invitations {
+                                    tr {
+                                        td {
+                                            "{invitation.email}"
+                                        }
+                                        td {
+                                            {role_name(invitation.role, locale)}
+                                        }
+                                        td {
+                                            {format_timestamp(invitation.created_at, &timezone, locale)}
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if can_invite {
+                Card {
+                    class: "card-bordered mt-12",
+                    CardHeader {
+                        class: "p-3 border-b",
+                        title: locale.t("team-invite")
+                    }
+                    CardBody {
+                        class: "p-3",
+                        form {
+                            class: "flex flex-col",
+                            action: "/teams/{team_id}/invitations",
+                            method: "POST",
+
+                            Input {
+                                input_type: InputType::Email,
+                                placeholder: locale.t("email-placeholder"),
+                                help_text: locale.t("team-invite-help"),
+                                required: true,
+                                label: locale.t("email"),
+                                name: "email"
+                            }
+                            Select {
+                                label: locale.t("role"),
+                                name: "role",
+                                value: "Member",
+                                SelectOption {
+                                    value: "Member",
+                                    selected_value: "Member",
+                                    {locale.t("role-member")}
+                                }
+                                SelectOption {
+                                    value: "ReadOnly",
+                                    selected_value: "Member",
+                                    {locale.t("role-read-only")}
+                                }
+                                SelectOption {
+                                    value: "Administrator",
+                                    selected_value: "Member",
+                                    {locale.t("role-administrator")}
+                                }
+                            }
+                            Button {
+                                class: "mt-4",
+                                button_type: ButtonType::Submit,
+                                button_scheme: ButtonScheme::Primary,
+                                {locale.t("team-invite-send")}
+                            }
+                        }
+                    }
+                }
+            }
+
+            if !my_invitations.is_empty() {
+                Card {
+                    class: "card-bordered mt-12 has-data-table",
+                    CardHeader {
+                        class: "p-3 border-b",
+                        title: locale.t("team-your-invitations")
+                    }
+                    CardBody {
+                        class: "p-0",
+                        table {
+                            class: "table table-sm",
+                            thead {
+                                tr {
+                                    th { {locale.t("team-team")} }
+                                    th { {locale.t("role")} }
+                                    th { {locale.t("team-invited")} }
+                                    th { }
+                                }
+                            }
+                            tbody {
+                                for invitation in my_invitations {
+                                    tr {
+                                        td {
+                                            "{invitation.team_name}"
+                                        }
+                                        td {
+                                            {role_name(invitation.role, locale)}
+                                        }
+                                        td {
+                                            {format_timestamp(invitation.created_at, &timezone, locale)}
+                                        }
+                                        td {
+                                            form {
+                                                action: "/invitations/{invitation.id}/accept",
+                                                method: "POST",
+                                                Button {
+                                                    button_type: ButtonType::Submit,
+                                                    button_size: ButtonSize::Small,
+                                                    {locale.t("team-accept")}
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            Card {
+                class: "card-bordered mt-12",
+                CardHeader {
+                    class: "p-3 border-b",
+                    title: locale.t("team-create")
+                }
+                CardBody {
+                    class: "p-3",
+                    form {
+                        class: "flex flex-col",
+                        action: "/teams",
+                        method: "POST",
+
+                        Input {
+                            input_type: InputType::Text,
+                            placeholder: locale.t("team-name-placeholder"),
+                            required: true,
+                            label: locale.t("name"),
+                            name: "name"
+                        }
+                        Button {
+                            class: "mt-4",
+                            button_type: ButtonType::Submit,
+                            button_scheme: ButtonScheme::Primary,
+                            {locale.t("team-create-button")}
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    render(page, locale, theme)
+}
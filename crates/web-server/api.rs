@@ -0,0 +1,23 @@
+use crate::{
+    authorization::{AuthorizeKey, ViewUsers},
+    errors::CustomError,
+};
+use axum::{Extension, Json};
+use db::User;
+
+// The JSON API. Requests are authenticated with an API key rather than the
+// proxy headers, and the key decides which team they're for.
+pub async fn users(
+    Extension(pool): Extension<db::Pool>,
+    AuthorizeKey(key, _): AuthorizeKey<ViewUsers>,
+) -> Result<Json<Vec<User>>, CustomError> {
+    let mut client = pool.get().await?;
+    let transaction = db::rls::transaction(&mut client, key.tenant()).await?;
+
+    let users = db::queries::users::get_team_users()
+        .bind(&transaction, &key.team_id)
+        .all()
+        .await?;
+
+    Ok(Json(users))
+}
@@ -0,0 +1,283 @@
+use crate::{audit::RequestMeta, cookies, errors::CustomError, teams::CurrentTeam};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Path},
+    http::{
+        header::{AUTHORIZATION, SET_COOKIE},
+        request::Parts,
+        HeaderMap, StatusCode,
+    },
+    response::{AppendHeaders, IntoResponse, Redirect, Response},
+    Extension,
+};
+use axum_extra::extract::Form;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use db::{
+    encryption::{self, Ciphertext, Keyring},
+    queries::api_keys::ApiKey as StoredKey,
+    Permission,
+};
+use rand::RngCore;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use validator::Validate;
+
+const KEY_PREFIX: &str = "nails_";
+// Enough of the key to tell them apart in the UI.
+const SHOWN_LEN: usize = KEY_PREFIX.len() + 8;
+// Carries a new key to the settings page, see `take_flash`.
+pub const FLASH_COOKIE: &str = "new_api_key";
+
+/// A request made with an API key in an `Authorization: Bearer` header.
+/// The permissions are the key's scopes minus anything the owner's role
+/// no longer allows.
+pub struct ApiKey {
+    pub id: i32,
+    pub team_id: i32,
+    pub user_id: i32,
+    pub permissions: Vec<Permission>,
+}
+
+impl ApiKey {
+    pub fn tenant(&self) -> db::rls::Tenant {
+        db::rls::Tenant {
+            user_id: self.user_id,
+            team_id: self.team_id,
+        }
+    }
+
+    pub fn can(&self, permission: Permission) -> bool {
+        self.permissions.contains(&permission)
+    }
+}
+
+fn hash(key: &str) -> Vec<u8> {
+    Sha256::digest(key.as_bytes()).to_vec()
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ApiKey
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let key = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|header| header.to_str().ok())
+            .and_then(|header| header.strip_prefix("Bearer "))
+            .ok_or_else(|| (StatusCode::UNAUTHORIZED, "Missing API key").into_response())?;
+        let key_hash = hash(key.trim());
+
+        let meta = RequestMeta::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        let pool =
+            parts.extensions.get::<db::Pool>().cloned().ok_or_else(|| {
+                CustomError::FaultySetup("No database pool".into()).into_response()
+            })?;
+        // The key is what tells us the tenant.
+        let db::PrivilegedPool(privileged) = parts
+            .extensions
+            .get::<db::PrivilegedPool>()
+            .cloned()
+            .ok_or_else(|| {
+                CustomError::FaultySetup("No privileged database pool".into()).into_response()
+            })?;
+
+        let lookup = privileged
+            .get()
+            .await
+            .map_err(|e| CustomError::from(e).into_response())?;
+        let owner = db::queries::api_keys::get_api_key_owner()
+            .bind(&lookup, &key_hash)
+            .opt()
+            .await
+            .map_err(|e| CustomError::from(e).into_response())?
+            .ok_or_else(|| (StatusCode::UNAUTHORIZED, "Invalid API key").into_response())?;
+        drop(lookup);
+
+        let api_key = ApiKey {
+            id: owner.id,
+            team_id: owner.team_id,
+            user_id: owner.user_id,
+            permissions: owner.permissions,
+        };
+
+        let mut client = pool
+            .get()
+            .await
+            .map_err(|e| CustomError::from(e).into_response())?;
+        let record_usage = async {
+            let transaction = db::rls::transaction(&mut client, api_key.tenant()).await?;
+            db::queries::api_keys::touch_api_key()
+                .bind(&transaction, &api_key.id)
+                .await?;
+            db::audit::record(
+                &transaction,
+                db::audit::AuditEvent {
+                    team_id: Some(api_key.team_id),
+                    actor_id: Some(api_key.user_id),
+                    action: "api_key.used",
+                    target_type: "api_key",
+                    target_id: Some(api_key.id),
+                    ip_address: meta.ip_address,
+                    user_agent: meta.user_agent.as_deref(),
+                    ..Default::default()
+                },
+            )
+            .await?;
+            transaction.commit().await
+        };
+        record_usage
+            .await
+            .map_err(|e| CustomError::from(e).into_response())?;
+
+        Ok(api_key)
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct NewApiKey {
+    #[validate(length(min = 1, max = 100))]
+    name: String,
+    // Checkboxes, one per permission
+    #[serde(default)]
+    scopes: Vec<String>,
+}
+
+pub async fn new_action(
+    Extension(pool): Extension<db::Pool>,
+    Extension(keyring): Extension<Keyring>,
+    team: CurrentTeam,
+    meta: RequestMeta,
+    Form(form): Form<NewApiKey>,
+) -> Result<Response, CustomError> {
+    // Keys can only be given permissions you have yourself.
+    let scopes: Option<Vec<Permission>> = form
+        .scopes
+        .iter()
+        .map(|scope| {
+            team.teams
+                .permissions
+                .iter()
+                .find(|permission| format!("{permission:?}") == *scope)
+                .copied()
+        })
+        .collect();
+    let scopes = match (form.validate(), scopes) {
+        (Ok(()), Some(scopes)) if !scopes.is_empty() => scopes,
+        _ => return Ok((StatusCode::BAD_REQUEST, "Bad request").into_response()),
+    };
+
+    let mut secret = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut secret);
+    let key = format!("{KEY_PREFIX}{}", URL_SAFE_NO_PAD.encode(secret));
+    let prefix = &key[..SHOWN_LEN];
+
+    let mut client = pool.get().await?;
+    let transaction = db::rls::transaction(&mut client, team.tenant()).await?;
+
+    let name = form.name.trim();
+    let key_id = db::queries::api_keys::create_api_key()
+        .bind(
+            &transaction,
+            &team.id,
+            &team.settings.user_id,
+            &name,
+            &prefix,
+            &hash(&key),
+            &scopes,
+        )
+        .one()
+        .await?;
+
+    db::audit::record(
+        &transaction,
+        db::audit::AuditEvent {
+            team_id: Some(team.id),
+            actor_id: Some(team.settings.user_id),
+            action: "api_key.created",
+            target_type: "api_key",
+            target_id: Some(key_id),
+            diff: serde_json::json!({
+                "name": { "old": null, "new": name },
+                "prefix": { "old": null, "new": prefix },
+                "scopes": { "old": null, "new": scopes },
+            }),
+            ip_address: meta.ip_address,
+            user_agent: meta.user_agent.as_deref(),
+        },
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    // The key is shown once on the settings page, carried there encrypted
+    // so it never sits in a POST response the browser might resubmit.
+    let flash = keyring.encrypt_str(&key, &flash_context(key_id));
+    let flash = format!("{key_id}.{}", URL_SAFE_NO_PAD.encode(flash.as_bytes()));
+
+    Ok((
+        AppendHeaders([(SET_COOKIE, cookies::flash(FLASH_COOKIE, &flash))]),
+        Redirect::to(&format!("/teams/{}/settings", team.id)),
+    )
+        .into_response())
+}
+
+fn flash_context(key_id: i32) -> String {
+    encryption::context("api_keys", "flash", key_id)
+}
+
+/// The key just created, if the flash cookie holds one of `keys`. Anything
+/// that doesn't decrypt is ignored, the cookie is cleared either way.
+pub fn take_flash(headers: &HeaderMap, keyring: &Keyring, keys: &[StoredKey]) -> Option<String> {
+    let (key_id, ciphertext) = cookies::get(headers, FLASH_COOKIE)?.split_once('.')?;
+    let key_id: i32 = key_id.parse().ok()?;
+    if !keys.iter().any(|key| key.id == key_id) {
+        return None;
+    }
+    let ciphertext = Ciphertext::from(URL_SAFE_NO_PAD.decode(ciphertext).ok()?);
+    keyring
+        .decrypt_string(&ciphertext, &flash_context(key_id))
+        .ok()
+}
+
+pub async fn revoke_action(
+    Extension(pool): Extension<db::Pool>,
+    team: CurrentTeam,
+    meta: RequestMeta,
+    Path((_, id)): Path<(i32, i32)>,
+) -> Result<Response, CustomError> {
+    let mut client = pool.get().await?;
+    let transaction = db::rls::transaction(&mut client, team.tenant()).await?;
+
+    let revoked = db::queries::api_keys::revoke_api_key()
+        .bind(&transaction, &id, &team.id, &team.settings.user_id)
+        .opt()
+        .await?;
+    if revoked.is_none() {
+        return Ok((StatusCode::NOT_FOUND, "API key not found").into_response());
+    }
+
+    db::audit::record(
+        &transaction,
+        db::audit::AuditEvent {
+            team_id: Some(team.id),
+            actor_id: Some(team.settings.user_id),
+            action: "api_key.revoked",
+            target_type: "api_key",
+            target_id: Some(id),
+            ip_address: meta.ip_address,
+            user_agent: meta.user_agent.as_deref(),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(Redirect::to(&format!("/teams/{}/settings", team.id)).into_response())
+}
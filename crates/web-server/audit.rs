@@ -0,0 +1,216 @@
+use std::net::{IpAddr, SocketAddr};
+
+use crate::{
+    authorization::{Authorize, ViewAuditLog},
+    config::{Config, TrustedProxies},
+    errors::CustomError,
+    replicas::ReadPool,
+};
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts, Query},
+    http::{header::USER_AGENT, request::Parts, HeaderMap},
+    response::Html,
+};
+use serde::Deserialize;
+use time::{macros::format_description, Date};
+use web_pages::audit::{self, AuditFilters};
+
+const X_FORWARDED_FOR: &str = "X-Forwarded-For";
+
+/// Where a request came from, for the audit log.
+pub struct RequestMeta {
+    pub ip_address: Option<IpAddr>,
+    pub user_agent: Option<String>,
+}
+
+/// Anyone can send an `X-Forwarded-For`, so it only counts when the peer is
+/// one of our proxies. Each proxy appends the address it got the request
+/// from, so reading from the right the first address that isn't one of
+/// ours is the client. Everything to the left of it is up to the client.
+fn client_ip(
+    peer: Option<IpAddr>,
+    headers: &HeaderMap,
+    trusted: &TrustedProxies,
+) -> Option<IpAddr> {
+    let mut client = peer?;
+    if !trusted.contains(client) {
+        return Some(client);
+    }
+
+    let forwarded: Vec<&str> = headers
+        .get_all(X_FORWARDED_FOR)
+        .iter()
+        .filter_map(|header| header.to_str().ok())
+        .flat_map(|header| header.split(','))
+        .collect();
+    for hop in forwarded.iter().rev() {
+        let Ok(hop) = hop.trim().parse::<IpAddr>() else {
+            break;
+        };
+        client = hop;
+        if !trusted.contains(hop) {
+            break;
+        }
+    }
+    Some(client)
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for RequestMeta
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        let trusted = parts
+            .extensions
+            .get::<Config>()
+            .map(|config| config.trusted_proxies.clone())
+            .unwrap_or_default();
+
+        let user_agent = parts
+            .headers
+            .get(USER_AGENT)
+            .and_then(|header| header.to_str().ok())
+            .map(String::from);
+
+        Ok(RequestMeta {
+            ip_address: client_ip(peer, &parts.headers, &trusted),
+            user_agent,
+        })
+    }
+}
+
+#[derive(Deserialize, Default)]
+pub struct AuditQuery {
+    actor: Option<String>,
+    target_type: Option<String>,
+    target_id: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+}
+
+// Empty form fields mean "don't filter on this".
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_date(value: &Option<String>) -> Option<Date> {
+    non_empty(value)
+        .and_then(|date| Date::parse(date, format_description!("[year]-[month]-[day]")).ok())
+}
+
+pub async fn loader(
+    Authorize(team, _): Authorize<ViewAuditLog>,
+    ReadPool(pool): ReadPool,
+    Query(query): Query<AuditQuery>,
+) -> Result<Html<String>, CustomError> {
+    let mut client = pool.get().await?;
+    let transaction = db::rls::transaction(&mut client, team.tenant()).await?;
+
+    let settings = team.settings;
+
+    let target_id = non_empty(&query.target_id).and_then(|id| id.parse::<i32>().ok());
+    let from = parse_date(&query.from);
+    let to = parse_date(&query.to);
+
+    let events = db::queries::audit_events::get_audit_events()
+        .bind(
+            &transaction,
+            &team.id,
+            &non_empty(&query.actor),
+            &non_empty(&query.target_type),
+            &target_id,
+            &from,
+            &settings.timezone.as_str(),
+            &to,
+        )
+        .all()
+        .await?;
+    let target_types = db::queries::audit_events::get_audit_target_types()
+        .bind(&transaction, &team.id)
+        .all()
+        .await?;
+
+    let filters = AuditFilters {
+        actor: non_empty(&query.actor).unwrap_or_default().to_string(),
+        target_type: non_empty(&query.target_type)
+            .unwrap_or_default()
+            .to_string(),
+        target_id: target_id.map(|id| id.to_string()).unwrap_or_default(),
+        from: from.map(|date| date.to_string()).unwrap_or_default(),
+        to: to.map(|date| date.to_string()).unwrap_or_default(),
+    };
+
+    let html = audit::index(
+        events,
+        target_types,
+        filters,
+        team.locale,
+        settings,
+        team.teams,
+    );
+
+    Ok(Html(html))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forwarded(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(X_FORWARDED_FOR, value.parse().unwrap());
+        headers
+    }
+
+    fn ip(ip: &str) -> Option<IpAddr> {
+        Some(ip.parse().unwrap())
+    }
+
+    #[test]
+    fn forwarded_for_only_counts_from_a_trusted_proxy() {
+        let trusted = TrustedProxies::parse("10.0.0.0/8, 192.168.1.10").unwrap();
+        let spoofed = forwarded("6.6.6.6, 203.0.113.7, 10.0.0.2");
+
+        // Straight from the client, whatever it says
+        assert_eq!(
+            client_ip(ip("203.0.113.7"), &spoofed, &trusted),
+            ip("203.0.113.7")
+        );
+        assert_eq!(
+            client_ip(ip("10.0.0.1"), &spoofed, &TrustedProxies::default()),
+            ip("10.0.0.1")
+        );
+
+        // Through our proxies the right-most address that isn't ours
+        assert_eq!(
+            client_ip(ip("10.0.0.1"), &spoofed, &trusted),
+            ip("203.0.113.7")
+        );
+        assert_eq!(
+            client_ip(ip("::ffff:192.168.1.10"), &spoofed, &trusted),
+            ip("203.0.113.7")
+        );
+        assert_eq!(
+            client_ip(ip("10.0.0.1"), &forwarded("junk, 10.0.0.2"), &trusted),
+            ip("10.0.0.2")
+        );
+        assert_eq!(
+            client_ip(ip("10.0.0.1"), &HeaderMap::new(), &trusted),
+            ip("10.0.0.1")
+        );
+
+        assert!(TrustedProxies::parse("10.0.0.0/33").is_err());
+        assert!(TrustedProxies::parse("proxy").is_err());
+    }
+}
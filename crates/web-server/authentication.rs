@@ -0,0 +1,143 @@
+use core::str;
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{request::Parts, StatusCode},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const X_FORWARDED_ACCESS_TOKEN: &str = "X-Forwarded-Access-Token";
+const X_FORWARDED_USER: &str = "X-Forwarded-User";
+const X_FORWARDED_EMAIL: &str = "X-Forwarded-Email";
+// Signs every request in as the user in this token, whatever the headers
+// say. Only for local development, `Config::from_env` warns when it's set.
+const DANGER_JWT_OVERRIDE: &str = "DANGER_JWT_OVERRIDE";
+
+// We assume all calls are pre-authenticated by something like Oauth2 Proxy
+// and arrive with the identity of the user in the headers.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Jwt {
+    pub sub: String,
+    pub email: String,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Jwt
+where
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let access_token = if let Ok(override_token) = std::env::var(DANGER_JWT_OVERRIDE) {
+            Some(override_token)
+        } else {
+            parts
+                .headers
+                .get(X_FORWARDED_ACCESS_TOKEN)
+                .and_then(|header| header.to_str().ok())
+                .map(|s| s.to_string())
+        };
+        let forwarded_user = parts.headers.get(X_FORWARDED_USER);
+        let forwarded_email = parts.headers.get(X_FORWARDED_EMAIL);
+
+        if let Some(access_token) = access_token {
+            if let Some(jwt) = decode_jwt(&access_token) {
+                return Ok(jwt);
+            }
+        } else if let (Some(user), Some(email)) = (forwarded_user, forwarded_email) {
+            if let (Ok(sub), Ok(email)) = (user.to_str(), email.to_str()) {
+                return Ok(Jwt {
+                    sub: sub.to_string(),
+                    email: email.to_string(),
+                    given_name: None,
+                    family_name: None,
+                });
+            }
+        }
+        Err((
+            StatusCode::UNAUTHORIZED,
+            "Didn't find an authentication header",
+        ))
+    }
+}
+
+// We don't verify the signature, that's the job of the proxy in front of us.
+fn decode_jwt(access_token: &str) -> Option<Jwt> {
+    let jwt_parts: Vec<&str> = access_token.split('.').collect();
+    if jwt_parts.len() != 3 {
+        return None;
+    }
+
+    let payload = URL_SAFE_NO_PAD.decode(jwt_parts[1]).ok()?;
+    let payload_str = str::from_utf8(&payload).ok()?;
+    let json_value: Value = serde_json::from_str(payload_str).ok()?;
+
+    let sub = json_value.get("sub").and_then(|v| v.as_str())?;
+    let email = json_value.get("email").and_then(|v| v.as_str())?;
+    let given_name = json_value
+        .get("given_name")
+        .and_then(|v| v.as_str())
+        .map(String::from);
+    let family_name = json_value
+        .get("family_name")
+        .and_then(|v| v.as_str())
+        .map(String::from);
+
+    Some(Jwt {
+        sub: sub.to_string(),
+        email: email.to_string(),
+        given_name,
+        family_name,
+    })
+}
+
+impl Jwt {
+    /// Load the settings for the authenticated user, creating the user
+    /// the first time we see them.
+    pub async fn settings(
+        &self,
+        client: &mut db::Client,
+    ) -> Result<db::UserSettings, db::TokioPostgresError> {
+        let email = self.email.as_str();
+        let user = db::queries::users::get_user_by_email()
+            .bind(&*client, &email)
+            .opt()
+            .await?;
+        let user = match user {
+            Some(user) => user,
+            // In a transaction so creating the user counts as a write, and
+            // the page we redirect to reads from the primary.
+            None => {
+                let transaction = client.transaction().await?;
+                let user = db::queries::users::get_or_create_user()
+                    .bind(&transaction, &email)
+                    .opt()
+                    .await?;
+                // A first login racing another for the same user waits for
+                // it, then finds the row in a statement of its own.
+                let user = match user {
+                    Some(user) => user,
+                    None => {
+                        db::queries::users::get_user_by_email()
+                            .bind(&transaction, &email)
+                            .one()
+                            .await?
+                    }
+                };
+                transaction.commit().await?;
+                user
+            }
+        };
+
+        db::queries::user_settings::get_user_settings()
+            .bind(&*client, &user.id)
+            .one()
+            .await
+    }
+}
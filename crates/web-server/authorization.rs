@@ -0,0 +1,107 @@
+use std::marker::PhantomData;
+
+use crate::{api_keys::ApiKey, teams::CurrentTeam};
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+};
+use db::Permission;
+
+/// A permission a handler can ask for with `Authorize<P>`. Enums can't be
+/// generic parameters yet so each permission gets a marker type.
+pub trait RequiredPermission {
+    const PERMISSION: Permission;
+}
+
+macro_rules! permissions {
+    ($($name:ident),*) => {
+        $(
+            pub struct $name;
+
+            impl RequiredPermission for $name {
+                const PERMISSION: Permission = Permission::$name;
+            }
+        )*
+    };
+}
+
+permissions!(
+    ViewUsers,
+    CreateUsers,
+    ViewTeam,
+    ManageTeam,
+    ViewJobs,
+    RetryJobs,
+    ViewAuditLog,
+    ViewFiles,
+    UploadFiles
+);
+
+/// The current team, but only if the user's role has permission `P` in it.
+/// Anyone else gets a 403.
+pub struct Authorize<P>(pub CurrentTeam, pub PhantomData<P>);
+
+#[async_trait]
+impl<S, P> FromRequestParts<S> for Authorize<P>
+where
+    S: Send + Sync,
+    P: RequiredPermission,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let team = CurrentTeam::from_request_parts(parts, state).await?;
+
+        if team.teams.can(P::PERMISSION) {
+            Ok(Authorize(team, PhantomData))
+        } else {
+            Err((StatusCode::FORBIDDEN, "Forbidden").into_response())
+        }
+    }
+}
+
+/// The current team, but only for the people in `ADMIN_EMAILS`. Their role
+/// in the team doesn't come into it, everyone else gets a 403.
+pub struct SiteAdmin(pub CurrentTeam);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for SiteAdmin
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let team = CurrentTeam::from_request_parts(parts, state).await?;
+
+        if team.teams.site_admin {
+            Ok(SiteAdmin(team))
+        } else {
+            Err((StatusCode::FORBIDDEN, "Forbidden").into_response())
+        }
+    }
+}
+
+/// The same check for the JSON API, against the scopes of the API key.
+pub struct AuthorizeKey<P>(pub ApiKey, pub PhantomData<P>);
+
+#[async_trait]
+impl<S, P> FromRequestParts<S> for AuthorizeKey<P>
+where
+    S: Send + Sync,
+    P: RequiredPermission,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let key = ApiKey::from_request_parts(parts, state).await?;
+
+        if key.can(P::PERMISSION) {
+            Ok(AuthorizeKey(key, PhantomData))
+        } else {
+            Err((StatusCode::FORBIDDEN, "Forbidden").into_response())
+        }
+    }
+}
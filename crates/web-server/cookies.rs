@@ -0,0 +1,34 @@
+use axum::http::{header::COOKIE, HeaderMap};
+
+const ONE_YEAR: u32 = 365 * 24 * 60 * 60;
+
+/// The value of a cookie the browser sent.
+pub fn get<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|header| header.to_str().ok())
+        .flat_map(|header| header.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(cookie, _)| *cookie == name)
+        .map(|(_, value)| value)
+}
+
+/// A `Set-Cookie` value that keeps a preference for a year, `None` clears it.
+pub fn set(name: &str, value: Option<&str>) -> String {
+    match value {
+        Some(value) => format!("{name}={value}; Path=/; Max-Age={ONE_YEAR}; SameSite=Lax"),
+        None => format!("{name}=; Path=/; Max-Age=0; SameSite=Lax"),
+    }
+}
+
+/// A `Set-Cookie` value that lasts until the browser is closed.
+pub fn session(name: &str, value: &str) -> String {
+    format!("{name}={value}; Path=/; HttpOnly; SameSite=Lax")
+}
+
+/// A `Set-Cookie` value that carries something to the next page and
+/// expires after a few minutes if it's never read.
+pub fn flash(name: &str, value: &str) -> String {
+    format!("{name}={value}; Path=/; Max-Age=600; HttpOnly; SameSite=Lax")
+}
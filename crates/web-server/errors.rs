@@ -9,6 +9,7 @@ use std::fmt;
 pub enum CustomError {
     FaultySetup(String),
     Database(String),
+    Storage(String),
 }
 
 // Allow the use of "{}" format specifier
@@ -20,6 +21,7 @@ impl fmt::Display for CustomError {
             CustomError::Database(ref cause) => {
                 write!(f, "Database Error: {}", cause)
             }
+            CustomError::Storage(ref cause) => write!(f, "Storage Error: {}", cause),
         }
     }
 }
@@ -30,6 +32,7 @@ impl IntoResponse for CustomError {
         let (status, error_message) = match self {
             CustomError::Database(message) => (StatusCode::UNPROCESSABLE_ENTITY, message),
             CustomError::FaultySetup(message) => (StatusCode::UNPROCESSABLE_ENTITY, message),
+            CustomError::Storage(message) => (StatusCode::UNPROCESSABLE_ENTITY, message),
         };
 
         format!("status = {}, message = {}", status, error_message).into_response()
@@ -53,3 +56,16 @@ impl From<PoolError> for CustomError {
         CustomError::Database(err.to_string())
     }
 }
+
+// Most likely ENCRYPTION_KEYS is missing a key that was used before.
+impl From<db::encryption::EncryptionError> for CustomError {
+    fn from(err: db::encryption::EncryptionError) -> CustomError {
+        CustomError::FaultySetup(err.to_string())
+    }
+}
+
+impl From<storage::StorageError> for CustomError {
+    fn from(err: storage::StorageError) -> CustomError {
+        CustomError::Storage(err.to_string())
+    }
+}
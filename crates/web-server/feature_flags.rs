@@ -0,0 +1,371 @@
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use crate::{
+    audit::RequestMeta, authorization::SiteAdmin, errors::CustomError,
+    notifications::FeatureFlagsChanged, teams::CurrentTeam,
+};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Path},
+    http::{request::Parts, StatusCode},
+    response::{Html, IntoResponse, Redirect, Response},
+    Extension,
+};
+use axum_extra::extract::Form;
+use db::{rls::Tenant, FeatureFlag};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tokio::sync::broadcast::{self, error::RecvError};
+use validator::{Validate, ValidationError};
+use web_pages::feature_flags::{self, Flags};
+
+// In case a notification went missing while the listener was reconnecting.
+const RELOAD_EVERY: Duration = Duration::from_secs(60);
+
+/// Every flag, kept in memory so checking one doesn't need the database.
+#[derive(Clone, Default)]
+pub struct FlagCache(Arc<RwLock<Vec<FeatureFlag>>>);
+
+impl FlagCache {
+    /// Load the flags and reload them whenever `changes` says one changed.
+    pub fn spawn(
+        pool: db::Pool,
+        mut changes: broadcast::Receiver<FeatureFlagsChanged>,
+    ) -> FlagCache {
+        let cache = FlagCache::default();
+
+        let task = cache.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = task.reload(&pool).await {
+                    eprintln!("Unable to load feature flags: {e}");
+                }
+                // Lagging behind only means several changes, one reload
+                // picks them all up.
+                tokio::select! {
+                    change = changes.recv() => {
+                        // Nothing can be sent any more, so only the timer is left.
+                        if let Err(RecvError::Closed) = change {
+                            tokio::time::sleep(RELOAD_EVERY).await;
+                        }
+                    }
+                    _ = tokio::time::sleep(RELOAD_EVERY) => {}
+                }
+            }
+        });
+
+        cache
+    }
+
+    pub async fn reload(&self, pool: &db::Pool) -> Result<(), CustomError> {
+        let client = pool.get().await?;
+        let flags = db::queries::feature_flags::get_feature_flags()
+            .bind(&client)
+            .all()
+            .await?;
+        *self.0.write().unwrap() = flags;
+        Ok(())
+    }
+
+    /// The flags that are on for this user in this team.
+    pub fn evaluate(&self, tenant: Tenant) -> Flags {
+        self.0
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|flag| is_on(flag, tenant))
+            .map(|flag| flag.name.clone())
+            .collect()
+    }
+}
+
+fn is_on(flag: &FeatureFlag, tenant: Tenant) -> bool {
+    flag.enabled
+        || flag.user_ids.contains(&tenant.user_id)
+        || flag.team_ids.contains(&tenant.team_id)
+        || bucket(&flag.name, tenant.user_id) < flag.rollout_percentage
+}
+
+// 0 to 99. The same user always lands in the same bucket so a flag doesn't
+// flicker between requests, and raising the percentage only ever adds
+// users. Hashing the name too means it isn't the same users who get every
+// new feature first.
+fn bucket(name: &str, user_id: i32) -> i32 {
+    let hash = Sha256::digest(format!("{name}:{user_id}"));
+    let first = u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]]);
+    (first % 100) as i32
+}
+
+/// The flags that are on for the signed in user in the current team, pass
+/// them to pages as they are.
+pub struct FeatureFlags(pub Flags);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for FeatureFlags
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        // Handlers nearly always take the current team as well, and that
+        // leaves its tenant behind so we don't look it up twice.
+        let tenant = match parts.extensions.get::<Tenant>() {
+            Some(tenant) => *tenant,
+            None => CurrentTeam::from_request_parts(parts, state)
+                .await?
+                .tenant(),
+        };
+        let cache = parts
+            .extensions
+            .get::<FlagCache>()
+            .ok_or_else(|| CustomError::FaultySetup("No feature flags".into()).into_response())?;
+
+        Ok(FeatureFlags(cache.evaluate(tenant)))
+    }
+}
+
+pub async fn loader(
+    Extension(pool): Extension<db::Pool>,
+    SiteAdmin(team): SiteAdmin,
+    FeatureFlags(flags): FeatureFlags,
+) -> Result<Html<String>, CustomError> {
+    let client = pool.get().await?;
+    let feature_flags = db::queries::feature_flags::get_feature_flags()
+        .bind(&client)
+        .all()
+        .await?;
+
+    let html = feature_flags::index(feature_flags, flags, team.locale, team.settings, team.teams);
+
+    Ok(Html(html))
+}
+
+#[derive(Deserialize, Validate)]
+pub struct NewFeatureFlag {
+    // Names end up in code, so keep them to something easy to type.
+    #[validate(length(min = 1, max = 100), custom(function = "validate_name"))]
+    name: String,
+    #[serde(default)]
+    #[validate(length(max = 500))]
+    description: String,
+}
+
+fn validate_name(name: &str) -> Result<(), ValidationError> {
+    let valid = name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(ValidationError::new("name"))
+    }
+}
+
+pub async fn new_action(
+    Extension(pool): Extension<db::Pool>,
+    SiteAdmin(team): SiteAdmin,
+    Extension(cache): Extension<FlagCache>,
+    meta: RequestMeta,
+    Form(form): Form<NewFeatureFlag>,
+) -> Result<Response, CustomError> {
+    if form.validate().is_err() {
+        return Ok((StatusCode::BAD_REQUEST, "Bad request").into_response());
+    }
+
+    let mut client = pool.get().await?;
+    // Flags aren't scoped, but the audit log is.
+    let transaction = db::rls::transaction(&mut client, team.tenant()).await?;
+
+    let flag = db::queries::feature_flags::create_feature_flag()
+        .bind(
+            &transaction,
+            &form.name.as_str(),
+            &form.description.as_str(),
+        )
+        .opt()
+        .await?;
+    // Nothing comes back when the name is taken.
+    let Some(flag) = flag else {
+        return Ok((StatusCode::BAD_REQUEST, "Bad request").into_response());
+    };
+
+    db::audit::record(
+        &transaction,
+        db::audit::AuditEvent {
+            team_id: Some(team.id),
+            actor_id: Some(team.settings.user_id),
+            action: "feature_flag.created",
+            target_type: "feature_flag",
+            target_id: Some(flag.id),
+            diff: db::audit::diff(None, Some(&flag)),
+            ip_address: meta.ip_address,
+            user_agent: meta.user_agent.as_deref(),
+        },
+    )
+    .await?;
+
+    transaction.commit().await?;
+    // Other servers hear about it from Postgres, this one shouldn't wait.
+    cache.reload(&pool).await?;
+
+    Ok(Redirect::to(&format!("/teams/{}/feature_flags", team.id)).into_response())
+}
+
+#[derive(Deserialize, Validate)]
+pub struct FeatureFlagForm {
+    // Unchecked checkboxes aren't sent by the browser
+    enabled: Option<String>,
+    #[validate(range(min = 0, max = 100))]
+    rollout_percentage: i32,
+    #[serde(default)]
+    user_ids: String,
+    #[serde(default)]
+    team_ids: String,
+}
+
+// "1, 2,3" is [1, 2, 3], anything that isn't a number is None.
+fn parse_ids(ids: &str) -> Option<Vec<i32>> {
+    ids.split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| id.parse().ok())
+        .collect()
+}
+
+pub async fn update_action(
+    Extension(pool): Extension<db::Pool>,
+    SiteAdmin(team): SiteAdmin,
+    Extension(cache): Extension<FlagCache>,
+    meta: RequestMeta,
+    Path((_, id)): Path<(i32, i32)>,
+    Form(form): Form<FeatureFlagForm>,
+) -> Result<Response, CustomError> {
+    let (Ok(()), Some(user_ids), Some(team_ids)) = (
+        form.validate(),
+        parse_ids(&form.user_ids),
+        parse_ids(&form.team_ids),
+    ) else {
+        return Ok((StatusCode::BAD_REQUEST, "Bad request").into_response());
+    };
+
+    let mut client = pool.get().await?;
+    let transaction = db::rls::transaction(&mut client, team.tenant()).await?;
+
+    let Some(old) = db::queries::feature_flags::get_feature_flag()
+        .bind(&transaction, &id)
+        .opt()
+        .await?
+    else {
+        return Ok((StatusCode::NOT_FOUND, "Feature flag not found").into_response());
+    };
+
+    let flag = db::queries::feature_flags::update_feature_flag()
+        .bind(
+            &transaction,
+            &form.enabled.is_some(),
+            &form.rollout_percentage,
+            &user_ids,
+            &team_ids,
+            &id,
+        )
+        .one()
+        .await?;
+
+    db::audit::record(
+        &transaction,
+        db::audit::AuditEvent {
+            team_id: Some(team.id),
+            actor_id: Some(team.settings.user_id),
+            action: "feature_flag.updated",
+            target_type: "feature_flag",
+            target_id: Some(flag.id),
+            diff: db::audit::diff(Some(&old), Some(&flag)),
+            ip_address: meta.ip_address,
+            user_agent: meta.user_agent.as_deref(),
+        },
+    )
+    .await?;
+
+    transaction.commit().await?;
+    cache.reload(&pool).await?;
+
+    Ok(Redirect::to(&format!("/teams/{}/feature_flags", team.id)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use time::OffsetDateTime;
+
+    use super::*;
+
+    fn flag(rollout_percentage: i32) -> FeatureFlag {
+        FeatureFlag {
+            id: 1,
+            name: "new-dashboard".to_string(),
+            description: String::new(),
+            enabled: false,
+            rollout_percentage,
+            user_ids: vec![],
+            team_ids: vec![],
+            updated_at: OffsetDateTime::UNIX_EPOCH,
+        }
+    }
+
+    fn tenant(user_id: i32) -> Tenant {
+        Tenant {
+            user_id,
+            team_id: 1,
+        }
+    }
+
+    fn share_on(flag: &FeatureFlag) -> usize {
+        (1..=1000).filter(|user| is_on(flag, tenant(*user))).count()
+    }
+
+    #[test]
+    fn rollouts_are_stable_and_only_grow() {
+        assert_eq!(share_on(&flag(0)), 0);
+        assert_eq!(share_on(&flag(100)), 1000);
+        let quarter = share_on(&flag(25));
+        assert!((200..300).contains(&quarter), "{quarter} of 1000 users");
+
+        // Everyone who had it at 25% still has it at 50%.
+        for user in 1..=1000 {
+            if is_on(&flag(25), tenant(user)) {
+                assert!(is_on(&flag(50), tenant(user)));
+            }
+        }
+    }
+
+    #[test]
+    fn targeted_users_and_teams_always_get_it() {
+        let mut targeted = flag(0);
+        targeted.user_ids = vec![7];
+        targeted.team_ids = vec![3];
+
+        assert!(is_on(&targeted, tenant(7)));
+        assert!(!is_on(&targeted, tenant(8)));
+        assert!(is_on(
+            &targeted,
+            Tenant {
+                user_id: 8,
+                team_id: 3
+            }
+        ));
+
+        let mut everyone = flag(0);
+        everyone.enabled = true;
+        assert!(is_on(&everyone, tenant(8)));
+    }
+
+    #[test]
+    fn ids_are_comma_separated() {
+        assert_eq!(parse_ids(""), Some(vec![]));
+        assert_eq!(parse_ids("1, 2,3,"), Some(vec![1, 2, 3]));
+        assert_eq!(parse_ids("1, two"), None);
+    }
+}
@@ -0,0 +1,516 @@
+//! Avatars and attachments. Uploads are checked for size and for what's
+//! really in them, kept by the `storage` crate and described in the files
+//! table. Downloads go through links that are signed and expire, so they
+//! work in an `<img>` and can be passed around without a session.
+
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    audit::RequestMeta,
+    authorization::{Authorize, UploadFiles, ViewFiles},
+    errors::CustomError,
+    teams::CurrentTeam,
+};
+use axum::{
+    extract::{Multipart, Path, Query},
+    http::{
+        header::{CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_TYPE, X_CONTENT_TYPE_OPTIONS},
+        StatusCode,
+    },
+    response::{Html, IntoResponse, Redirect, Response},
+    Extension,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use db::FileKind;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use storage::Storage;
+use web_pages::files::{self, Attachment};
+
+const AVATAR_LIMIT: usize = 2 * 1024 * 1024;
+// Long enough to read the page and click, short enough that a link that
+// gets out isn't much use.
+const LINK_TTL: Duration = Duration::from_secs(15 * 60);
+
+const DOCX: &str = "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+const XLSX: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+const PPTX: &str = "application/vnd.openxmlformats-officedocument.presentationml.presentation";
+
+const IMAGES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp"];
+const ATTACHMENTS: &[&str] = &[
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "application/pdf",
+    "text/plain",
+    "text/csv",
+    "application/zip",
+    DOCX,
+    XLSX,
+    PPTX,
+];
+
+/// Signs download links. Every server needs the same secret, or a link
+/// made by one won't work on another, see `Config::file_url_secret`.
+#[derive(Clone)]
+pub struct Signer(Arc<Vec<u8>>);
+
+impl Signer {
+    pub fn new(secret: &[u8]) -> Signer {
+        Signer(Arc::new(secret.to_vec()))
+    }
+
+    /// A link to the file that works for the next 15 minutes.
+    pub fn url(&self, id: i32) -> String {
+        self.url_until(id, now() + LINK_TTL.as_secs())
+    }
+
+    /// `expires` is in seconds since the epoch.
+    pub fn url_until(&self, id: i32, expires: u64) -> String {
+        let signature = URL_SAFE_NO_PAD.encode(self.mac(id, expires).finalize().into_bytes());
+        format!("/files/{id}?expires={expires}&signature={signature}")
+    }
+
+    fn mac(&self, id: i32, expires: u64) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.0).expect("HMAC takes a key of any length");
+        mac.update(format!("{id}:{expires}").as_bytes());
+        mac
+    }
+
+    fn verify(&self, id: i32, link: &Link, now: u64) -> bool {
+        let Ok(signature) = URL_SAFE_NO_PAD.decode(&link.signature) else {
+            return false;
+        };
+        // Compared in constant time
+        link.expires >= now && self.mac(id, link.expires).verify_slice(&signature).is_ok()
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+pub async fn loader(
+    Extension(pool): Extension<db::Pool>,
+    Authorize(team, _): Authorize<ViewFiles>,
+    Extension(signer): Extension<Signer>,
+) -> Result<Html<String>, CustomError> {
+    let mut client = pool.get().await?;
+    let transaction = db::rls::transaction(&mut client, team.tenant()).await?;
+    let attachments = db::queries::files::get_files()
+        .bind(&transaction, &team.id)
+        .all()
+        .await?
+        .into_iter()
+        .map(|file| Attachment {
+            url: signer.url(file.id),
+            file,
+        })
+        .collect();
+
+    let html = files::index(attachments, team.locale, team.settings, team.teams);
+
+    Ok(Html(html))
+}
+
+struct Upload {
+    file_name: String,
+    // Whatever the browser guessed from the extension
+    declared: Option<String>,
+    bytes: Vec<u8>,
+}
+
+// The `file` field, as long as it's no bigger than `limit`.
+async fn read_upload(multipart: &mut Multipart, limit: usize) -> Result<Upload, Response> {
+    // Going over the body limit shows up here as a 413.
+    let failed =
+        |e: axum::extract::multipart::MultipartError| (e.status(), e.body_text()).into_response();
+
+    while let Some(mut field) = multipart.next_field().await.map_err(failed)? {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let file_name = clean_file_name(field.file_name().unwrap_or_default());
+        let declared = field.content_type().map(String::from);
+
+        let mut bytes = vec![];
+        while let Some(chunk) = field.chunk().await.map_err(failed)? {
+            if bytes.len() + chunk.len() > limit {
+                return Err((StatusCode::PAYLOAD_TOO_LARGE, "File too large").into_response());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        return Ok(Upload {
+            file_name,
+            declared,
+            bytes,
+        });
+    }
+
+    Err((StatusCode::BAD_REQUEST, "Bad request").into_response())
+}
+
+// Browsers only send the name, but anything could be in there. What's
+// left is safe to put in a Content-Disposition header.
+fn clean_file_name(name: &str) -> String {
+    let name = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_graphic() && c != '"' || c == ' ' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(255)
+        .collect();
+    let name = name.trim();
+    if name.is_empty() {
+        "file".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// What the contents say the file is. The extension only tells zip based
+/// office documents apart, and CSV from other text.
+fn sniff(bytes: &[u8], file_name: &str) -> Option<&'static str> {
+    let extension = file_name
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if bytes.starts_with(b"%PDF-") {
+        Some("application/pdf")
+    } else if bytes.starts_with(b"PK\x03\x04") {
+        match extension.as_str() {
+            "docx" => Some(DOCX),
+            "xlsx" => Some(XLSX),
+            "pptx" => Some(PPTX),
+            _ => Some("application/zip"),
+        }
+    } else if !bytes.is_empty() && !bytes.contains(&0) && std::str::from_utf8(bytes).is_ok() {
+        match extension.as_str() {
+            "csv" => Some("text/csv"),
+            _ => Some("text/plain"),
+        }
+    } else {
+        None
+    }
+}
+
+/// The type to store the upload as, if it's one we take. When the browser
+/// names a type we know and the contents disagree, the file has been
+/// renamed, say a script called photo.png, and it's turned away too.
+fn content_type(upload: &Upload, allowed: &[&str]) -> Option<&'static str> {
+    let sniffed = sniff(&upload.bytes, &upload.file_name).filter(|t| allowed.contains(t))?;
+
+    let declared = upload
+        .declared
+        .as_deref()
+        .and_then(|declared| declared.split(';').next())
+        .map(|declared| declared.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    let agrees = if declared.starts_with("text/") {
+        sniffed.starts_with("text/")
+    } else {
+        !ATTACHMENTS.contains(&declared.as_str()) || declared == sniffed
+    };
+
+    agrees.then_some(sniffed)
+}
+
+// Contents first, then the row that points at them. If the row can't be
+// written the contents are removed again.
+async fn save(
+    pool: &db::Pool,
+    storage: &dyn Storage,
+    team: &CurrentTeam,
+    meta: &RequestMeta,
+    kind: FileKind,
+    upload: Upload,
+    content_type: &str,
+) -> Result<i32, CustomError> {
+    let mut random = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut random);
+    let key = format!("{}/{}", team.id, URL_SAFE_NO_PAD.encode(random));
+    let size_bytes = upload.bytes.len() as i64;
+    let sha256 = Sha256::digest(&upload.bytes).to_vec();
+
+    storage.put(&key, content_type, upload.bytes.into()).await?;
+
+    let saved = async {
+        let mut client = pool.get().await?;
+        let transaction = db::rls::transaction(&mut client, team.tenant()).await?;
+
+        let id = db::queries::files::create_file()
+            .bind(
+                &transaction,
+                &team.id,
+                &Some(team.settings.user_id),
+                &kind,
+                &key.as_str(),
+                &upload.file_name.as_str(),
+                &content_type,
+                &size_bytes,
+                &sha256,
+            )
+            .one()
+            .await?;
+
+        db::audit::record(
+            &transaction,
+            db::audit::AuditEvent {
+                team_id: Some(team.id),
+                actor_id: Some(team.settings.user_id),
+                action: "file.uploaded",
+                target_type: "file",
+                target_id: Some(id),
+                diff: db::audit::diff(
+                    None,
+                    Some(&serde_json::json!({
+                        "kind": kind,
+                        "file_name": upload.file_name,
+                        "content_type": content_type,
+                        "size_bytes": size_bytes,
+                    })),
+                ),
+                ip_address: meta.ip_address,
+                user_agent: meta.user_agent.as_deref(),
+            },
+        )
+        .await?;
+
+        transaction.commit().await?;
+        Ok::<_, CustomError>(id)
+    }
+    .await;
+
+    if saved.is_err() {
+        if let Err(e) = storage.delete(&key).await {
+            eprintln!("Unable to remove {key} after a failed upload: {e}");
+        }
+    }
+    saved
+}
+
+// Attachments can be as big as the upload body limit allows.
+pub async fn upload_action(
+    Extension(pool): Extension<db::Pool>,
+    Authorize(team, _): Authorize<UploadFiles>,
+    Extension(storage): Extension<Arc<dyn Storage>>,
+    meta: RequestMeta,
+    mut multipart: Multipart,
+) -> Result<Response, CustomError> {
+    let upload = match read_upload(&mut multipart, usize::MAX).await {
+        Ok(upload) => upload,
+        Err(response) => return Ok(response),
+    };
+    let Some(content_type) = content_type(&upload, ATTACHMENTS) else {
+        return Ok((StatusCode::BAD_REQUEST, "Unsupported file type").into_response());
+    };
+
+    save(
+        &pool,
+        storage.as_ref(),
+        &team,
+        &meta,
+        FileKind::Attachment,
+        upload,
+        content_type,
+    )
+    .await?;
+
+    Ok(Redirect::to(&format!("/teams/{}/files", team.id)).into_response())
+}
+
+pub async fn avatar_action(
+    Extension(pool): Extension<db::Pool>,
+    team: CurrentTeam,
+    Extension(storage): Extension<Arc<dyn Storage>>,
+    meta: RequestMeta,
+    mut multipart: Multipart,
+) -> Result<Response, CustomError> {
+    let upload = match read_upload(&mut multipart, AVATAR_LIMIT).await {
+        Ok(upload) => upload,
+        Err(response) => return Ok(response),
+    };
+    let Some(content_type) = content_type(&upload, IMAGES) else {
+        return Ok((StatusCode::BAD_REQUEST, "Unsupported file type").into_response());
+    };
+
+    save(
+        &pool,
+        storage.as_ref(),
+        &team,
+        &meta,
+        FileKind::Avatar,
+        upload,
+        content_type,
+    )
+    .await?;
+
+    Ok(Redirect::to(&format!("/teams/{}/settings", team.id)).into_response())
+}
+
+/// The signed in user's avatar. Pages link here rather than to the file,
+/// so they don't need a signer and the link never expires.
+pub async fn avatar(
+    team: CurrentTeam,
+    Extension(signer): Extension<Signer>,
+) -> Result<Response, CustomError> {
+    match team.settings.avatar_id {
+        Some(id) => Ok(Redirect::to(&signer.url(id)).into_response()),
+        None => Ok((StatusCode::NOT_FOUND, "No avatar").into_response()),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct Link {
+    expires: u64,
+    signature: String,
+}
+
+// Anyone with a link that's still good gets the file, there's no session
+// to check, nor a tenant to scope the lookup to.
+pub async fn download(
+    Extension(db::PrivilegedPool(pool)): Extension<db::PrivilegedPool>,
+    Extension(storage): Extension<Arc<dyn Storage>>,
+    Extension(signer): Extension<Signer>,
+    Path(id): Path<i32>,
+    Query(link): Query<Link>,
+) -> Result<Response, CustomError> {
+    let now = now();
+    if !signer.verify(id, &link, now) {
+        return Ok((StatusCode::FORBIDDEN, "This link has expired").into_response());
+    }
+
+    let client = pool.get().await?;
+    let Some(file) = db::queries::files::get_file_download()
+        .bind(&client, &id)
+        .opt()
+        .await?
+    else {
+        return Ok((StatusCode::NOT_FOUND, "File not found").into_response());
+    };
+    let bytes = storage.get(&file.storage_key).await?;
+
+    // Only images are shown in the browser, anything else is saved. The
+    // type is what we sniffed, and the browser isn't to guess another.
+    let disposition = if IMAGES.contains(&file.content_type.as_str()) {
+        "inline".to_string()
+    } else {
+        format!("attachment; filename=\"{}\"", file.file_name)
+    };
+    let cache = format!("private, max-age={}", link.expires - now);
+
+    Ok((
+        [
+            (CONTENT_TYPE, file.content_type),
+            (CONTENT_DISPOSITION, disposition),
+            (X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+            (CACHE_CONTROL, cache),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upload(file_name: &str, declared: Option<&str>, bytes: &[u8]) -> Upload {
+        Upload {
+            file_name: file_name.to_string(),
+            declared: declared.map(String::from),
+            bytes: bytes.to_vec(),
+        }
+    }
+
+    #[test]
+    fn files_are_what_their_contents_say() {
+        let png = b"\x89PNG\r\n\x1a\n rest of the image";
+        assert_eq!(
+            content_type(&upload("me.png", Some("image/png"), png), IMAGES),
+            Some("image/png")
+        );
+        // Browsers send octet-stream when they don't know the extension.
+        assert_eq!(
+            content_type(&upload("me", Some("application/octet-stream"), png), IMAGES),
+            Some("image/png")
+        );
+        // A PDF renamed to look like an image
+        assert_eq!(
+            content_type(
+                &upload("me.png", Some("image/png"), b"%PDF-1.7"),
+                ATTACHMENTS
+            ),
+            None
+        );
+        // Fine as an attachment, not as an avatar
+        let pdf = upload("report.pdf", Some("application/pdf"), b"%PDF-1.7");
+        assert_eq!(content_type(&pdf, ATTACHMENTS), Some("application/pdf"));
+        assert_eq!(content_type(&pdf, IMAGES), None);
+
+        let docx = upload("plan.docx", Some(DOCX), b"PK\x03\x04 the rest");
+        assert_eq!(content_type(&docx, ATTACHMENTS), Some(DOCX));
+        let csv = upload("users.csv", Some("text/csv"), b"email\nian@test.com\n");
+        assert_eq!(content_type(&csv, ATTACHMENTS), Some("text/csv"));
+        let binary = upload("a.exe", None, b"MZ\x90\x00\x03\x00");
+        assert_eq!(content_type(&binary, ATTACHMENTS), None);
+    }
+
+    #[test]
+    fn links_expire_and_cant_be_changed() {
+        let signer = Signer::new(b"secret");
+        let link = |url: String| {
+            Query::<Link>::try_from_uri(&url.parse().unwrap())
+                .unwrap()
+                .0
+        };
+
+        let good = link(signer.url_until(7, 1_000));
+        assert!(signer.verify(7, &good, 1_000));
+        assert!(!signer.verify(7, &good, 1_001));
+        assert!(!signer.verify(8, &good, 1_000));
+        assert!(!Signer::new(b"other").verify(7, &good, 1_000));
+
+        let later = Link {
+            expires: 2_000,
+            ..good
+        };
+        assert!(!signer.verify(7, &later, 1_000));
+    }
+
+    #[test]
+    fn file_names_are_safe_in_headers() {
+        assert_eq!(clean_file_name("C:\\Users\\ian\\report.pdf"), "report.pdf");
+        assert_eq!(clean_file_name("../../etc/passwd"), "passwd");
+        assert_eq!(
+            clean_file_name("a \"quoted\"\r\nname.txt"),
+            "a _quoted___name.txt"
+        );
+        assert_eq!(clean_file_name(""), "file");
+    }
+}
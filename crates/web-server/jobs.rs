@@ -0,0 +1,67 @@
+use crate::{
+    audit::RequestMeta,
+    authorization::{Authorize, RetryJobs, ViewJobs},
+    errors::CustomError,
+};
+use axum::{
+    extract::Path,
+    http::StatusCode,
+    response::{Html, IntoResponse, Redirect, Response},
+    Extension,
+};
+use web_pages::jobs;
+
+pub async fn loader(
+    Extension(pool): Extension<db::Pool>,
+    Authorize(team, _): Authorize<ViewJobs>,
+) -> Result<Html<String>, CustomError> {
+    let mut client = pool.get().await?;
+    let transaction = db::rls::transaction(&mut client, team.tenant()).await?;
+
+    let failed_jobs = db::queries::jobs::get_failed_jobs()
+        .bind(&transaction, &team.id)
+        .all()
+        .await?;
+
+    let html = jobs::index(failed_jobs, team.locale, team.settings, team.teams);
+
+    Ok(Html(html))
+}
+
+pub async fn retry_action(
+    Extension(pool): Extension<db::Pool>,
+    Authorize(team, _): Authorize<RetryJobs>,
+    meta: RequestMeta,
+    Path((_, id)): Path<(i32, i32)>,
+) -> Result<Response, CustomError> {
+    let mut client = pool.get().await?;
+
+    let transaction = db::rls::transaction(&mut client, team.tenant()).await?;
+
+    let requeued = db::queries::jobs::requeue_job()
+        .bind(&transaction, &id, &team.id)
+        .opt()
+        .await?;
+    if requeued.is_none() {
+        return Ok((StatusCode::NOT_FOUND, "Job not found").into_response());
+    }
+
+    db::audit::record(
+        &transaction,
+        db::audit::AuditEvent {
+            team_id: Some(team.id),
+            actor_id: Some(team.settings.user_id),
+            action: "job.requeued",
+            target_type: "job",
+            target_id: Some(id),
+            ip_address: meta.ip_address,
+            user_agent: meta.user_agent.as_deref(),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(Redirect::to(&format!("/teams/{}/jobs", team.id)).into_response())
+}
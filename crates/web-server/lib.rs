@@ -0,0 +1,194 @@
+//! The web server as a library, so tests can build the same `Router` the
+//! binary serves and call it in process.
+
+mod api;
+mod api_keys;
+mod audit;
+mod authentication;
+mod authorization;
+pub mod config;
+mod cookies;
+mod errors;
+pub mod feature_flags;
+mod files;
+mod jobs;
+mod locale;
+mod mailbox;
+mod middleware;
+pub mod notifications;
+mod replicas;
+mod root;
+mod settings;
+mod static_files;
+mod teams;
+pub mod telemetry;
+mod theme;
+mod users_csv;
+pub mod worker;
+
+use std::{sync::Arc, time::Duration};
+
+use axum::{
+    extract::DefaultBodyLimit,
+    middleware::{from_fn, from_fn_with_state},
+    routing::{get, post, MethodRouter},
+    Extension, Router,
+};
+use config::{Limits, MailerConfig};
+use tokio::sync::broadcast;
+use tower_http::{
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    services::ServeDir,
+};
+
+/// Every route along with the extensions the handlers need. Background
+/// work like the job worker and the listeners is up to the caller.
+pub fn app(
+    config: config::Config,
+    pools: db::Pools,
+    privileged: db::PrivilegedPool,
+    user_changes: broadcast::Sender<notifications::UserChanged>,
+    mailer: email::Mailer,
+    storage: Arc<dyn storage::Storage>,
+    flags: feature_flags::FlagCache,
+) -> Router {
+    let signer = files::Signer::new(&config.file_url_secret);
+    let limits = config.limits.clone();
+    let mut team_routes = team_routes(&limits);
+
+    // Emails only stay in memory when MAILER=memory, so let us read them.
+    if config.mailer == MailerConfig::Memory {
+        team_routes = team_routes.route("/dev/mailbox", get(mailbox::loader));
+    }
+
+    // build our application with a route
+    let app = Limited::new(&limits, "", limits.request_timeout, limits.body_limit)
+        .route("/", get(teams::redirect_to_team))
+        .route("/teams", post(teams::new_team_action))
+        .route("/invitations/:id/accept", post(teams::accept_action))
+        .route("/api/users", get(api::users))
+        .route("/files/:id", get(files::download))
+        .router
+        .nest(TEAM_PREFIX, team_routes)
+        .route("/static/*path", get(static_files::static_path))
+        // Anything with a Content-Encoding already, like the precompressed
+        // wasm bundle, is passed through as it is.
+        .nest_service(
+            "/wasm",
+            ServeDir::new("/workspace/crates/web-csr/dist")
+                .precompressed_br()
+                .precompressed_gzip(),
+        )
+        .layer(middleware::compression(config.compress_above))
+        .layer(from_fn(replicas::remember_writes));
+
+    // Handlers that store sensitive fields take Extension<Keyring>.
+    let keyring = config.encryption_keys.clone();
+
+    app.layer(Extension(keyring))
+        .layer(Extension(config))
+        // Handlers that write take the primary, see `replicas::ReadPool`
+        // for the ones that only read.
+        .layer(Extension(pools.write().clone()))
+        .layer(Extension(pools))
+        // Only for what has to happen before there's a tenant.
+        .layer(Extension(privileged))
+        .layer(Extension(user_changes))
+        .layer(Extension(mailer))
+        .layer(Extension(storage))
+        .layer(Extension(signer))
+        .layer(Extension(flags))
+        .layer(DefaultBodyLimit::max(limits.body_limit))
+        .layer(from_fn(middleware::catch_panic))
+        .layer(from_fn(middleware::trace))
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+}
+
+const TEAM_PREFIX: &str = "/teams/:team_id";
+
+// Everything a team owns lives under its id, the `CurrentTeam` extractor
+// checks the user is a member before a handler runs.
+pub fn team_routes(limits: &Limits) -> Router {
+    // Imports carry a whole file, so get longer and more room.
+    let uploads = Limited::new(
+        limits,
+        TEAM_PREFIX,
+        limits.upload_timeout,
+        limits.upload_body_limit,
+    )
+    .route("/users/import", post(users_csv::import_action))
+    .route("/users/import/preview", post(users_csv::preview_action))
+    .route("/files", post(files::upload_action))
+    .route("/avatar", post(files::avatar_action))
+    .router;
+
+    Limited::new(
+        limits,
+        TEAM_PREFIX,
+        limits.request_timeout,
+        limits.body_limit,
+    )
+    .route("/", get(root::loader))
+    .route("/new_user", post(root::new_user_action))
+    .route("/users/export.csv", get(users_csv::export))
+    .route("/team", get(teams::loader))
+    .route("/invitations", post(teams::invite_action))
+    .route("/settings", get(settings::loader).post(settings::action))
+    .route("/theme", post(theme::action))
+    .route("/api_keys", post(api_keys::new_action))
+    .route("/api_keys/:id/revoke", post(api_keys::revoke_action))
+    .route("/jobs", get(jobs::loader))
+    .route("/jobs/:id/retry", post(jobs::retry_action))
+    .route("/audit", get(audit::loader))
+    .route(
+        "/feature_flags",
+        get(feature_flags::loader).post(feature_flags::new_action),
+    )
+    .route("/feature_flags/:id", post(feature_flags::update_action))
+    .route("/files", get(files::loader))
+    .route("/avatar", get(files::avatar))
+    .router
+    // Events stay open for as long as the page does.
+    .route("/users/events", get(root::events))
+    .merge(uploads)
+}
+
+/// Adds routes with their group's timeout and body limit, or the ones
+/// `Limits` has for their path.
+struct Limited<'a> {
+    router: Router,
+    limits: &'a Limits,
+    // Where the router is nested, the overrides are by the whole path.
+    prefix: &'a str,
+    timeout: Duration,
+    body_limit: usize,
+}
+
+impl<'a> Limited<'a> {
+    fn new(limits: &'a Limits, prefix: &'a str, timeout: Duration, body_limit: usize) -> Self {
+        Limited {
+            router: Router::new(),
+            limits,
+            prefix,
+            timeout,
+            body_limit,
+        }
+    }
+
+    fn route(mut self, path: &str, method_router: MethodRouter) -> Self {
+        let full_path = format!("{}{path}", self.prefix);
+        let timeout = self.limits.route_timeouts.get(&full_path);
+        let body_limit = self.limits.route_body_limits.get(&full_path);
+        let method_router = method_router
+            .layer(DefaultBodyLimit::max(
+                *body_limit.unwrap_or(&self.body_limit),
+            ))
+            .route_layer(from_fn_with_state(
+                *timeout.unwrap_or(&self.timeout),
+                middleware::timeout,
+            ));
+        self.router = self.router.route(path, method_router);
+        self
+    }
+}
@@ -0,0 +1,28 @@
+use axum::http::{header::ACCEPT_LANGUAGE, HeaderMap};
+use web_pages::i18n::Locale;
+
+use crate::cookies;
+
+/// Remembers the language picked on the settings page, so pages that are
+/// shown before we know who the user is, like the error pages, match.
+const LOCALE_COOKIE: &str = "locale";
+
+/// The language to render in. The user's own setting wins when we have it,
+/// then the cookie, then `Accept-Language`.
+pub fn from_headers(headers: &HeaderMap, setting: Option<&str>) -> Locale {
+    let accept_language = headers
+        .get(ACCEPT_LANGUAGE)
+        .and_then(|header| header.to_str().ok());
+
+    Locale::negotiate(
+        setting,
+        cookies::get(headers, LOCALE_COOKIE),
+        accept_language,
+    )
+}
+
+/// A `Set-Cookie` value for the user's choice, `None` clears it so the
+/// browser's language is used again.
+pub fn set_cookie(locale: Option<Locale>) -> String {
+    cookies::set(LOCALE_COOKIE, locale.map(Locale::code))
+}
@@ -0,0 +1,15 @@
+use crate::{authorization::SiteAdmin, errors::CustomError};
+use axum::{response::Html, Extension};
+use email::Mailer;
+use web_pages::mailbox;
+
+pub async fn loader(
+    Extension(mailer): Extension<Mailer>,
+    SiteAdmin(team): SiteAdmin,
+) -> Result<Html<String>, CustomError> {
+    let emails = mailer.outbox().unwrap_or_default();
+
+    let html = mailbox::index(emails, team.locale, team.settings, team.teams);
+
+    Ok(Html(html))
+}
@@ -1,40 +1,93 @@
-mod config;
-mod errors;
-mod root;
-mod settings;
-mod static_files;
-
-use std::net::SocketAddr;
-use tower_http::services::ServeDir;
-
-use axum::{
-    routing::{get, post},
-    Extension, Router,
-};
+use std::{future::IntoFuture, net::SocketAddr};
+
 use tower_livereload::LiveReloadLayer;
+use web_server::{config, feature_flags, notifications, telemetry, worker};
 
 #[tokio::main]
 async fn main() {
-    let config = config::Config::new();
+    let config = config::Config::from_env();
+    let telemetry = telemetry::init(&config.telemetry);
+
+    if config.migrate_on_startup {
+        migrate(&config.database_url).await;
+    }
 
-    let pool = db::create_pool(&config.database_url);
+    let pools = db::create_pools(
+        &config.application_database_url,
+        &config.database_replica_urls,
+        &config.database_pool,
+    )
+    .expect("Invalid APPLICATION_DATABASE_URL or DATABASE_REPLICA_URLS");
+    let privileged = db::PrivilegedPool(
+        db::create_pool_with(&config.database_url, &config.database_pool)
+            .expect("Invalid DATABASE_URL"),
+    );
+    let user_changes = notifications::spawn_listener(config.database_url.clone());
+    let flags = feature_flags::FlagCache::spawn(
+        pools.write().clone(),
+        notifications::spawn_feature_flags_listener(config.database_url.clone()).subscribe(),
+    );
+    let mailer = match &config.mailer {
+        config::MailerConfig::Smtp(url) => {
+            email::Mailer::smtp(url, &config.email_from).expect("Invalid SMTP_URL")
+        }
+        config::MailerConfig::Memory => email::Mailer::memory(&config.email_from),
+    };
+    let storage = match &config.storage_url {
+        Some(url) => storage::from_url(url).expect("Invalid STORAGE_URL"),
+        None => std::sync::Arc::new(storage::LocalStorage::new("uploads")),
+    };
+    // Jobs are for whichever team queued them, so the worker can't have a
+    // tenant of its own.
+    worker::spawn(
+        privileged.0.clone(),
+        mailer.clone(),
+        config.worker_concurrency,
+    );
 
-    // build our application with a route
-    let app = Router::new()
-        .route("/", get(root::loader))
-        .route("/settings", get(settings::loader))
-        .route("/new_user", post(root::new_user_action))
-        .route("/static/*path", get(static_files::static_path))
-        .nest_service("/wasm", ServeDir::new("/workspace/crates/web-csr/dist"))
-        .layer(LiveReloadLayer::new())
-        .layer(Extension(config))
-        .layer(Extension(pool.clone()));
+    let app = web_server::app(
+        config,
+        pools,
+        privileged,
+        user_changes,
+        mailer,
+        storage,
+        flags,
+    )
+    .layer(LiveReloadLayer::new());
 
     // run it
     let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
     println!("listening on {}", addr);
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    axum::serve(listener, app.into_make_service())
+    // The audit log records the peer address, or what our TRUSTED_PROXIES
+    // say it was forwarded for
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    );
+    // Not a graceful shutdown, open event streams would keep us waiting.
+    tokio::select! {
+        result = server.into_future() => result.unwrap(),
+        _ = tokio::signal::ctrl_c() => {}
+    }
+
+    // The last batch of spans and metrics would be lost otherwise.
+    if let Some(telemetry) = telemetry {
+        let _ = tokio::task::spawn_blocking(|| telemetry.shutdown()).await;
+    }
+}
+
+// Every server runs this as it starts, the lock in db::migrations makes
+// the others wait for whichever gets there first.
+async fn migrate(database_url: &str) {
+    let mut client = db::migrations::connect(database_url)
+        .await
+        .expect("Unable to connect to migrate");
+    let applied = db::migrations::up(&mut client, false)
         .await
-        .unwrap();
+        .unwrap_or_else(|e| panic!("{e}"));
+    for migration in applied {
+        println!("Applied migration {}", migration.name);
+    }
 }
@@ -0,0 +1,272 @@
+use std::{
+    any::Any,
+    panic::AssertUnwindSafe,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{Request, State},
+    http::{header::CONTENT_TYPE, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{Html, IntoResponse, Response},
+};
+use futures::FutureExt;
+use opentelemetry::{
+    global,
+    trace::{FutureExt as _, SpanKind, Status, TraceContextExt, Tracer},
+    KeyValue,
+};
+use tower_http::{
+    compression::{predicate::SizeAbove, CompressionLayer, Predicate},
+    request_id::RequestId,
+};
+
+fn request_id(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("x-request-id")
+        .and_then(|id| id.to_str().ok())
+        .map(String::from)
+}
+
+/// A server span for every request, carrying on the caller's trace if the
+/// request came with a `traceparent`. Everything the handler does happens
+/// inside it, database queries included.
+///
+/// It ends when the response starts, so for server sent events it only
+/// covers setting the stream up.
+pub async fn trace(req: Request, next: Next) -> Response {
+    let parent = crate::telemetry::parent(req.headers());
+    let method = req.method().to_string();
+
+    let tracer = global::tracer("web-server");
+    let mut attributes = vec![
+        KeyValue::new("http.request.method", method.clone()),
+        KeyValue::new("url.path", req.uri().path().to_string()),
+    ];
+    if let Some(request_id) = request_id(req.headers()) {
+        attributes.push(KeyValue::new("http.request.id", request_id));
+    }
+    // The route isn't known until the router has run, and paths have ids
+    // in them, so the method is all the name can be.
+    let span = tracer
+        .span_builder(method.clone())
+        .with_kind(SpanKind::Server)
+        .with_attributes(attributes)
+        .start_with_context(&tracer, &parent);
+    let cx = parent.with_span(span);
+
+    let started = Instant::now();
+    let response = next.run(req).with_context(cx.clone()).await;
+    let status = response.status();
+
+    crate::telemetry::REQUEST_DURATION.record(
+        started.elapsed().as_secs_f64(),
+        &[
+            KeyValue::new("http.request.method", method),
+            KeyValue::new("http.response.status_code", i64::from(status.as_u16())),
+        ],
+    );
+    let span = cx.span();
+    span.set_attribute(KeyValue::new(
+        "http.response.status_code",
+        i64::from(status.as_u16()),
+    ));
+    if status.is_server_error() {
+        span.set_status(Status::error(status.to_string()));
+    }
+    span.end();
+
+    response
+}
+
+/// Give up on a request after `duration` and show the timed out page.
+/// Queries the request started are cancelled too, otherwise Postgres keeps
+/// working on them long after anyone is waiting.
+pub async fn timeout(State(duration): State<Duration>, req: Request, next: Next) -> Response {
+    let request_id = request_id(req.headers());
+    let locale = crate::locale::from_headers(req.headers(), None);
+    let theme = crate::theme::from_headers(req.headers());
+    let path = req.uri().path().to_string();
+
+    let canceller = db::cancel::QueryCanceller::default();
+    let response = canceller.scope(next.run(req));
+    tokio::pin!(response);
+
+    tokio::select! {
+        response = &mut response => response,
+        _ = tokio::time::sleep(duration) => {
+            // Cancel while the request still holds its connections, they go
+            // back to the pool when it's dropped on the way out.
+            canceller.cancel().await;
+
+            eprintln!(
+                "Request {} to {path} timed out after {duration:?}",
+                request_id.as_deref().unwrap_or("-")
+            );
+            let html = web_pages::errors::timed_out(request_id.as_deref(), locale, theme);
+            (StatusCode::SERVICE_UNAVAILABLE, Html(html)).into_response()
+        }
+    }
+}
+
+/// Turn a panic in a handler into the 500 page rather than a dropped
+/// connection.
+pub async fn catch_panic(req: Request, next: Next) -> Response {
+    let request_id = req
+        .extensions()
+        .get::<RequestId>()
+        .and_then(|id| id.header_value().to_str().ok())
+        .map(String::from)
+        .or_else(|| request_id(req.headers()));
+    let locale = crate::locale::from_headers(req.headers(), None);
+    let theme = crate::theme::from_headers(req.headers());
+    let path = req.uri().path().to_string();
+
+    match AssertUnwindSafe(next.run(req)).catch_unwind().await {
+        Ok(response) => response,
+        Err(panic) => {
+            eprintln!(
+                "Request {} to {path} panicked: {}",
+                request_id.as_deref().unwrap_or("-"),
+                panic_message(&panic)
+            );
+            let html = web_pages::errors::server_error(request_id.as_deref(), locale, theme);
+            (StatusCode::INTERNAL_SERVER_ERROR, Html(html)).into_response()
+        }
+    }
+}
+
+fn panic_message(panic: &Box<dyn Any + Send>) -> &str {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message
+    } else {
+        "Box<dyn Any>"
+    }
+}
+
+/// gzip, brotli or zstd, whichever the browser prefers, for pages and
+/// other text over `min_size` bytes. Server sent events are left alone so
+/// they aren't held back in the encoder's buffer, and responses that are
+/// already compressed aren't compressed again.
+pub fn compression(min_size: u16) -> CompressionLayer<impl Predicate> {
+    let compressible = |_: StatusCode, _: _, headers: &HeaderMap, _: &_| {
+        let content_type = headers
+            .get(CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .unwrap_or_default();
+        compressible(content_type)
+    };
+
+    CompressionLayer::new().compress_when(SizeAbove::new(min_size).and(compressible))
+}
+
+fn compressible(content_type: &str) -> bool {
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    match essence.as_str() {
+        "text/event-stream" => false,
+        "application/json" | "application/javascript" | "image/svg+xml" => true,
+        essence => essence.starts_with("text/"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, middleware, routing::get, Extension, Router};
+    use tower::ServiceExt;
+    use tower_http::request_id::{MakeRequestUuid, SetRequestIdLayer};
+
+    use super::*;
+
+    async fn send(app: Router) -> (StatusCode, String) {
+        let request = Request::builder()
+            .uri("/")
+            .header("x-request-id", "test-request")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, String::from_utf8_lossy(&body).into_owned())
+    }
+
+    #[test]
+    fn only_text_is_compressed() {
+        assert!(compressible("text/html; charset=utf-8"));
+        assert!(compressible("text/csv"));
+        assert!(compressible("application/json"));
+        assert!(!compressible("text/event-stream"));
+        assert!(!compressible("image/png"));
+        assert!(!compressible("application/wasm"));
+        assert!(!compressible(""));
+    }
+
+    #[tokio::test]
+    async fn panics_render_the_error_page() {
+        let app = Router::new()
+            .route("/", get(|| async { panic!("boom") as &'static str }))
+            .layer(middleware::from_fn(catch_panic))
+            .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid));
+
+        let (status, body) = send(app).await;
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(body.contains("Something went wrong"));
+        assert!(body.contains("test-request"));
+    }
+
+    #[tokio::test]
+    async fn slow_queries_are_cancelled() {
+        let pool = db::create_pool(&std::env::var("DATABASE_URL").unwrap()).unwrap();
+        let app = Router::new()
+            .route(
+                "/",
+                get(|Extension(pool): Extension<db::Pool>| async move {
+                    let client = pool.get().await.unwrap();
+                    client
+                        .execute("SELECT pg_sleep(30) /* slow_queries_are_cancelled */", &[])
+                        .await
+                        .unwrap();
+                }),
+            )
+            .route_layer(middleware::from_fn_with_state(
+                Duration::from_millis(500),
+                timeout,
+            ))
+            .layer(Extension(pool.clone()));
+
+        let (status, body) = send(app).await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(body.contains("That took too long"));
+
+        // The cancel is sent, but Postgres gets round to it in its own time.
+        let client = pool.get().await.unwrap();
+        let mut running = 1;
+        for _ in 0..20 {
+            running = client
+                .query_one(
+                    "SELECT COUNT(*) FROM pg_stat_activity
+                    WHERE state = 'active'
+                    AND query LIKE '%/* slow_queries_are_cancelled */'",
+                    &[],
+                )
+                .await
+                .unwrap()
+                .get::<_, i64>(0);
+            if running == 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert_eq!(running, 0);
+    }
+}
@@ -0,0 +1,63 @@
+use std::pin::pin;
+
+use futures::StreamExt;
+use serde::{de::DeserializeOwned, Deserialize};
+use tokio::sync::broadcast;
+
+const USERS_CHANGED: &str = "users_changed";
+const FEATURE_FLAGS_CHANGED: &str = "feature_flags_changed";
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Operation {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// Sent by the `notify_users_changed` trigger whenever the users table changes.
+#[derive(Clone, Debug, Deserialize)]
+pub struct UserChanged {
+    pub operation: Operation,
+    pub id: i32,
+}
+
+/// Sent by the `notify_feature_flags_changed` trigger, once per statement
+/// that changes any flag.
+#[derive(Clone, Debug, Deserialize)]
+pub struct FeatureFlagsChanged {
+    pub operation: Operation,
+}
+
+/// Listen for changes to the users table and fan them out to every
+/// subscriber.
+pub fn spawn_listener(database_url: String) -> broadcast::Sender<UserChanged> {
+    spawn(database_url, USERS_CHANGED)
+}
+
+/// Listen for changes to any feature flag, see `feature_flags::FlagCache`.
+pub fn spawn_feature_flags_listener(
+    database_url: String,
+) -> broadcast::Sender<FeatureFlagsChanged> {
+    spawn(database_url, FEATURE_FLAGS_CHANGED)
+}
+
+// Each channel has its own listener, see `db::listen`, and fans out to
+// every subscriber.
+fn spawn<T>(database_url: String, channel: &'static str) -> broadcast::Sender<T>
+where
+    T: DeserializeOwned + Clone + Send + 'static,
+{
+    let (sender, _) = broadcast::channel(128);
+
+    let tx = sender.clone();
+    tokio::spawn(async move {
+        let mut notifications = pin!(db::listen::<T>(&database_url, &[channel]));
+        while let Some(notification) = notifications.next().await {
+            // Nobody listening is fine, e.g. there are no open pages.
+            let _ = tx.send(notification.payload);
+        }
+    });
+
+    sender
+}
@@ -0,0 +1,64 @@
+//! Sending reads to replicas. Handlers that only read take a `ReadPool`
+//! instead of `Extension<db::Pool>`. Once a browser has changed something
+//! it reads from the primary for the rest of its session, so nobody saves
+//! a form and then sees the page from before.
+
+use crate::{cookies, errors::CustomError};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request},
+    http::{header::SET_COOKIE, request::Parts, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+const WROTE_COOKIE: &str = "wrote";
+
+/// A replica, or the primary if this session has written anything.
+pub struct ReadPool(pub db::Pool);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ReadPool
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let pools = parts
+            .extensions
+            .get::<db::Pools>()
+            .ok_or_else(|| CustomError::FaultySetup("No database pools".into()).into_response())?;
+
+        if cookies::get(&parts.headers, WROTE_COOKIE).is_some() {
+            Ok(ReadPool(pools.write().clone()))
+        } else {
+            Ok(ReadPool(pools.read().clone()))
+        }
+    }
+}
+
+/// Remember that the browser changed something, see `ReadPool`. Any
+/// request that commits a change to the primary counts, whatever its
+/// method, like the GET that creates the user on their first login.
+pub async fn remember_writes(req: Request, next: Next) -> Response {
+    let watch = cookies::get(req.headers(), WROTE_COOKIE).is_none()
+        && req
+            .extensions()
+            .get::<db::Pools>()
+            .is_some_and(db::Pools::has_replicas);
+    if !watch {
+        return next.run(req).await;
+    }
+
+    let tracker = db::writes::WriteTracker::default();
+    let mut response = tracker.scope(next.run(req)).await;
+
+    if tracker.wrote() {
+        let cookie = cookies::session(WROTE_COOKIE, "1");
+        if let Ok(cookie) = HeaderValue::from_str(&cookie) {
+            response.headers_mut().append(SET_COOKIE, cookie);
+        }
+    }
+    response
+}
@@ -1,20 +1,62 @@
-use crate::errors::CustomError;
+use std::convert::Infallible;
+
+use crate::{
+    audit::RequestMeta,
+    authorization::{Authorize, CreateUsers, ViewUsers},
+    errors::CustomError,
+    notifications::{Operation, UserChanged},
+    replicas::ReadPool,
+    teams::CurrentTeam,
+};
 use axum::{
-    http::StatusCode,
-    response::{Html, IntoResponse, Redirect, Response},
+    extract::Query,
+    http::{HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Html, IntoResponse, Redirect, Response,
+    },
     Extension,
 };
 use axum_extra::extract::Form;
+use futures::{Stream, StreamExt};
 use serde::Deserialize;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
 use validator::Validate;
-use web_pages::root;
+use web_pages::{i18n::Locale, root};
+
+const LAST_EVENT_ID: &str = "Last-Event-ID";
+
+/// The users page search, also used by the CSV export.
+#[derive(Deserialize, Default)]
+pub struct UserFilter {
+    #[serde(default)]
+    email: String,
+}
 
-pub async fn loader(Extension(pool): Extension<db::Pool>) -> Result<Html<String>, CustomError> {
-    let client = pool.get().await?;
+impl UserFilter {
+    pub fn email(&self) -> Option<String> {
+        Some(self.email.trim())
+            .filter(|email| !email.is_empty())
+            .map(String::from)
+    }
+}
 
-    let users = db::queries::users::get_users().bind(&client).all().await?;
+pub async fn loader(
+    Authorize(team, _): Authorize<ViewUsers>,
+    ReadPool(pool): ReadPool,
+    Query(filter): Query<UserFilter>,
+) -> Result<Html<String>, CustomError> {
+    let mut client = pool.get().await?;
+    let transaction = db::rls::transaction(&mut client, team.tenant()).await?;
+
+    let email = filter.email();
+    let users = db::queries::users::search_team_users()
+        .bind(&transaction, &team.id, &email.as_deref())
+        .all()
+        .await?;
 
-    let html = root::index(users);
+    let html = root::index(users, email, team.locale, team.settings, team.teams);
 
     Ok(Html(html))
 }
@@ -23,12 +65,14 @@ pub async fn loader(Extension(pool): Extension<db::Pool>) -> Result<Html<String>
 #[derive(Deserialize, Validate)]
 pub struct SignUp {
     #[validate(email)] // 👈 add validate annotation
-    email: String,
+    pub email: String,
 }
 
 // 👇 handle form submission
 pub async fn new_user_action(
-    Extension(pool): Extension<db::Pool>,
+    Extension(db::PrivilegedPool(pool)): Extension<db::PrivilegedPool>,
+    Authorize(team, _): Authorize<CreateUsers>,
+    meta: RequestMeta,
     Form(form): Form<SignUp>,
 ) -> Result<Response, CustomError> {
     // 👇 add our error handling
@@ -36,13 +80,195 @@ pub async fn new_user_action(
         return Ok((StatusCode::BAD_REQUEST, "Bad request").into_response());
     }
 
-    let client = pool.get().await?;
+    let mut client = pool.get().await?;
+    let transaction = client.transaction().await?;
+
+    let email = form.email.trim();
+    let user = db::queries::users::create_new_user()
+        .bind(&transaction, &email)
+        .opt()
+        .await?;
+
+    // The new user isn't in the team yet so we can't see them until they
+    // are, which is why we only scope the transaction from here.
+    db::rls::set_tenant(&transaction, team.tenant()).await?;
 
-    let email = form.email;
-    let _ = db::queries::users::create_user()
-        .bind(&client, &email.as_str())
+    // Someone with an account decides for themselves whether to join, and
+    // the response is the same either way so it doesn't give away who has
+    // an account.
+    let Some(user) = user else {
+        invite_existing_user(&transaction, &team, &meta, email).await?;
+        transaction.commit().await?;
+        return Ok(Redirect::to(&format!("/teams/{}", team.id)).into_response());
+    };
+
+    db::queries::teams::add_team_member()
+        .bind(&transaction, &team.id, &user.id, &db::TeamRole::Member)
         .await?;
 
+    db::audit::record(
+        &transaction,
+        db::audit::AuditEvent {
+            team_id: Some(team.id),
+            actor_id: Some(team.settings.user_id),
+            action: "user.created",
+            target_type: "user",
+            target_id: Some(user.id),
+            diff: db::audit::diff(None, Some(&user)),
+            ip_address: meta.ip_address,
+            user_agent: meta.user_agent.as_deref(),
+        },
+    )
+    .await?;
+
+    // Only queue the email if the user really gets created
+    db::jobs::enqueue(
+        &transaction,
+        Some(team.id),
+        &db::jobs::WelcomeEmail {
+            user_id: user.id,
+            email: user.email,
+        },
+    )
+    .await?;
+
+    transaction.commit().await?;
+
     // 303 redirect to users list
-    Ok(Redirect::to("/").into_response())
+    Ok(Redirect::to(&format!("/teams/{}", team.id)).into_response())
+}
+
+async fn invite_existing_user<C: db::GenericClient>(
+    transaction: &C,
+    team: &CurrentTeam,
+    meta: &RequestMeta,
+    email: &str,
+) -> Result<(), CustomError> {
+    let invitation_id = db::queries::teams::create_invitation()
+        .bind(
+            transaction,
+            &team.id,
+            &email,
+            &db::TeamRole::Member,
+            &team.settings.user_id,
+        )
+        .one()
+        .await?;
+
+    db::audit::record(
+        transaction,
+        db::audit::AuditEvent {
+            team_id: Some(team.id),
+            actor_id: Some(team.settings.user_id),
+            action: "invitation.created",
+            target_type: "invitation",
+            target_id: Some(invitation_id),
+            diff: serde_json::json!({
+                "email": { "old": null, "new": email },
+                "role": { "old": null, "new": db::TeamRole::Member },
+            }),
+            ip_address: meta.ip_address,
+            user_agent: meta.user_agent.as_deref(),
+        },
+    )
+    .await?;
+    Ok(())
+}
+
+// Push changes to the users table to every open users page of the team.
+pub async fn events(
+    Extension(pool): Extension<db::Pool>,
+    Extension(changes): Extension<broadcast::Sender<UserChanged>>,
+    Authorize(team, _): Authorize<ViewUsers>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, CustomError> {
+    // Subscribe before catching up so we can't miss anything in between.
+    let receiver = changes.subscribe();
+
+    let mut client = pool.get().await?;
+    let tenant = team.tenant();
+    let team_id = team.id;
+    let timezone = team.settings.timezone;
+    let locale = team.locale;
+
+    // When the browser reconnects it tells us the last user it saw,
+    // so send whatever was added while it was away.
+    let last_event_id = headers
+        .get(LAST_EVENT_ID)
+        .and_then(|id| id.to_str().ok())
+        .and_then(|id| id.parse::<i32>().ok());
+    let missed = match last_event_id {
+        Some(id) => {
+            let transaction = db::rls::transaction(&mut client, tenant).await?;
+            db::queries::users::get_team_users_after()
+                .bind(&transaction, &team_id, &id)
+                .all()
+                .await?
+        }
+        None => vec![],
+    };
+    drop(client);
+
+    let catch_up: Vec<Event> = missed
+        .into_iter()
+        .map(|user| inserted_event(user, timezone.clone(), locale))
+        .collect();
+
+    let live = BroadcastStream::new(receiver)
+        // If we lagged behind the browser will pick the rows up on the next load
+        .filter_map(|change| async move { change.ok() })
+        .then(move |change| {
+            let pool = pool.clone();
+            let timezone = timezone.clone();
+            async move {
+                match change_event(&pool, tenant, change, timezone, locale).await {
+                    Ok(event) => event,
+                    Err(e) => {
+                        eprintln!("Unable to render user change: {e}");
+                        None
+                    }
+                }
+            }
+        })
+        .filter_map(|event| async move { event });
+
+    let stream = futures::stream::iter(catch_up).chain(live).map(Ok);
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+async fn change_event(
+    pool: &db::Pool,
+    tenant: db::rls::Tenant,
+    change: UserChanged,
+    timezone: String,
+    locale: Locale,
+) -> Result<Option<Event>, CustomError> {
+    if change.operation == Operation::Delete {
+        let html = root::user_row_removed(change.id);
+        return Ok(Some(Event::default().event("users").data(html)));
+    }
+
+    let mut client = pool.get().await?;
+    let transaction = db::rls::transaction(&mut client, tenant).await?;
+    // Users outside the team don't exist as far as this page is concerned.
+    let user = db::queries::users::get_team_user()
+        .bind(&transaction, &tenant.team_id, &change.id)
+        .opt()
+        .await?;
+
+    Ok(user.map(|user| match change.operation {
+        Operation::Update => Event::default()
+            .event("users")
+            .data(root::user_row_updated(user, timezone, locale)),
+        _ => inserted_event(user, timezone, locale),
+    }))
+}
+
+// The id lets the browser resume from the last user it saw.
+fn inserted_event(user: db::User, timezone: String, locale: Locale) -> Event {
+    Event::default()
+        .event("users")
+        .id(user.id.to_string())
+        .data(root::user_row(user, timezone, locale))
 }
@@ -1,20 +1,226 @@
-use crate::errors::CustomError;
+use crate::{api_keys, audit::RequestMeta, cookies, errors::CustomError, teams::CurrentTeam};
 use axum::{
-    http::StatusCode,
-    response::{Html, IntoResponse, Redirect, Response},
+    http::{
+        header::{CACHE_CONTROL, SET_COOKIE},
+        HeaderMap, StatusCode,
+    },
+    response::{AppendHeaders, Html, IntoResponse, Redirect, Response},
     Extension,
 };
 use axum_extra::extract::Form;
+use db::{
+    encryption::{self, Ciphertext, Keyring},
+    Theme,
+};
 use serde::Deserialize;
-use validator::Validate;
-use web_pages::settings;
+use validator::{Validate, ValidationError};
+use web_pages::{
+    i18n::Locale,
+    settings::{self, ApiKeys},
+};
+
+// Display names are personal data, so they're stored encrypted and only
+// decrypt for the user they belong to.
+fn display_name_context(user_id: i32) -> String {
+    encryption::context("user_settings", "encrypted_display_name", user_id)
+}
+
+pub fn encrypt_display_name(
+    keyring: &Keyring,
+    user_id: i32,
+    name: Option<&str>,
+) -> Option<Ciphertext> {
+    name.map(|name| keyring.encrypt_str(name, &display_name_context(user_id)))
+}
+
+// The audit log is stored in the clear, so it only says the name changed.
+pub fn redact_display_name(mut diff: serde_json::Value) -> serde_json::Value {
+    if let Some(change) = diff.get_mut("display_name") {
+        *change = serde_json::json!({ "old": "[encrypted]", "new": "[encrypted]" });
+    }
+    diff
+}
+
+/// Settings as they were loaded, with the display name decrypted into
+/// `display_name`.
+pub fn decrypt_display_name(
+    keyring: &Keyring,
+    mut settings: db::UserSettings,
+) -> Result<db::UserSettings, CustomError> {
+    if let Some(encrypted) = settings.encrypted_display_name.take() {
+        let context = display_name_context(settings.user_id);
+        settings.display_name = Some(keyring.decrypt_string(&encrypted.into(), &context)?);
+    }
+    Ok(settings)
+}
+
+pub async fn loader(
+    Extension(pool): Extension<db::Pool>,
+    Extension(keyring): Extension<Keyring>,
+    team: CurrentTeam,
+    headers: HeaderMap,
+) -> Result<Response, CustomError> {
+    let mut api_keys = api_keys(&pool, &team).await?;
+    if cookies::get(&headers, api_keys::FLASH_COOKIE).is_none() {
+        let html = settings::index(team.settings, vec![], api_keys, team.locale, team.teams);
+        return Ok(Html(html).into_response());
+    }
+
+    // A key was just created, show it this once and make sure nothing
+    // keeps a copy of the page.
+    api_keys.created = api_keys::take_flash(&headers, &keyring, &api_keys.keys);
+    let html = settings::index(team.settings, vec![], api_keys, team.locale, team.teams);
+    Ok((
+        AppendHeaders([
+            (SET_COOKIE, cookies::set(api_keys::FLASH_COOKIE, None)),
+            (CACHE_CONTROL, "no-store".to_string()),
+        ]),
+        Html(html),
+    )
+        .into_response())
+}
+
+// The signed in user's keys for this team.
+async fn api_keys(pool: &db::Pool, team: &CurrentTeam) -> Result<ApiKeys, CustomError> {
+    let mut client = pool.get().await?;
+    let transaction = db::rls::transaction(&mut client, team.tenant()).await?;
+    let keys = db::queries::api_keys::get_api_keys()
+        .bind(&transaction, &team.id, &team.settings.user_id)
+        .all()
+        .await?;
+
+    Ok(ApiKeys {
+        keys,
+        created: None,
+    })
+}
+
+#[derive(Deserialize, Validate)]
+pub struct UserSettingsForm {
+    // Codes are looked up as settings-error-{code} in the locale files
+    #[validate(length(max = 100, code = "display_name"))]
+    display_name: String,
+    #[validate(custom(function = "validate_timezone"))]
+    timezone: String,
+    #[validate(custom(function = "validate_theme"))]
+    theme: String,
+    // Empty for whatever the browser asks for
+    #[serde(default)]
+    #[validate(custom(function = "validate_locale"))]
+    locale: String,
+    // Unchecked checkboxes aren't sent by the browser
+    notify_product_updates: Option<String>,
+    notify_security_alerts: Option<String>,
+}
+
+fn validate_timezone(timezone: &str) -> Result<(), ValidationError> {
+    if web_pages::datetime::timezones().any(|tz| tz == timezone) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("timezone"))
+    }
+}
+
+fn validate_theme(theme: &str) -> Result<(), ValidationError> {
+    match crate::theme::parse(theme) {
+        Some(_) => Ok(()),
+        None => Err(ValidationError::new("theme")),
+    }
+}
+
+fn validate_locale(locale: &str) -> Result<(), ValidationError> {
+    if locale.is_empty() || Locale::parse(locale).is_some() {
+        Ok(())
+    } else {
+        Err(ValidationError::new("locale"))
+    }
+}
+
+pub async fn action(
+    Extension(pool): Extension<db::Pool>,
+    Extension(keyring): Extension<Keyring>,
+    team: CurrentTeam,
+    meta: RequestMeta,
+    Form(form): Form<UserSettingsForm>,
+) -> Result<Response, CustomError> {
+    let tenant = team.tenant();
+
+    let display_name = Some(form.display_name.trim()).filter(|name| !name.is_empty());
+    let notify_product_updates = form.notify_product_updates.is_some();
+    let notify_security_alerts = form.notify_security_alerts.is_some();
+
+    if let Err(errors) = form.validate() {
+        // Show the user what they typed along with what was wrong with it
+        let errors = errors
+            .field_errors()
+            .values()
+            .flat_map(|errors| errors.iter())
+            .map(|error| team.locale.t(&format!("settings-error-{}", error.code)))
+            .collect();
+        let api_keys = api_keys(&pool, &team).await?;
+        let settings = db::UserSettings {
+            display_name: display_name.map(String::from),
+            timezone: form.timezone,
+            notify_product_updates,
+            notify_security_alerts,
+            locale: Some(form.locale).filter(|locale| !locale.is_empty()),
+            ..team.settings
+        };
+        let html = settings::index(settings, errors, api_keys, team.locale, team.teams);
+        return Ok((StatusCode::UNPROCESSABLE_ENTITY, Html(html)).into_response());
+    }
+
+    let theme = crate::theme::parse(&form.theme).unwrap_or(Theme::System);
+    let locale = Locale::parse(&form.locale);
+    let settings = team.settings;
+
+    let mut client = pool.get().await?;
+
+    let transaction = db::rls::transaction(&mut client, tenant).await?;
 
-pub async fn loader(Extension(pool): Extension<db::Pool>) -> Result<Html<String>, CustomError> {
-    let client = pool.get().await?;
+    db::queries::user_settings::upsert_user_settings()
+        .bind(
+            &transaction,
+            &settings.user_id,
+            &encrypt_display_name(&keyring, settings.user_id, display_name),
+            &form.timezone.as_str(),
+            &theme,
+            &notify_product_updates,
+            &notify_security_alerts,
+            &locale.map(Locale::code),
+        )
+        .await?;
 
-    let users = db::queries::users::get_users().bind(&client).all().await?;
+    let updated = db::queries::user_settings::get_user_settings()
+        .bind(&transaction, &settings.user_id)
+        .one()
+        .await?;
+    let updated = decrypt_display_name(&keyring, updated)?;
+    db::audit::record(
+        &transaction,
+        db::audit::AuditEvent {
+            team_id: Some(team.id),
+            actor_id: Some(settings.user_id),
+            action: "user_settings.updated",
+            target_type: "user",
+            target_id: Some(settings.user_id),
+            diff: redact_display_name(db::audit::diff(Some(&settings), Some(&updated))),
+            ip_address: meta.ip_address,
+            user_agent: meta.user_agent.as_deref(),
+        },
+    )
+    .await?;
 
-    let html = settings::index(users);
+    transaction.commit().await?;
 
-    Ok(Html(html))
+    // 303 redirect back to the settings page, the cookies carry the
+    // language and theme to pages that don't know who the user is.
+    Ok((
+        AppendHeaders([
+            (SET_COOKIE, crate::locale::set_cookie(locale)),
+            (SET_COOKIE, crate::theme::set_cookie(theme)),
+        ]),
+        Redirect::to(&format!("/teams/{}/settings", team.id)),
+    )
+        .into_response())
 }
@@ -0,0 +1,364 @@
+use crate::{
+    audit::RequestMeta,
+    authentication::Jwt,
+    authorization::{Authorize, ManageTeam, ViewTeam},
+    errors::CustomError,
+};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Path},
+    http::{request::Parts, StatusCode},
+    response::{Html, IntoResponse, Redirect, Response},
+    Extension,
+};
+use axum_extra::extract::Form;
+use db::TeamRole;
+use serde::Deserialize;
+use validator::Validate;
+use web_pages::{
+    i18n::Locale,
+    teams::{self, Teams},
+};
+
+#[derive(Deserialize)]
+struct TeamPath {
+    team_id: i32,
+}
+
+/// The team named in the URL. This only resolves if the signed in user is
+/// a member, so handlers can scope every query with `team.id` and never
+/// see another tenant's data.
+pub struct CurrentTeam {
+    pub id: i32,
+    pub teams: Teams,
+    pub settings: db::UserSettings,
+    /// What to render pages in, see `locale::from_headers`.
+    pub locale: Locale,
+}
+
+impl CurrentTeam {
+    /// For `db::rls`, so the database enforces the same scoping.
+    pub fn tenant(&self) -> db::rls::Tenant {
+        db::rls::Tenant {
+            user_id: self.settings.user_id,
+            team_id: self.id,
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for CurrentTeam
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let jwt = Jwt::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        let Path(TeamPath { team_id }) = Path::<TeamPath>::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        // We don't know the tenant until we've found the user and checked
+        // they're in the team.
+        let db::PrivilegedPool(pool) = parts
+            .extensions
+            .get::<db::PrivilegedPool>()
+            .cloned()
+            .ok_or_else(|| {
+                CustomError::FaultySetup("No privileged database pool".into()).into_response()
+            })?;
+
+        let mut client = pool
+            .get()
+            .await
+            .map_err(|e| CustomError::from(e).into_response())?;
+        let keyring = parts
+            .extensions
+            .get::<db::encryption::Keyring>()
+            .ok_or_else(|| CustomError::FaultySetup("No keyring".into()).into_response())?;
+        let settings = jwt
+            .settings(&mut client)
+            .await
+            .map_err(|e| CustomError::from(e).into_response())?;
+        let settings = crate::settings::decrypt_display_name(keyring, settings)
+            .map_err(IntoResponse::into_response)?;
+        let all = db::queries::teams::get_teams()
+            .bind(&client, &settings.user_id)
+            .all()
+            .await
+            .map_err(|e| CustomError::from(e).into_response())?;
+
+        // Teams you're not in look the same as teams that don't exist.
+        let current = all
+            .iter()
+            .find(|team| team.id == team_id)
+            .cloned()
+            .ok_or_else(|| (StatusCode::NOT_FOUND, "Team not found").into_response())?;
+
+        let permissions = db::queries::teams::get_permissions()
+            .bind(&client, &team_id, &settings.user_id)
+            .all()
+            .await
+            .map_err(|e| CustomError::from(e).into_response())?;
+
+        let locale = crate::locale::from_headers(&parts.headers, settings.locale.as_deref());
+        let site_admin = parts
+            .extensions
+            .get::<crate::config::Config>()
+            .is_some_and(|config| config.is_admin(&settings.email));
+
+        let team = CurrentTeam {
+            id: team_id,
+            locale,
+            teams: Teams {
+                current,
+                all,
+                permissions,
+                site_admin,
+            },
+            settings,
+        };
+        // For extractors that only need to know who and where, like
+        // `FeatureFlags`.
+        parts.extensions.insert(team.tenant());
+
+        Ok(team)
+    }
+}
+
+// Send people to their first team, creating one if this is their first visit.
+pub async fn redirect_to_team(
+    Extension(db::PrivilegedPool(pool)): Extension<db::PrivilegedPool>,
+    jwt: Jwt,
+) -> Result<Redirect, CustomError> {
+    let mut client = pool.get().await?;
+
+    let settings = jwt.settings(&mut client).await?;
+    let teams = db::queries::teams::get_teams()
+        .bind(&client, &settings.user_id)
+        .all()
+        .await?;
+
+    let team_id = match teams.first() {
+        Some(team) => team.id,
+        None => {
+            let transaction = client.transaction().await?;
+            let name = format!("{}'s team", settings.email);
+            let team_id = create_team(&transaction, &name, settings.user_id).await?;
+            transaction.commit().await?;
+            team_id
+        }
+    };
+
+    Ok(Redirect::to(&format!("/teams/{team_id}")))
+}
+
+async fn create_team<C: db::GenericClient>(
+    client: &C,
+    name: &str,
+    user_id: i32,
+) -> Result<i32, db::TokioPostgresError> {
+    let team_id = db::queries::teams::create_team()
+        .bind(client, &name, &user_id)
+        .one()
+        .await?;
+    db::queries::teams::add_team_member()
+        .bind(client, &team_id, &user_id, &TeamRole::Administrator)
+        .await?;
+    Ok(team_id)
+}
+
+pub async fn loader(
+    Extension(pool): Extension<db::Pool>,
+    Extension(db::PrivilegedPool(privileged)): Extension<db::PrivilegedPool>,
+    Authorize(team, _): Authorize<ViewTeam>,
+) -> Result<Html<String>, CustomError> {
+    // These are for other teams, which row level security won't show us,
+    // so they're read the way `accept_action` finds them.
+    let my_invitations = db::queries::teams::get_invitations_for_email()
+        .bind(&privileged.get().await?, &team.settings.email.as_str())
+        .all()
+        .await?;
+
+    let mut client = pool.get().await?;
+    let transaction = db::rls::transaction(&mut client, team.tenant()).await?;
+    let members = db::queries::teams::get_team_members()
+        .bind(&transaction, &team.id)
+        .all()
+        .await?;
+    let invitations = db::queries::teams::get_invitations()
+        .bind(&transaction, &team.id)
+        .all()
+        .await?;
+
+    let html = teams::index(
+        members,
+        invitations,
+        my_invitations,
+        team.locale,
+        team.settings,
+        team.teams,
+    );
+
+    Ok(Html(html))
+}
+
+#[derive(Deserialize, Validate)]
+pub struct NewTeam {
+    #[validate(length(min = 1, max = 100))]
+    name: String,
+}
+
+// The team doesn't exist yet, so there's no tenant to scope this to.
+pub async fn new_team_action(
+    Extension(db::PrivilegedPool(pool)): Extension<db::PrivilegedPool>,
+    jwt: Jwt,
+    meta: RequestMeta,
+    Form(form): Form<NewTeam>,
+) -> Result<Response, CustomError> {
+    if form.validate().is_err() {
+        return Ok((StatusCode::BAD_REQUEST, "Bad request").into_response());
+    }
+
+    let mut client = pool.get().await?;
+    let settings = jwt.settings(&mut client).await?;
+    let transaction = client.transaction().await?;
+
+    let name = form.name.trim();
+    let team_id = create_team(&transaction, name, settings.user_id).await?;
+
+    db::audit::record(
+        &transaction,
+        db::audit::AuditEvent {
+            team_id: Some(team_id),
+            actor_id: Some(settings.user_id),
+            action: "team.created",
+            target_type: "team",
+            target_id: Some(team_id),
+            diff: serde_json::json!({ "name": { "old": null, "new": name } }),
+            ip_address: meta.ip_address,
+            user_agent: meta.user_agent.as_deref(),
+        },
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(Redirect::to(&format!("/teams/{team_id}/team")).into_response())
+}
+
+#[derive(Deserialize, Validate)]
+pub struct Invite {
+    #[validate(email)]
+    email: String,
+    role: String,
+}
+
+fn parse_role(role: &str) -> Option<TeamRole> {
+    match role {
+        "Administrator" => Some(TeamRole::Administrator),
+        "Member" => Some(TeamRole::Member),
+        "ReadOnly" => Some(TeamRole::ReadOnly),
+        _ => None,
+    }
+}
+
+pub async fn invite_action(
+    Extension(pool): Extension<db::Pool>,
+    Authorize(team, _): Authorize<ManageTeam>,
+    meta: RequestMeta,
+    Form(form): Form<Invite>,
+) -> Result<Response, CustomError> {
+    let role = match (form.validate(), parse_role(&form.role)) {
+        (Ok(()), Some(role)) => role,
+        _ => return Ok((StatusCode::BAD_REQUEST, "Bad request").into_response()),
+    };
+
+    let mut client = pool.get().await?;
+    let transaction = db::rls::transaction(&mut client, team.tenant()).await?;
+
+    let email = form.email.trim();
+    let invitation_id = db::queries::teams::create_invitation()
+        .bind(
+            &transaction,
+            &team.id,
+            &email,
+            &role,
+            &team.settings.user_id,
+        )
+        .one()
+        .await?;
+
+    db::audit::record(
+        &transaction,
+        db::audit::AuditEvent {
+            team_id: Some(team.id),
+            actor_id: Some(team.settings.user_id),
+            action: "invitation.created",
+            target_type: "invitation",
+            target_id: Some(invitation_id),
+            diff: serde_json::json!({
+                "email": { "old": null, "new": email },
+                "role": { "old": null, "new": role },
+            }),
+            ip_address: meta.ip_address,
+            user_agent: meta.user_agent.as_deref(),
+        },
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(Redirect::to(&format!("/teams/{}/team", team.id)).into_response())
+}
+
+// Invitations aren't scoped to a team in the URL because you're not in
+// the team until you accept, nor can the database scope them.
+pub async fn accept_action(
+    Extension(db::PrivilegedPool(pool)): Extension<db::PrivilegedPool>,
+    jwt: Jwt,
+    meta: RequestMeta,
+    Path(id): Path<i32>,
+) -> Result<Response, CustomError> {
+    let mut client = pool.get().await?;
+    let settings = jwt.settings(&mut client).await?;
+    let transaction = client.transaction().await?;
+
+    let accepted = db::queries::teams::accept_invitation()
+        .bind(&transaction, &id, &settings.email.as_str())
+        .opt()
+        .await?;
+    let Some(accepted) = accepted else {
+        return Ok((StatusCode::NOT_FOUND, "Invitation not found").into_response());
+    };
+
+    db::queries::teams::add_team_member()
+        .bind(
+            &transaction,
+            &accepted.team_id,
+            &settings.user_id,
+            &accepted.role,
+        )
+        .await?;
+
+    db::audit::record(
+        &transaction,
+        db::audit::AuditEvent {
+            team_id: Some(accepted.team_id),
+            actor_id: Some(settings.user_id),
+            action: "invitation.accepted",
+            target_type: "invitation",
+            target_id: Some(id),
+            ip_address: meta.ip_address,
+            user_agent: meta.user_agent.as_deref(),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(Redirect::to(&format!("/teams/{}", accepted.team_id)).into_response())
+}
@@ -0,0 +1,111 @@
+//! Traces and metrics over OTLP. `init` installs the exporters globally,
+//! then `middleware::trace` and the spans in `db` find them from there.
+
+use std::sync::LazyLock;
+
+use axum::http::HeaderMap;
+use opentelemetry::{global, metrics::Histogram, propagation::Extractor, Context, KeyValue};
+use opentelemetry_otlp::{MetricExporter, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{
+    metrics::{PeriodicReader, SdkMeterProvider},
+    propagation::TraceContextPropagator,
+    runtime,
+    trace::{Sampler, TracerProvider},
+    Resource,
+};
+
+use crate::config::Telemetry;
+
+// Made once, so `init` has to run before the first request.
+pub(crate) static REQUEST_DURATION: LazyLock<Histogram<f64>> = LazyLock::new(|| {
+    global::meter("web-server")
+        .f64_histogram("http.server.request.duration")
+        .with_unit("s")
+        .with_description("How long requests took until the response started")
+        .build()
+});
+
+/// The installed exporters. Spans and metrics are sent in batches, so shut
+/// these down on the way out or the last batch is lost.
+pub struct Providers {
+    tracer: TracerProvider,
+    meter: SdkMeterProvider,
+}
+
+impl Providers {
+    /// Send whatever is waiting now. This blocks, so call it from
+    /// `spawn_blocking` in async code.
+    pub fn flush(&self) {
+        for result in self.tracer.force_flush() {
+            if let Err(e) = result {
+                eprintln!("Unable to export spans: {e}");
+            }
+        }
+        if let Err(e) = self.meter.force_flush() {
+            eprintln!("Unable to export metrics: {e}");
+        }
+    }
+
+    pub fn shutdown(self) {
+        if let Err(e) = self.tracer.shutdown() {
+            eprintln!("Unable to export spans: {e}");
+        }
+        if let Err(e) = self.meter.shutdown() {
+            eprintln!("Unable to export metrics: {e}");
+        }
+    }
+}
+
+/// Export to the configured endpoint, if there is one. `traceparent` is
+/// passed on either way, so a trace that goes through us isn't broken up.
+pub fn init(config: &Telemetry) -> Option<Providers> {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let endpoint = config.otlp_endpoint.as_deref()?.trim_end_matches('/');
+    let resource = Resource::new([KeyValue::new("service.name", config.service_name.clone())]);
+
+    let spans = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(format!("{endpoint}/v1/traces"))
+        .build()
+        .expect("Invalid OTEL_EXPORTER_OTLP_ENDPOINT");
+    let tracer = TracerProvider::builder()
+        .with_batch_exporter(spans, runtime::Tokio)
+        .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+            config.sample_ratio,
+        ))))
+        .with_resource(resource.clone())
+        .build();
+
+    let metrics = MetricExporter::builder()
+        .with_http()
+        .with_endpoint(format!("{endpoint}/v1/metrics"))
+        .build()
+        .expect("Invalid OTEL_EXPORTER_OTLP_ENDPOINT");
+    let meter = SdkMeterProvider::builder()
+        .with_reader(PeriodicReader::builder(metrics, runtime::Tokio).build())
+        .with_resource(resource)
+        .build();
+
+    global::set_tracer_provider(tracer.clone());
+    global::set_meter_provider(meter.clone());
+
+    Some(Providers { tracer, meter })
+}
+
+/// The trace a request is part of, going by its `traceparent` header.
+pub(crate) fn parent(headers: &HeaderMap) -> Context {
+    global::get_text_map_propagator(|propagator| propagator.extract(&Headers(headers)))
+}
+
+struct Headers<'a>(&'a HeaderMap);
+
+impl Extractor for Headers<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|name| name.as_str()).collect()
+    }
+}
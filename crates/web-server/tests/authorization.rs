@@ -0,0 +1,250 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header::CONTENT_TYPE, Method, Request, StatusCode},
+};
+use common::{signed_in, TestApp};
+use db::{Permission, TeamRole};
+use sha2::Digest;
+use tower::ServiceExt;
+
+const ADMIN: usize = 0;
+const MEMBER: usize = 1;
+const READ_ONLY: usize = 2;
+const ROLES: [TeamRole; 3] = [
+    TeamRole::Administrator,
+    TeamRole::Member,
+    TeamRole::ReadOnly,
+];
+const EMAILS: [&str; 3] = ["admin@rbac.test", "member@rbac.test", "read-only@rbac.test"];
+
+// Every team route and which of [Administrator, Member, ReadOnly] may use it.
+const MATRIX: &[(&str, &str, &str, [bool; 3])] = &[
+    ("GET", "", "", [true, true, true]),
+    ("GET", "/users/events", "", [true, true, true]),
+    (
+        "POST",
+        "/new_user",
+        "email=new-{role}@rbac.test",
+        [true, true, false],
+    ),
+    ("GET", "/users/export.csv", "", [true, true, true]),
+    (
+        "POST",
+        "/users/import",
+        "emails=imported-{role}@rbac.test",
+        [true, true, false],
+    ),
+    ("GET", "/team", "", [true, true, true]),
+    (
+        "POST",
+        "/invitations",
+        "email=invited-{role}@rbac.test&role=Member",
+        [true, false, false],
+    ),
+    ("GET", "/settings", "", [true, true, true]),
+    (
+        "POST",
+        "/api_keys",
+        "name=ci&scopes=ViewUsers",
+        [true, true, true],
+    ),
+    (
+        "POST",
+        "/settings",
+        "display_name=&timezone=UTC&theme=System",
+        [true, true, true],
+    ),
+    ("POST", "/theme", "theme=Dark", [true, true, true]),
+    ("GET", "/jobs", "", [true, true, true]),
+    ("POST", "/jobs/{job}/retry", "", [true, false, false]),
+    ("GET", "/audit", "", [true, false, false]),
+    ("GET", "/files", "", [true, true, true]),
+    // Flags are site wide, so not even a team's administrators.
+    ("GET", "/feature_flags", "", [false, false, false]),
+    (
+        "POST",
+        "/feature_flags",
+        "name=rbac-{role}",
+        [false, false, false],
+    ),
+    (
+        "POST",
+        "/feature_flags/0",
+        "rollout_percentage=100",
+        [false, false, false],
+    ),
+];
+
+#[tokio::test]
+async fn each_role_can_only_use_its_routes() {
+    let app = TestApp::new().await;
+
+    // One team with a user in each role.
+    let team_id = app.sign_in(EMAILS[ADMIN]).await;
+    let client = app.pool.get().await.unwrap();
+    for role in [MEMBER, READ_ONLY] {
+        app.sign_in(EMAILS[role]).await;
+        let user = db::queries::users::get_user_by_email()
+            .bind(&client, &EMAILS[role])
+            .one()
+            .await
+            .unwrap();
+        db::queries::teams::add_team_member()
+            .bind(&client, &team_id, &user.id, &ROLES[role])
+            .await
+            .unwrap();
+    }
+
+    // Something for an administrator to retry.
+    let job: i32 = client
+        .query_one(
+            "INSERT INTO jobs (team_id, kind, payload, status)
+            VALUES ($1, 'welcome_email', '{}', 'Dead')
+            RETURNING id",
+            &[&team_id],
+        )
+        .await
+        .unwrap()
+        .get(0);
+
+    for (method, path, body, allowed) in MATRIX {
+        let path = path.replace("{job}", &job.to_string());
+        for role in [ADMIN, MEMBER, READ_ONLY] {
+            let request = signed_in(EMAILS[role])
+                .method(Method::from_bytes(method.as_bytes()).unwrap())
+                .uri(format!("/teams/{team_id}{path}"))
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::from(body.replace("{role}", &role.to_string())))
+                .unwrap();
+
+            // Not `app.send`, the events never finish.
+            let status = app.router.clone().oneshot(request).await.unwrap().status();
+
+            if allowed[role] {
+                assert!(
+                    status.is_success() || status.is_redirection(),
+                    "{method} {path} as {:?} should be allowed, got {status}",
+                    ROLES[role]
+                );
+            } else {
+                assert_eq!(
+                    status,
+                    StatusCode::FORBIDDEN,
+                    "{method} {path} as {:?} should be forbidden",
+                    ROLES[role]
+                );
+            }
+        }
+    }
+
+    // Someone outside the team can't even see it exists.
+    let page = app
+        .get("outsider@rbac.test", &format!("/teams/{team_id}"))
+        .await;
+    assert_eq!(page.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn api_keys_only_get_their_scopes() {
+    let app = TestApp::new().await;
+    let team_id = app.sign_in("keys@api.test").await;
+    let client = app.pool.get().await.unwrap();
+
+    let user = db::queries::users::get_user_by_email()
+        .bind(&client, &"keys@api.test")
+        .one()
+        .await
+        .unwrap();
+    client
+        .execute(
+            "UPDATE team_memberships SET role = 'ReadOnly' WHERE team_id = $1",
+            &[&team_id],
+        )
+        .await
+        .unwrap();
+
+    // ReadOnly can't create users, so that scope does nothing.
+    let keys = [
+        ("nails_users_key", vec![Permission::ViewUsers]),
+        ("nails_create_key", vec![Permission::CreateUsers]),
+    ];
+    for (key, scopes) in &keys {
+        db::queries::api_keys::create_api_key()
+            .bind(
+                &client,
+                &team_id,
+                &user.id,
+                key,
+                &&key[..8],
+                &sha2::Sha256::digest(key.as_bytes()).to_vec(),
+                scopes,
+            )
+            .one()
+            .await
+            .unwrap();
+    }
+
+    let request = |key: &str| {
+        Request::builder()
+            .uri("/api/users")
+            .header("Authorization", format!("Bearer {key}"))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = app.send(request(keys[0].0)).await;
+    assert_eq!(response.status, StatusCode::OK);
+    let response = app.send(request(keys[1].0)).await;
+    assert_eq!(response.status, StatusCode::FORBIDDEN);
+    let response = app.send(request("nails_guess")).await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+
+    let used = client
+        .query_one(
+            "SELECT COUNT(*) FROM audit_events WHERE team_id = $1 AND action = 'api_key.used'",
+            &[&team_id],
+        )
+        .await
+        .unwrap();
+    assert_eq!(used.get::<_, i64>(0), 2);
+}
+
+#[tokio::test]
+async fn requests_see_nothing_without_a_tenant() {
+    let app = TestApp::new().await;
+    let team_id = app.sign_in("ian@test.com").await;
+    let user = db::queries::users::get_user_by_email()
+        .bind(&app.pool.get().await.unwrap(), &"ian@test.com")
+        .one()
+        .await
+        .unwrap();
+
+    let mut client = app.request_pool.get().await.unwrap();
+    let count = |table: &str| format!("SELECT COUNT(*) FROM {table}");
+    let tables = ["users", "teams", "team_memberships"];
+
+    // No SET LOCAL ROLE, it's who requests log in as. Nor is there
+    // another role to reset to or take on.
+    client.batch_execute("RESET ROLE").await.unwrap();
+    for table in tables {
+        let rows: i64 = client.query_one(&count(table), &[]).await.unwrap().get(0);
+        assert_eq!(rows, 0, "{table} is visible without a tenant");
+    }
+    assert!(client.batch_execute("SET ROLE postgres").await.is_err());
+
+    let tenant = db::rls::Tenant {
+        user_id: user.id,
+        team_id,
+    };
+    let transaction = db::rls::transaction(&mut client, tenant).await.unwrap();
+    for table in tables {
+        let rows: i64 = transaction
+            .query_one(&count(table), &[])
+            .await
+            .unwrap()
+            .get(0);
+        assert_eq!(rows, 1, "{table} isn't visible to the tenant");
+    }
+}
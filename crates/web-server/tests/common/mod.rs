@@ -0,0 +1,393 @@
+//! Runs the app in process against a database of its own.
+//!
+//! Migrations are applied once to a template database, named after a hash
+//! of the migrations so it's rebuilt whenever they change. Each test
+//! then gets a copy of the template, which Postgres makes almost instantly,
+//! and the copy is dropped again when the test is done.
+
+// Each test file only uses some of the helpers.
+#![allow(dead_code)]
+
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, Request, StatusCode},
+    Router,
+};
+use scraper::{Html, Selector};
+use tokio::sync::broadcast;
+use tokio_postgres::NoTls;
+use tower::ServiceExt;
+use web_server::config::{Config, Limits, MailerConfig};
+
+// Any number will do, as long as nothing else locks it.
+const TEMPLATE_LOCK: i64 = 7_297_001;
+
+/// Signs in as a site admin, see `Config::admin_emails`.
+pub const ADMIN_EMAIL: &str = "admin@nails.test";
+
+static DATABASES: AtomicUsize = AtomicUsize::new(0);
+
+pub struct TestApp {
+    pub router: Router,
+    /// Logged in as the owner, so tests can set up and check anything.
+    pub pool: db::Pool,
+    /// What requests use, logged in as `application`.
+    pub request_pool: db::Pool,
+    pub mailer: email::Mailer,
+    databases: Vec<String>,
+    uploads: PathBuf,
+}
+
+impl TestApp {
+    pub async fn new() -> TestApp {
+        TestApp::build(false, Limits::default()).await
+    }
+
+    /// Like `new`, with a replica that never catches up. It's a second
+    /// copy of the template, so reads that go to it see an empty database.
+    pub async fn with_replica() -> TestApp {
+        TestApp::build(true, Limits::default()).await
+    }
+
+    pub async fn with_limits(limits: Limits) -> TestApp {
+        TestApp::build(false, limits).await
+    }
+
+    async fn build(replica: bool, limits: Limits) -> TestApp {
+        let admin = connect(&admin_url()).await;
+        let template = template(&admin).await;
+
+        let mut databases = vec![copy_template(&admin, &template).await];
+        if replica {
+            databases.push(copy_template(&admin, &template).await);
+        }
+
+        let database_url = with_database(&admin_url(), &databases[0]);
+        let application_database_url = as_application(&database_url);
+        let pool = db::create_pool(&database_url).unwrap();
+        let request_pool = db::create_pool(&application_database_url).unwrap();
+        let replicas = databases[1..]
+            .iter()
+            .map(|database| {
+                let url = as_application(&with_database(&admin_url(), database));
+                db::create_pool(&url).unwrap()
+            })
+            .collect();
+        let pools = db::Pools::new(request_pool.clone(), replicas);
+        let mailer = email::Mailer::memory("test@localhost");
+        // Nothing listens for changes, pages just won't get live updates.
+        let (user_changes, _) = broadcast::channel(16);
+        // Likewise flags only reload when they're changed through the app.
+        let (_, flag_changes) = broadcast::channel(16);
+        let flags = web_server::feature_flags::FlagCache::spawn(request_pool.clone(), flag_changes);
+        // Named like the database, so tests running at once don't share it.
+        let uploads = std::env::temp_dir().join(&databases[0]);
+        let storage = Arc::new(storage::LocalStorage::new(&uploads));
+
+        let config = Config {
+            database_url,
+            application_database_url,
+            database_pool: Default::default(),
+            database_replica_urls: vec![],
+            migrate_on_startup: false,
+            worker_concurrency: 0,
+            mailer: MailerConfig::Memory,
+            email_from: "test@localhost".to_string(),
+            encryption_keys: db::encryption::Keyring::parse(
+                "test:dGVzdC1rZXktZG8tbm90LXVzZS1pbi1wcm9kdWN0aW8=",
+            )
+            .unwrap(),
+            limits,
+            compress_above: 1024,
+            admin_emails: vec![ADMIN_EMAIL.to_string()],
+            trusted_proxies: Default::default(),
+            telemetry: Default::default(),
+            storage_url: None,
+            file_url_secret: b"test".to_vec(),
+        };
+        let router = web_server::app(
+            config,
+            pools,
+            db::PrivilegedPool(pool.clone()),
+            user_changes,
+            mailer.clone(),
+            storage,
+            flags,
+        );
+
+        TestApp {
+            router,
+            pool,
+            request_pool,
+            mailer,
+            databases,
+            uploads,
+        }
+    }
+
+    pub async fn get(&self, email: &str, path: &str) -> TestResponse {
+        let request = signed_in(email).uri(path).body(Body::empty()).unwrap();
+        self.send(request).await
+    }
+
+    /// Post a form, `body` is already url encoded.
+    pub async fn post_form(&self, email: &str, path: &str, body: &str) -> TestResponse {
+        let request = signed_in(email)
+            .method("POST")
+            .uri(path)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        self.send(request).await
+    }
+
+    /// Upload a file as the `file` field of a multipart form.
+    pub async fn post_file(
+        &self,
+        email: &str,
+        path: &str,
+        file_name: &str,
+        content_type: &str,
+        bytes: &[u8],
+    ) -> TestResponse {
+        let boundary = "nails-test-boundary";
+        let mut body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; \
+            filename=\"{file_name}\"\r\nContent-Type: {content_type}\r\n\r\n"
+        )
+        .into_bytes();
+        body.extend_from_slice(bytes);
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+        let request = signed_in(email)
+            .method("POST")
+            .uri(path)
+            .header(
+                header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={boundary}"),
+            )
+            .body(Body::from(body))
+            .unwrap();
+        self.send(request).await
+    }
+
+    pub async fn send(&self, request: Request<Body>) -> TestResponse {
+        let response = self.router.clone().oneshot(request).await.unwrap();
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let location = response
+            .headers()
+            .get(header::LOCATION)
+            .map(|location| location.to_str().unwrap().to_string());
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        TestResponse {
+            status,
+            headers,
+            location,
+            body: String::from_utf8_lossy(&bytes).into_owned(),
+        }
+    }
+
+    /// Visit `/` as a user, which creates them and their first team, and
+    /// return the team id.
+    pub async fn sign_in(&self, email: &str) -> i32 {
+        let response = self.get(email, "/").await;
+        assert_eq!(response.status, StatusCode::SEE_OTHER);
+        response
+            .location
+            .as_deref()
+            .and_then(|location| location.strip_prefix("/teams/"))
+            .and_then(|id| id.parse().ok())
+            .expect("redirected to a team")
+    }
+}
+
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        // Drop can't be async, so clean up on a runtime of our own.
+        let _ = std::fs::remove_dir_all(&self.uploads);
+        let databases = self.databases.clone();
+        std::thread::spawn(move || {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(async {
+                    let admin = connect(&admin_url()).await;
+                    for database in databases {
+                        let _ = admin
+                            .batch_execute(&format!(
+                                "DROP DATABASE IF EXISTS {database} WITH (FORCE)"
+                            ))
+                            .await;
+                    }
+                });
+        })
+        .join()
+        .unwrap();
+    }
+}
+
+pub fn signed_in(email: &str) -> axum::http::request::Builder {
+    Request::builder()
+        .header("X-Forwarded-User", email)
+        .header("X-Forwarded-Email", email)
+}
+
+pub struct TestResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub location: Option<String>,
+    pub body: String,
+}
+
+impl TestResponse {
+    pub fn html(&self) -> Html {
+        Html::parse_document(&self.body)
+    }
+
+    /// The text of every element matching a CSS selector.
+    pub fn text(&self, selector: &str) -> Vec<String> {
+        let selector = Selector::parse(selector).unwrap();
+        self.html()
+            .select(&selector)
+            .map(|element| element.text().collect::<String>().trim().to_string())
+            .collect()
+    }
+
+    /// An attribute of the first element matching a CSS selector.
+    pub fn attr(&self, selector: &str, attr: &str) -> Option<String> {
+        let selector = Selector::parse(selector).unwrap();
+        self.html()
+            .select(&selector)
+            .next()
+            .and_then(|element| element.value().attr(attr))
+            .map(String::from)
+    }
+}
+
+fn admin_url() -> String {
+    std::env::var("DATABASE_URL").expect("DATABASE_URL not set")
+}
+
+// Swap the database name in a postgres:// url.
+fn with_database(url: &str, database: &str) -> String {
+    let (base, query) = match url.split_once('?') {
+        Some((base, query)) => (base, Some(query)),
+        None => (url, None),
+    };
+    let (server, _) = base.rsplit_once('/').expect("DATABASE_URL has a database");
+    match query {
+        Some(query) => format!("{server}/{database}?{query}"),
+        None => format!("{server}/{database}"),
+    }
+}
+
+// The same server and database, logged in as `application` with whatever
+// password the admin has. Test databases trust local connections.
+fn as_application(url: &str) -> String {
+    let (scheme, rest) = url.split_once("://").expect("DATABASE_URL is a URL");
+    let rest = match rest.split_once('@') {
+        Some((credentials, host)) => match credentials.split_once(':') {
+            Some((_, password)) => format!("application:{password}@{host}"),
+            None => format!("application@{host}"),
+        },
+        None => format!("application@{rest}"),
+    };
+    format!("{scheme}://{rest}")
+}
+
+async fn connect(url: &str) -> tokio_postgres::Client {
+    let (client, connection) = tokio_postgres::connect(url, NoTls).await.unwrap();
+    tokio::spawn(connection);
+    client
+}
+
+/// A fresh database for one test.
+async fn copy_template(admin: &tokio_postgres::Client, template: &str) -> String {
+    let database = format!(
+        "nails_test_{}_{}",
+        std::process::id(),
+        DATABASES.fetch_add(1, Ordering::SeqCst)
+    );
+    admin
+        .batch_execute(&format!("DROP DATABASE IF EXISTS {database} WITH (FORCE)"))
+        .await
+        .unwrap();
+    admin
+        .batch_execute(&format!("CREATE DATABASE {database} TEMPLATE {template}"))
+        .await
+        .unwrap();
+    database
+}
+
+/// Make sure the template exists and return its name. Test binaries run
+/// at the same time, so only one of them builds it.
+async fn template(admin: &tokio_postgres::Client) -> String {
+    let migrations = db::migrations::migrations().unwrap();
+    let mut hasher = DefaultHasher::new();
+    migrations.hash(&mut hasher);
+    let template = format!("nails_test_template_{:x}", hasher.finish());
+
+    admin
+        .execute("SELECT pg_advisory_lock($1)", &[&TEMPLATE_LOCK])
+        .await
+        .unwrap();
+
+    let exists = admin
+        .query_opt("SELECT 1 FROM pg_database WHERE datname = $1", &[&template])
+        .await
+        .unwrap()
+        .is_some();
+    if !exists {
+        // Built under another name so a failed migration can't leave a
+        // half finished template behind.
+        let building = format!("{template}_building");
+        admin
+            .batch_execute(&format!("DROP DATABASE IF EXISTS {building}"))
+            .await
+            .unwrap();
+        admin
+            .batch_execute(&format!("CREATE DATABASE {building}"))
+            .await
+            .unwrap();
+
+        let mut client = connect(&with_database(&admin_url(), &building)).await;
+        db::migrations::up(&mut client, false)
+            .await
+            .unwrap_or_else(|e| panic!("{e}"));
+        drop(client);
+        admin
+            .execute(
+                "SELECT pg_terminate_backend(pid) FROM pg_stat_activity WHERE datname = $1",
+                &[&building],
+            )
+            .await
+            .unwrap();
+
+        admin
+            .batch_execute(&format!("ALTER DATABASE {building} RENAME TO {template}"))
+            .await
+            .unwrap();
+    }
+
+    admin
+        .execute("SELECT pg_advisory_unlock($1)", &[&TEMPLATE_LOCK])
+        .await
+        .unwrap();
+
+    template
+}
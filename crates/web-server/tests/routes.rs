@@ -0,0 +1,800 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use common::{signed_in, TestApp, ADMIN_EMAIL};
+use tower::ServiceExt;
+use web_server::config::Limits;
+
+#[tokio::test]
+async fn root_sends_new_users_to_their_own_team() {
+    let app = TestApp::new().await;
+
+    let team_id = app.sign_in("ian@test.com").await;
+
+    let page = app.get("ian@test.com", &format!("/teams/{team_id}")).await;
+    assert_eq!(page.status, StatusCode::OK);
+    assert_eq!(page.text("title"), ["Users Table"]);
+    assert_eq!(page.text("tbody tr td:nth-child(2)"), ["ian@test.com"]);
+    assert!(page
+        .text("summary span")
+        .contains(&"ian@test.com's team".to_string()));
+
+    // Coming back finds the same team rather than making another.
+    assert_eq!(app.sign_in("ian@test.com").await, team_id);
+}
+
+#[tokio::test]
+async fn settings_are_saved_and_shown_again() {
+    let app = TestApp::new().await;
+    let team_id = app.sign_in("ian@test.com").await;
+    let settings = format!("/teams/{team_id}/settings");
+
+    let page = app.get("ian@test.com", &settings).await;
+    assert_eq!(page.status, StatusCode::OK);
+    assert_eq!(
+        page.attr("select[name=timezone] option[selected]", "value")
+            .as_deref(),
+        Some("UTC")
+    );
+
+    let response = app
+        .post_form(
+            "ian@test.com",
+            &settings,
+            "display_name=Ian&timezone=Europe%2FLondon&theme=Dark",
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::SEE_OTHER);
+    assert_eq!(response.location.as_deref(), Some(settings.as_str()));
+
+    let page = app.get("ian@test.com", &settings).await;
+    assert_eq!(
+        page.attr("input[name=display_name]", "value").as_deref(),
+        Some("Ian")
+    );
+    assert_eq!(
+        page.attr("select[name=timezone] option[selected]", "value")
+            .as_deref(),
+        Some("Europe/London")
+    );
+
+    // Bad input comes back with the errors and nothing saved.
+    let page = app
+        .post_form(
+            "ian@test.com",
+            &settings,
+            "display_name=&timezone=Mars%2FOlympus&theme=Dark",
+        )
+        .await;
+    assert_eq!(page.status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(page.text(".alert li"), ["Please choose a valid timezone"]);
+}
+
+#[tokio::test]
+async fn display_names_are_stored_encrypted() {
+    let app = TestApp::new().await;
+    let team_id = app.sign_in("ian@test.com").await;
+    let response = app
+        .post_form(
+            "ian@test.com",
+            &format!("/teams/{team_id}/settings"),
+            "display_name=Ian&timezone=UTC&theme=Dark",
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::SEE_OTHER);
+
+    let client = app.pool.get().await.unwrap();
+    let stored = client
+        .query_one(
+            "SELECT display_name, encrypted_display_name FROM user_settings",
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(stored.get::<_, Option<String>>(0), None);
+    let encrypted: Vec<u8> = stored.get(1);
+    assert!(!encrypted.windows(3).any(|bytes| bytes == b"Ian"));
+
+    // Nor does the audit log give it away.
+    let diff: serde_json::Value = client
+        .query_one(
+            "SELECT diff FROM audit_events WHERE action = 'user_settings.updated'",
+            &[],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(diff["display_name"]["new"], "[encrypted]");
+    assert!(!diff.to_string().contains("Ian"));
+
+    // It only decrypts for ian, not once it's copied to someone else.
+    let jane_team = app.sign_in("jane@test.com").await;
+    client
+        .execute(
+            "INSERT INTO user_settings (user_id, encrypted_display_name)
+            SELECT id, $1 FROM users WHERE email = 'jane@test.com'",
+            &[&encrypted],
+        )
+        .await
+        .unwrap();
+    let page = app
+        .get("jane@test.com", &format!("/teams/{jane_team}"))
+        .await;
+    assert!(page.body.contains("Unable to decrypt"));
+    assert!(!page.body.contains("Ian"));
+}
+
+#[tokio::test]
+async fn new_user_joins_the_team_and_gets_a_welcome_email() {
+    let app = TestApp::new().await;
+    let team_id = app.sign_in("ian@test.com").await;
+
+    let response = app
+        .post_form(
+            "ian@test.com",
+            &format!("/teams/{team_id}/new_user"),
+            "email=jane%40test.com",
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::SEE_OTHER);
+
+    let page = app.get("ian@test.com", &format!("/teams/{team_id}")).await;
+    assert_eq!(
+        page.text("tbody tr td:nth-child(2)"),
+        ["ian@test.com", "jane@test.com"]
+    );
+
+    let client = app.pool.get().await.unwrap();
+    let queued = client
+        .query_one(
+            "SELECT COUNT(*) FROM jobs WHERE team_id = $1 AND kind = 'welcome_email'",
+            &[&team_id],
+        )
+        .await
+        .unwrap();
+    assert_eq!(queued.get::<_, i64>(0), 1);
+
+    let response = app
+        .post_form(
+            "ian@test.com",
+            &format!("/teams/{team_id}/new_user"),
+            "email=not-an-email",
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn adding_someone_with_an_account_invites_them() {
+    let app = TestApp::new().await;
+    let team_id = app.sign_in("ian@test.com").await;
+    app.sign_in("jane@test.com").await;
+
+    let response = app
+        .post_form(
+            "ian@test.com",
+            &format!("/teams/{team_id}/new_user"),
+            "email=jane%40test.com",
+        )
+        .await;
+    // The same as for a new user, nothing says jane has an account
+    assert_eq!(response.status, StatusCode::SEE_OTHER);
+    assert_eq!(
+        response.location.as_deref(),
+        Some(&*format!("/teams/{team_id}"))
+    );
+
+    let page = app.get("ian@test.com", &format!("/teams/{team_id}")).await;
+    assert_eq!(page.text("tbody tr td:nth-child(2)"), ["ian@test.com"]);
+
+    let client = app.pool.get().await.unwrap();
+    let invited: i64 = client
+        .query_one(
+            "SELECT COUNT(*) FROM invitations WHERE team_id = $1 AND email = 'jane@test.com'",
+            &[&team_id],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(invited, 1);
+    let welcomed: i64 = client
+        .query_one(
+            "SELECT COUNT(*) FROM jobs WHERE team_id = $1 AND kind = 'welcome_email'",
+            &[&team_id],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(welcomed, 0);
+}
+
+#[tokio::test]
+async fn invitees_see_their_invitations() {
+    let app = TestApp::new().await;
+    let team_id = app.sign_in("ian@test.com").await;
+    let jane_team = app.sign_in("jane@test.com").await;
+
+    let response = app
+        .post_form(
+            "ian@test.com",
+            &format!("/teams/{team_id}/invitations"),
+            "email=jane%40test.com&role=Member",
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::SEE_OTHER);
+
+    let page = app
+        .get("jane@test.com", &format!("/teams/{jane_team}/team"))
+        .await;
+    assert_eq!(page.status, StatusCode::OK);
+    assert!(page.text("td").contains(&"ian@test.com's team".to_string()));
+    let accept = page.attr("form[action^='/invitations/']", "action");
+    assert!(accept.is_some_and(|action| action.ends_with("/accept")));
+}
+
+#[tokio::test]
+async fn new_api_keys_are_shown_once_after_a_redirect() {
+    let app = TestApp::new().await;
+    let team_id = app.sign_in("ian@test.com").await;
+    let settings = format!("/teams/{team_id}/settings");
+    let with_cookie = |cookie: &str| {
+        signed_in("ian@test.com")
+            .uri(&settings)
+            .header(header::COOKIE, cookie)
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = app
+        .post_form(
+            "ian@test.com",
+            &format!("/teams/{team_id}/api_keys"),
+            "name=ci&scopes=ViewUsers",
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::SEE_OTHER);
+    assert_eq!(response.location.as_deref(), Some(&*settings));
+    assert!(!response.body.contains("nails_"));
+    let flash = response.headers[header::SET_COOKIE].to_str().unwrap();
+    let flash = flash.split(';').next().unwrap().to_string();
+
+    let page = app.send(with_cookie(&flash)).await;
+    assert_eq!(page.status, StatusCode::OK);
+    assert_eq!(page.headers[header::CACHE_CONTROL], "no-store");
+    let cleared = page.headers[header::SET_COOKIE].to_str().unwrap();
+    assert!(cleared.starts_with("new_api_key=;"));
+    let key = page.text("code.select-all");
+    assert_eq!(key.len(), 1);
+    assert!(key[0].starts_with("nails_"));
+
+    // Once the cookie is gone the key is too.
+    let page = app.get("ian@test.com", &settings).await;
+    assert!(page.text("code.select-all").is_empty());
+
+    // A flash that doesn't decrypt for that key shows nothing.
+    let (key_id, ciphertext) = flash.split_once('.').unwrap();
+    let key_id: i32 = key_id.trim_start_matches("new_api_key=").parse().unwrap();
+    let moved = format!("new_api_key={}.{ciphertext}", key_id + 1);
+    let page = app.send(with_cookie(&moved)).await;
+    assert!(page.text("code.select-all").is_empty());
+    let tampered = format!("{flash}A");
+    let page = app.send(with_cookie(&tampered)).await;
+    assert_eq!(page.status, StatusCode::OK);
+    assert!(page.text("code.select-all").is_empty());
+}
+
+#[tokio::test]
+async fn only_the_teams_dead_jobs_are_retried() {
+    let app = TestApp::new().await;
+    let team_id = app.sign_in("ian@test.com").await;
+    let other_team = app.sign_in("jane@test.com").await;
+
+    let client = app.pool.get().await.unwrap();
+    let mut jobs = vec![];
+    for (team, status) in [
+        (team_id, "Dead"),
+        (team_id, "Pending"),
+        (other_team, "Dead"),
+    ] {
+        let id: i32 = client
+            .query_one(
+                "INSERT INTO jobs (team_id, kind, payload, status)
+                VALUES ($1, 'welcome_email', '{}', $2::TEXT::job_status)
+                RETURNING id",
+                &[&team, &status],
+            )
+            .await
+            .unwrap()
+            .get(0);
+        jobs.push(id);
+    }
+    let retry = |id: i32| format!("/teams/{team_id}/jobs/{id}/retry");
+
+    // Not dead, another team's, or not there at all.
+    for id in [jobs[1], jobs[2], 0] {
+        let response = app.post_form("ian@test.com", &retry(id), "").await;
+        assert_eq!(response.status, StatusCode::NOT_FOUND);
+    }
+    let response = app.post_form("ian@test.com", &retry(jobs[0]), "").await;
+    assert_eq!(response.status, StatusCode::SEE_OTHER);
+
+    let audited: Vec<i32> = client
+        .query(
+            "SELECT target_id FROM audit_events WHERE action = 'job.requeued'",
+            &[],
+        )
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| row.get(0))
+        .collect();
+    assert_eq!(audited, [jobs[0]]);
+}
+
+#[tokio::test]
+async fn oversized_bodies_are_turned_away() {
+    let app = TestApp::new().await;
+    let team_id = app.sign_in("ian@test.com").await;
+
+    let email = format!("{}%40test.com", "a".repeat(300 * 1024));
+    let response = app
+        .post_form(
+            "ian@test.com",
+            &format!("/teams/{team_id}/new_user"),
+            &format!("email={email}"),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn routes_can_have_limits_of_their_own() {
+    let mut limits = Limits::default();
+    limits
+        .route_body_limits
+        .insert("/teams/:team_id/new_user".to_string(), 16);
+    limits
+        .route_body_limits
+        .insert("/teams/:team_id/users/import/preview".to_string(), 16);
+    let app = TestApp::with_limits(limits).await;
+    let team_id = app.sign_in("ian@test.com").await;
+
+    let response = app
+        .post_form(
+            "ian@test.com",
+            &format!("/teams/{team_id}/new_user"),
+            "email=jane%40test.com",
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::PAYLOAD_TOO_LARGE);
+
+    // Uploads can be limited too, not only the group's defaults.
+    let response = app
+        .post_file(
+            "ian@test.com",
+            &format!("/teams/{team_id}/users/import/preview"),
+            "users.csv",
+            "text/csv",
+            b"email\njane@test.com\n",
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+
+    // Everything else keeps the defaults.
+    let response = app
+        .post_form(
+            "ian@test.com",
+            &format!("/teams/{team_id}/invitations"),
+            "email=jane%40test.com&role=Member",
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::SEE_OTHER);
+}
+
+#[tokio::test]
+async fn pages_are_compressed_but_events_are_not() {
+    let app = TestApp::new().await;
+    let team_id = app.sign_in("ian@test.com").await;
+
+    for encoding in ["br", "gzip", "zstd"] {
+        let request = signed_in("ian@test.com")
+            .uri(format!("/teams/{team_id}"))
+            .header(header::ACCEPT_ENCODING, encoding)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.send(request).await;
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(response.headers[header::CONTENT_ENCODING], encoding);
+    }
+
+    // The stream never ends, so only look at the headers.
+    let request = signed_in("ian@test.com")
+        .uri(format!("/teams/{team_id}/users/events"))
+        .header(header::ACCEPT_ENCODING, "gzip")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+}
+
+#[tokio::test]
+async fn pages_are_in_the_users_language() {
+    let app = TestApp::new().await;
+    let team_id = app.sign_in("ian@test.com").await;
+    let german_browser = || {
+        signed_in("ian@test.com")
+            .uri(format!("/teams/{team_id}"))
+            .header(header::ACCEPT_LANGUAGE, "de-DE,de;q=0.9,en;q=0.8")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let page = app.send(german_browser()).await;
+    assert_eq!(page.text("title"), ["Benutzertabelle"]);
+    assert_eq!(page.attr("html", "lang").as_deref(), Some("de"));
+
+    // Choosing a language in settings beats the browser.
+    let settings = format!("/teams/{team_id}/settings");
+    let response = app
+        .post_form(
+            "ian@test.com",
+            &settings,
+            "display_name=&timezone=UTC&theme=System&locale=en",
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::SEE_OTHER);
+    assert!(response.headers[header::SET_COOKIE]
+        .to_str()
+        .unwrap()
+        .starts_with("locale=en;"));
+
+    let page = app.send(german_browser()).await;
+    assert_eq!(page.text("title"), ["Users Table"]);
+
+    let response = app
+        .post_form(
+            "ian@test.com",
+            &settings,
+            "display_name=&timezone=UTC&theme=System&locale=xx",
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        response.text(".alert li"),
+        ["Please choose a valid language"]
+    );
+}
+
+#[tokio::test]
+async fn theme_is_switched_from_any_page() {
+    let app = TestApp::new().await;
+    let team_id = app.sign_in("ian@test.com").await;
+
+    let page = app.get("ian@test.com", &format!("/teams/{team_id}")).await;
+    assert_eq!(page.attr("html", "data-theme"), None);
+
+    let request = signed_in("ian@test.com")
+        .method("POST")
+        .uri(format!("/teams/{team_id}/theme"))
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .header(
+            header::REFERER,
+            format!("http://localhost/teams/{team_id}/jobs"),
+        )
+        .body(Body::from("theme=Dark"))
+        .unwrap();
+    let response = app.send(request).await;
+    assert_eq!(response.status, StatusCode::SEE_OTHER);
+    assert_eq!(
+        response.location.as_deref(),
+        Some(format!("/teams/{team_id}/jobs").as_str())
+    );
+    assert!(response.headers[header::SET_COOKIE]
+        .to_str()
+        .unwrap()
+        .starts_with("theme=Dark;"));
+
+    let page = app.get("ian@test.com", &format!("/teams/{team_id}")).await;
+    assert_eq!(page.attr("html", "data-theme").as_deref(), Some("dark"));
+
+    // Somewhere outside the team isn't somewhere to go back to.
+    let request = signed_in("ian@test.com")
+        .method("POST")
+        .uri(format!("/teams/{team_id}/theme"))
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .header(header::REFERER, "https://elsewhere.test/teams/1")
+        .body(Body::from("theme=Light"))
+        .unwrap();
+    let response = app.send(request).await;
+    assert_eq!(
+        response.location.as_deref(),
+        Some(format!("/teams/{team_id}").as_str())
+    );
+}
+
+#[tokio::test]
+async fn feature_flags_are_only_for_site_admins() {
+    let app = TestApp::new().await;
+    let team_id = app.sign_in(ADMIN_EMAIL).await;
+    let other_team = app.sign_in("ian@test.com").await;
+
+    let response = app
+        .get(
+            "ian@test.com",
+            &format!("/teams/{other_team}/feature_flags"),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::FORBIDDEN);
+    let page = app
+        .get("ian@test.com", &format!("/teams/{other_team}"))
+        .await;
+    assert!(!page.body.contains("/feature_flags"));
+
+    let path = format!("/teams/{team_id}/feature_flags");
+    let response = app
+        .post_form(ADMIN_EMAIL, &path, "name=new-dashboard&description=Charts")
+        .await;
+    assert_eq!(response.status, StatusCode::SEE_OTHER);
+    let response = app
+        .post_form(ADMIN_EMAIL, &path, "name=new-dashboard")
+        .await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+
+    let page = app.get(ADMIN_EMAIL, &path).await;
+    assert_eq!(
+        page.text(".card-title"),
+        ["new-dashboard", "New Feature Flag"]
+    );
+    assert!(page.body.contains("Off for you"));
+
+    // On for the admin's team only, straight away on this server.
+    let client = app.pool.get().await.unwrap();
+    let id: i32 = client
+        .query_one(
+            "SELECT id FROM feature_flags WHERE name = 'new-dashboard'",
+            &[],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    let response = app
+        .post_form(
+            ADMIN_EMAIL,
+            &format!("{path}/{id}"),
+            &format!("rollout_percentage=0&user_ids=&team_ids={team_id}"),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::SEE_OTHER);
+
+    let page = app.get(ADMIN_EMAIL, &path).await;
+    assert!(page.body.contains("On for you"));
+
+    let audited: i64 = client
+        .query_one(
+            "SELECT COUNT(*) FROM audit_events WHERE target_type = 'feature_flag'",
+            &[],
+        )
+        .await
+        .unwrap()
+        .get(0);
+    assert_eq!(audited, 2);
+}
+
+#[tokio::test]
+async fn dev_mailbox_is_only_for_site_admins() {
+    let app = TestApp::new().await;
+    let team_id = app.sign_in("ian@test.com").await;
+    let admin_team = app.sign_in(ADMIN_EMAIL).await;
+
+    let email = email::Email {
+        from: "test@localhost".to_string(),
+        to: "jane@test.com".to_string(),
+        subject: "Secret reset link".to_string(),
+        html: "<p>Hi</p>".to_string(),
+        text: "Hi".to_string(),
+    };
+    app.mailer.send(email).await.unwrap();
+
+    let page = app
+        .get("ian@test.com", &format!("/teams/{team_id}/dev/mailbox"))
+        .await;
+    assert_eq!(page.status, StatusCode::FORBIDDEN);
+    assert!(!page.body.contains("Secret reset link"));
+
+    let page = app
+        .get(ADMIN_EMAIL, &format!("/teams/{admin_team}/dev/mailbox"))
+        .await;
+    assert_eq!(page.status, StatusCode::OK);
+    assert!(page.body.contains("Secret reset link"));
+}
+
+#[tokio::test]
+async fn files_are_uploaded_and_downloaded_with_signed_links() {
+    let app = TestApp::new().await;
+    let team_id = app.sign_in("ian@test.com").await;
+
+    // Avatars have to be images, whatever they're called.
+    let png = b"\x89PNG\r\n\x1a\n not much of an image";
+    let avatar = format!("/teams/{team_id}/avatar");
+    let response = app
+        .post_file("ian@test.com", &avatar, "me.png", "image/png", b"%PDF-1.7")
+        .await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+    let response = app
+        .post_file("ian@test.com", &avatar, "me.png", "image/png", png)
+        .await;
+    assert_eq!(response.status, StatusCode::SEE_OTHER);
+    let too_big = [png.as_slice(), &vec![0; 2 * 1024 * 1024]].concat();
+    let response = app
+        .post_file("ian@test.com", &avatar, "big.png", "image/png", &too_big)
+        .await;
+    assert_eq!(response.status, StatusCode::PAYLOAD_TOO_LARGE);
+
+    let page = app
+        .get("ian@test.com", &format!("/teams/{team_id}/settings"))
+        .await;
+    assert_eq!(
+        page.attr("img[src$='/avatar']", "src"),
+        Some(avatar.clone())
+    );
+    let response = app.get("ian@test.com", &avatar).await;
+    let link = response.location.expect("a signed link");
+    let image = app
+        .send(Request::get(&link).body(Body::empty()).unwrap())
+        .await;
+    assert_eq!(image.status, StatusCode::OK);
+    assert_eq!(image.headers[header::CONTENT_TYPE], "image/png");
+    assert_eq!(image.headers[header::CONTENT_DISPOSITION], "inline");
+    assert_eq!(image.headers["x-content-type-options"], "nosniff");
+
+    // Attachments are listed for the team and always downloaded.
+    let files = format!("/teams/{team_id}/files");
+    let csv = "email\nian@test.com\n";
+    let response = app
+        .post_file(
+            "ian@test.com",
+            &files,
+            "users.csv",
+            "text/csv",
+            csv.as_bytes(),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::SEE_OTHER);
+    let response = app
+        .post_file(
+            "ian@test.com",
+            &files,
+            "tool.exe",
+            "application/x-msdownload",
+            b"MZ\x90\x00",
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+
+    let page = app.get("ian@test.com", &files).await;
+    assert_eq!(page.text("tbody a.link"), ["users.csv"]);
+    let link = page.attr("tbody a.link", "href").unwrap();
+    let download = app
+        .send(Request::get(&link).body(Body::empty()).unwrap())
+        .await;
+    assert_eq!(download.status, StatusCode::OK);
+    assert_eq!(download.body, csv);
+    assert_eq!(
+        download.headers[header::CONTENT_DISPOSITION],
+        "attachment; filename=\"users.csv\""
+    );
+
+    // Changing any part of the link breaks it.
+    let (path, query) = link.split_once('?').unwrap();
+    let expires: u64 = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("expires="))
+        .unwrap()
+        .parse()
+        .unwrap();
+    for tampered in [
+        link.replace(
+            &format!("expires={expires}"),
+            &format!("expires={}", expires + 60),
+        ),
+        link.replace(path, "/files/0"),
+        format!("{link}x"),
+    ] {
+        let response = app
+            .send(Request::get(&tampered).body(Body::empty()).unwrap())
+            .await;
+        assert_eq!(response.status, StatusCode::FORBIDDEN, "{tampered}");
+    }
+
+    // Nobody else sees the team's files.
+    app.sign_in("someone@else.com").await;
+    let response = app.get("someone@else.com", &files).await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn reads_go_to_the_primary_once_the_browser_has_written() {
+    let app = TestApp::with_replica().await;
+    let team_id = app.sign_in("ian@test.com").await;
+    let users = format!("/teams/{team_id}");
+
+    // The replica is behind, it hasn't seen the team yet.
+    let page = app.get("ian@test.com", &users).await;
+    assert_eq!(page.status, StatusCode::OK);
+    assert!(page.text("tbody tr td:nth-child(2)").is_empty());
+
+    let response = app
+        .post_form(
+            "ian@test.com",
+            &format!("/teams/{team_id}/new_user"),
+            "email=jane%40test.com",
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::SEE_OTHER);
+    let cookie = response
+        .headers
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .filter_map(|cookie| cookie.to_str().ok()?.split(';').next())
+        .find(|cookie| cookie.starts_with("wrote="))
+        .expect("remembered the write")
+        .to_string();
+
+    let request = signed_in("ian@test.com")
+        .uri(&users)
+        .header(header::COOKIE, cookie)
+        .body(Body::empty())
+        .unwrap();
+    let page = app.send(request).await;
+    assert_eq!(
+        page.text("tbody tr td:nth-child(2)"),
+        ["ian@test.com", "jane@test.com"]
+    );
+}
+
+#[tokio::test]
+async fn a_first_login_reads_from_the_primary() {
+    let app = TestApp::with_replica().await;
+    let wrote = |response: &common::TestResponse| {
+        response
+            .headers
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .filter_map(|cookie| cookie.to_str().ok()?.split(';').next())
+            .find(|cookie| cookie.starts_with("wrote="))
+            .map(String::from)
+    };
+
+    // The GET that creates the user and their team is a write too.
+    let response = app.get("ian@test.com", "/").await;
+    assert_eq!(response.status, StatusCode::SEE_OTHER);
+    let cookie = wrote(&response).expect("remembered the write");
+    let users = response.location.unwrap();
+
+    let request = signed_in("ian@test.com")
+        .uri(&users)
+        .header(header::COOKIE, cookie)
+        .body(Body::empty())
+        .unwrap();
+    let page = app.send(request).await;
+    assert_eq!(page.text("tbody tr td:nth-child(2)"), ["ian@test.com"]);
+
+    // Coming back changes nothing, and neither does a preview.
+    let response = app.get("ian@test.com", "/").await;
+    assert_eq!(wrote(&response), None);
+    let response = app
+        .post_file(
+            "ian@test.com",
+            &format!("{users}/users/import/preview"),
+            "users.csv",
+            "text/csv",
+            b"email\njane@test.com\n",
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(wrote(&response), None);
+}
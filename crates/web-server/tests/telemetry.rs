@@ -0,0 +1,156 @@
+//! Exports to a stand-in for the OpenTelemetry collector that keeps
+//! whatever it's sent. Telemetry is installed globally, so this is a test
+//! binary of its own.
+
+mod common;
+
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::State,
+    http::StatusCode,
+    routing::post,
+    Router,
+};
+use common::{signed_in, TestApp};
+use opentelemetry_proto::tonic::{
+    collector::{
+        metrics::v1::ExportMetricsServiceRequest, trace::v1::ExportTraceServiceRequest,
+    },
+    trace::v1::{span::SpanKind, Span},
+};
+use prost::Message;
+use web_server::{config::Telemetry, telemetry};
+
+#[derive(Clone, Default)]
+struct Collector {
+    traces: Arc<Mutex<Vec<Bytes>>>,
+    metrics: Arc<Mutex<Vec<Bytes>>>,
+}
+
+impl Collector {
+    async fn start() -> (Collector, SocketAddr) {
+        let collector = Collector::default();
+        let app = Router::new()
+            .route(
+                "/v1/traces",
+                post(|State(collector): State<Collector>, body: Bytes| async move {
+                    collector.traces.lock().unwrap().push(body);
+                }),
+            )
+            .route(
+                "/v1/metrics",
+                post(|State(collector): State<Collector>, body: Bytes| async move {
+                    collector.metrics.lock().unwrap().push(body);
+                }),
+            )
+            .with_state(collector.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        (collector, addr)
+    }
+
+    fn spans(&self) -> Vec<Span> {
+        self.traces
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|body| {
+                ExportTraceServiceRequest::decode(body.clone())
+                    .unwrap()
+                    .resource_spans
+            })
+            .flat_map(|resource| resource.scope_spans)
+            .flat_map(|scope| scope.spans)
+            .collect()
+    }
+
+    fn metric_names(&self) -> Vec<String> {
+        self.metrics
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|body| {
+                ExportMetricsServiceRequest::decode(body.clone())
+                    .unwrap()
+                    .resource_metrics
+            })
+            .flat_map(|resource| resource.scope_metrics)
+            .flat_map(|scope| scope.metrics)
+            .map(|metric| metric.name)
+            .collect()
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+// Flushing blocks until the batch task has exported, which needs another
+// worker thread to run on.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn requests_and_queries_are_exported() {
+    let (collector, addr) = Collector::start().await;
+    let providers = telemetry::init(&Telemetry {
+        otlp_endpoint: Some(format!("http://{addr}")),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let app = TestApp::new().await;
+    let team_id = app.sign_in("ian@test.com").await;
+
+    let trace_id = "4bf92f3577b34da6a3ce929d0e0e4736";
+    let parent_id = "00f067aa0ba902b7";
+    let request = signed_in("ian@test.com")
+        .uri(format!("/teams/{team_id}"))
+        .header("traceparent", format!("00-{trace_id}-{parent_id}-01"))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.send(request).await;
+    assert_eq!(response.status, StatusCode::OK);
+
+    tokio::task::spawn_blocking(move || providers.shutdown())
+        .await
+        .unwrap();
+
+    let spans = collector.spans();
+    let server = spans
+        .iter()
+        .find(|span| hex(&span.trace_id) == trace_id && span.kind == SpanKind::Server as i32)
+        .expect("a server span in the caller's trace");
+    assert_eq!(hex(&server.parent_span_id), parent_id);
+    assert_eq!(server.name, "GET");
+
+    // The handler's checkouts and queries hang off the request.
+    let children: Vec<&Span> = spans
+        .iter()
+        .filter(|span| span.parent_span_id == server.span_id)
+        .collect();
+    assert!(children.iter().any(|span| span.name == "pool.checkout"));
+    let query = children
+        .iter()
+        .find(|span| span.name == "SELECT")
+        .expect("a query span");
+    assert_eq!(query.kind, SpanKind::Client as i32);
+    assert!(query
+        .attributes
+        .iter()
+        .any(|attribute| attribute.key == "db.statement"));
+
+    let metrics = collector.metric_names();
+    for name in [
+        "http.server.request.duration",
+        "db.client.connection.wait_time",
+        "db.client.operation.duration",
+    ] {
+        assert!(metrics.iter().any(|metric| metric == name), "{name} missing");
+    }
+}
@@ -0,0 +1,116 @@
+use crate::{
+    audit::RequestMeta,
+    cookies,
+    errors::CustomError,
+    settings::{decrypt_display_name, encrypt_display_name, redact_display_name},
+    teams::CurrentTeam,
+};
+use axum::{
+    http::{
+        header::{REFERER, SET_COOKIE},
+        HeaderMap, StatusCode, Uri,
+    },
+    response::{IntoResponse, Redirect, Response},
+    Extension,
+};
+use axum_extra::extract::Form;
+use db::Theme;
+use serde::Deserialize;
+
+/// The theme for pages that don't know who the user is, signed in pages
+/// use their settings.
+const THEME_COOKIE: &str = "theme";
+
+pub fn parse(theme: &str) -> Option<Theme> {
+    match theme {
+        "System" => Some(Theme::System),
+        "Light" => Some(Theme::Light),
+        "Dark" => Some(Theme::Dark),
+        _ => None,
+    }
+}
+
+pub fn from_headers(headers: &HeaderMap) -> Theme {
+    cookies::get(headers, THEME_COOKIE)
+        .and_then(parse)
+        .unwrap_or(Theme::System)
+}
+
+pub fn set_cookie(theme: Theme) -> String {
+    cookies::set(THEME_COOKIE, Some(&format!("{theme:?}")))
+}
+
+#[derive(Deserialize)]
+pub struct ThemeForm {
+    theme: String,
+}
+
+// The switcher in the header. Only the theme changes, everything else in
+// the user's settings is saved as it was.
+pub async fn action(
+    Extension(pool): Extension<db::Pool>,
+    Extension(keyring): Extension<db::encryption::Keyring>,
+    team: CurrentTeam,
+    meta: RequestMeta,
+    headers: HeaderMap,
+    Form(form): Form<ThemeForm>,
+) -> Result<Response, CustomError> {
+    let Some(theme) = parse(&form.theme) else {
+        return Ok((StatusCode::BAD_REQUEST, "Bad request").into_response());
+    };
+    let tenant = team.tenant();
+    let settings = team.settings;
+
+    let mut client = pool.get().await?;
+    let transaction = db::rls::transaction(&mut client, tenant).await?;
+
+    db::queries::user_settings::upsert_user_settings()
+        .bind(
+            &transaction,
+            &settings.user_id,
+            &encrypt_display_name(&keyring, settings.user_id, settings.display_name.as_deref()),
+            &settings.timezone.as_str(),
+            &theme,
+            &settings.notify_product_updates,
+            &settings.notify_security_alerts,
+            &settings.locale.as_deref(),
+        )
+        .await?;
+
+    let updated = db::queries::user_settings::get_user_settings()
+        .bind(&transaction, &settings.user_id)
+        .one()
+        .await?;
+    let updated = decrypt_display_name(&keyring, updated)?;
+    db::audit::record(
+        &transaction,
+        db::audit::AuditEvent {
+            team_id: Some(team.id),
+            actor_id: Some(settings.user_id),
+            action: "user_settings.updated",
+            target_type: "user",
+            target_id: Some(settings.user_id),
+            diff: redact_display_name(db::audit::diff(Some(&settings), Some(&updated))),
+            ip_address: meta.ip_address,
+            user_agent: meta.user_agent.as_deref(),
+        },
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    // Back to the page the switcher was on, as long as it's in this team.
+    let team_path = format!("/teams/{}", team.id);
+    let back = headers
+        .get(REFERER)
+        .and_then(|referer| referer.to_str().ok())
+        .and_then(|referer| referer.parse::<Uri>().ok())
+        .and_then(|uri| uri.path_and_query().map(|path| path.to_string()))
+        .filter(|path| {
+            path.strip_prefix(&team_path)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(['/', '?']))
+        })
+        .unwrap_or(team_path);
+
+    Ok(([(SET_COOKIE, set_cookie(theme))], Redirect::to(&back)).into_response())
+}
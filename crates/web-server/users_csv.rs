@@ -0,0 +1,282 @@
+use std::io;
+
+use crate::{
+    audit::RequestMeta,
+    authorization::{Authorize, CreateUsers, ViewUsers},
+    errors::CustomError,
+    replicas::ReadPool,
+    root::{SignUp, UserFilter},
+};
+use axum::{
+    body::{Body, Bytes},
+    extract::{Multipart, Query},
+    http::{
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+        StatusCode,
+    },
+    response::{Html, IntoResponse, Redirect, Response},
+    Extension,
+};
+use axum_extra::extract::Form;
+use futures::StreamExt;
+use serde::Deserialize;
+use time::format_description::well_known::Rfc3339;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use validator::Validate;
+use web_pages::{
+    i18n::Locale,
+    root::{self, ImportError},
+};
+
+// Rows per chunk of the export.
+const EXPORT_CHUNK: usize = 500;
+
+// Rows go out as they come back from Postgres, so the export never holds
+// more than a chunk in memory however big the team is.
+pub async fn export(
+    Authorize(team, _): Authorize<ViewUsers>,
+    ReadPool(pool): ReadPool,
+    Query(filter): Query<UserFilter>,
+) -> Result<Response, CustomError> {
+    let mut client = pool.get().await?;
+    let tenant = team.tenant();
+    let email = filter.email();
+
+    let (sender, receiver) = mpsc::channel::<Result<Bytes, io::Error>>(4);
+    tokio::spawn(async move {
+        let export = async {
+            let transaction = db::rls::transaction(&mut client, tenant).await?;
+            let email = email.as_deref();
+            let mut query = db::queries::users::search_team_users();
+            let users = query
+                .bind(&transaction, &tenant.team_id, &email)
+                .iter()
+                .await?;
+            futures::pin_mut!(users);
+
+            let mut writer = csv::Writer::from_writer(vec![]);
+            writer.write_record(["id", "email", "created_at"])?;
+            let mut rows = 0;
+            while let Some(user) = users.next().await {
+                let user = user?;
+                let created_at = user.created_at.format(&Rfc3339)?;
+                writer.write_record([&user.id.to_string(), &user.email, &created_at])?;
+                rows += 1;
+                if rows % EXPORT_CHUNK == 0 {
+                    let chunk = std::mem::replace(&mut writer, csv::Writer::from_writer(vec![]))
+                        .into_inner()?;
+                    if sender.send(Ok(chunk.into())).await.is_err() {
+                        // The browser went away
+                        return Ok(());
+                    }
+                }
+            }
+            let chunk = writer.into_inner()?;
+            let _ = sender.send(Ok(chunk.into())).await;
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+        };
+        if let Err(e) = export.await {
+            eprintln!("Unable to export users: {e}");
+            // Cut the download short rather than pass off a partial file.
+            let _ = sender.send(Err(io::Error::other(e.to_string()))).await;
+        }
+    });
+
+    Ok((
+        [
+            (CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (CONTENT_DISPOSITION, "attachment; filename=\"users.csv\""),
+        ],
+        Body::from_stream(ReceiverStream::new(receiver)),
+    )
+        .into_response())
+}
+
+// Check every row against the same rules as the Add User form, and show
+// what's wrong before anything is written.
+pub async fn preview_action(
+    Authorize(team, _): Authorize<CreateUsers>,
+    mut multipart: Multipart,
+) -> Result<Response, CustomError> {
+    let mut file = None;
+    while let Ok(Some(field)) = multipart.next_field().await {
+        if field.name() == Some("file") {
+            file = field.bytes().await.ok();
+        }
+    }
+    let Some(file) = file else {
+        return Ok((StatusCode::BAD_REQUEST, "Bad request").into_response());
+    };
+
+    let (emails, errors) = validate_csv(&file, team.locale);
+
+    let html = root::import_preview(emails, errors, team.locale, team.settings, team.teams);
+
+    Ok(Html(html).into_response())
+}
+
+fn validate_csv(file: &[u8], locale: Locale) -> (Vec<String>, Vec<ImportError>) {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(file);
+
+    let mut emails = vec![];
+    let mut errors = vec![];
+
+    let headers = match reader.headers() {
+        Ok(headers) if headers.iter().any(|header| header == "email") => headers.clone(),
+        _ => {
+            errors.push(ImportError {
+                line: 1,
+                message: locale.t("users-import-no-email-column"),
+            });
+            return (emails, errors);
+        }
+    };
+
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map(|p| p.line()).unwrap_or_default();
+                errors.push(ImportError {
+                    line,
+                    message: locale.t("users-import-unreadable"),
+                });
+                continue;
+            }
+        };
+        let line = record.position().map(|p| p.line()).unwrap_or_default();
+        let sign_up = match record.deserialize::<SignUp>(Some(&headers)) {
+            Ok(sign_up) => sign_up,
+            Err(_) => {
+                errors.push(ImportError {
+                    line,
+                    message: locale.t("users-import-missing-email"),
+                });
+                continue;
+            }
+        };
+        match sign_up.validate() {
+            Ok(()) => emails.push(sign_up.email),
+            Err(_) => errors.push(ImportError {
+                line,
+                message: locale.t_args(
+                    "users-import-invalid-email",
+                    &[("email", sign_up.email.as_str().into())],
+                ),
+            }),
+        }
+    }
+
+    (emails, errors)
+}
+
+#[derive(Deserialize)]
+pub struct ConfirmImport {
+    // One email per line, from the preview
+    emails: String,
+}
+
+pub async fn import_action(
+    Extension(db::PrivilegedPool(pool)): Extension<db::PrivilegedPool>,
+    Authorize(team, _): Authorize<CreateUsers>,
+    meta: RequestMeta,
+    Form(form): Form<ConfirmImport>,
+) -> Result<Response, CustomError> {
+    let emails: Vec<String> = form
+        .emails
+        .lines()
+        .map(str::trim)
+        .filter(|email| !email.is_empty())
+        .map(String::from)
+        .collect();
+
+    // The preview only passes valid rows, but check again.
+    let all_valid = emails.iter().all(|email| {
+        SignUp {
+            email: email.clone(),
+        }
+        .validate()
+        .is_ok()
+    });
+    if emails.is_empty() || !all_valid {
+        return Ok((StatusCode::BAD_REQUEST, "Bad request").into_response());
+    }
+
+    let mut client = pool.get().await?;
+    let transaction = client.transaction().await?;
+
+    let imported =
+        db::import::import_users(&transaction, team.id, team.settings.user_id, &emails).await?;
+
+    db::rls::set_tenant(&transaction, team.tenant()).await?;
+
+    db::audit::record(
+        &transaction,
+        db::audit::AuditEvent {
+            team_id: Some(team.id),
+            actor_id: Some(team.settings.user_id),
+            action: "users.imported",
+            target_type: "user",
+            diff: serde_json::json!({
+                "rows": { "old": null, "new": emails.len() },
+                "created": { "old": null, "new": imported.created.len() },
+                "invited": { "old": null, "new": imported.invited.len() },
+            }),
+            ip_address: meta.ip_address,
+            user_agent: meta.user_agent.as_deref(),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    for user in imported.created {
+        db::jobs::enqueue(
+            &transaction,
+            Some(team.id),
+            &db::jobs::WelcomeEmail {
+                user_id: user.id,
+                email: user.email,
+            },
+        )
+        .await?;
+    }
+
+    transaction.commit().await?;
+
+    Ok(Redirect::to(&format!("/teams/{}", team.id)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_rows_are_checked_like_the_sign_up_form() {
+        let file = "id,email\n1,ian@test.com\n2,not-an-email\n3\n4, jane@test.com \n";
+
+        let (emails, errors) = validate_csv(file.as_bytes(), Locale::En);
+
+        assert_eq!(emails, ["ian@test.com", "jane@test.com"]);
+        assert_eq!(
+            errors.iter().map(|error| error.line).collect::<Vec<_>>(),
+            [3, 4]
+        );
+        assert_eq!(
+            errors[0].message,
+            "not-an-email isn't a valid email address"
+        );
+        assert_eq!(errors[1].message, "The line has no email");
+
+        let (emails, errors) = validate_csv(b"name\nIan\n", Locale::De);
+        assert!(emails.is_empty());
+        assert_eq!(errors[0].line, 1);
+        assert_eq!(
+            errors[0].message,
+            "Die erste Zeile braucht eine Spalte email"
+        );
+    }
+}
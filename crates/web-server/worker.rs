@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use db::{
+    jobs::{self, Job, WelcomeEmail},
+    queries::jobs::ClaimedJob,
+};
+
+use email::{templates, Mailer};
+
+use crate::errors::CustomError;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+// A job still Running after this long belonged to a worker that died.
+const STALE_AFTER_SECONDS: f64 = 15.0 * 60.0;
+// Jobs are given up on well before they'd look stale, otherwise a slow one
+// would be picked up by a second worker and run twice.
+const JOB_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+const BASE_RETRY_SECONDS: f64 = 10.0;
+const MAX_RETRY_SECONDS: f64 = 60.0 * 60.0;
+
+/// Run jobs from the `jobs` table alongside the web server.
+pub fn spawn(pool: db::Pool, mailer: Mailer, concurrency: usize) {
+    for _ in 0..concurrency {
+        let pool = pool.clone();
+        let mailer = mailer.clone();
+        tokio::spawn(async move {
+            loop {
+                match run_next(&pool, &mailer).await {
+                    Ok(true) => {}
+                    Ok(false) => tokio::time::sleep(POLL_INTERVAL).await,
+                    Err(e) => {
+                        eprintln!("Job worker error: {e}");
+                        tokio::time::sleep(POLL_INTERVAL).await
+                    }
+                }
+            }
+        });
+    }
+}
+
+// Returns false when there was nothing to do.
+async fn run_next(pool: &db::Pool, mailer: &Mailer) -> Result<bool, CustomError> {
+    let client = pool.get().await?;
+
+    db::queries::jobs::release_stale_jobs()
+        .bind(&client, &STALE_AFTER_SECONDS)
+        .await?;
+
+    let Some(job) = db::queries::jobs::claim_job().bind(&client).opt().await? else {
+        return Ok(false);
+    };
+
+    let result = tokio::time::timeout(JOB_TIMEOUT, run(&job, mailer))
+        .await
+        .unwrap_or_else(|_| Err(format!("Timed out after {}s", JOB_TIMEOUT.as_secs())));
+
+    match result {
+        Ok(()) => {
+            db::queries::jobs::complete_job()
+                .bind(&client, &job.id)
+                .await?;
+        }
+        Err(error) if job.attempts >= job.max_attempts => {
+            eprintln!("Job {} ({}) is dead: {error}", job.id, job.kind);
+            db::queries::jobs::dead_letter_job()
+                .bind(&client, &error.as_str(), &job.id)
+                .await?;
+        }
+        Err(error) => {
+            db::queries::jobs::retry_job()
+                .bind(&client, &error.as_str(), &backoff(job.attempts), &job.id)
+                .await?;
+        }
+    }
+
+    Ok(true)
+}
+
+// 10s, 20s, 40s... up to an hour between attempts.
+fn backoff(attempts: i32) -> f64 {
+    let exponent = attempts.saturating_sub(1).min(16);
+    (BASE_RETRY_SECONDS * 2f64.powi(exponent)).min(MAX_RETRY_SECONDS)
+}
+
+async fn run(job: &ClaimedJob, mailer: &Mailer) -> Result<(), String> {
+    match job.kind.as_str() {
+        WelcomeEmail::KIND => {
+            let welcome: WelcomeEmail = jobs::payload(job).map_err(|e| e.to_string())?;
+            let email = templates::welcome(mailer, &welcome.email);
+            mailer.send(email).await.map_err(|e| e.to_string())
+        }
+        kind => Err(format!("Unknown job kind {kind}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_an_hour() {
+        assert_eq!(backoff(1), 10.0);
+        assert_eq!(backoff(2), 20.0);
+        assert_eq!(backoff(3), 40.0);
+        assert_eq!(backoff(20), MAX_RETRY_SECONDS);
+    }
+
+    #[test]
+    fn jobs_time_out_well_before_they_go_stale() {
+        assert!(JOB_TIMEOUT.as_secs_f64() * 2.0 <= STALE_AFTER_SECONDS);
+    }
+}